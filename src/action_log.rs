@@ -0,0 +1,275 @@
+// Action log: every ArenaCommand each robot issues, grouped by turn and chained
+// into a hash, plus a hash of the final match state. Verification first checks
+// the chain, so an edited command shows up in the turn it was changed in, then
+// replays the logged commands into the arena (see `Game::verify_action_log`):
+// because the engine is deterministic, they must lead to the final state hash.
+//
+// Driving isn't an ArenaCommand, so the robots' programs still run during a
+// replay to move them; only what they ask of the arena comes from the log.
+// Matches are only reproducible when their inputs are: robots that read
+// @rand, or randomly placed obstacles, can only be re-verified with the same
+// --seed.
+
+use crate::arena::Arena;
+use crate::robot::{Robot, RobotStatus};
+use crate::types::ArenaCommand;
+use std::fmt::Write;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+// 64-bit FNV-1a, continuing from `hash`. Stable across platforms and Rust
+// versions, unlike std's DefaultHasher.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Hash of the state that decides a match: each robot's position, health and
+/// status, and every projectile in flight
+pub fn state_hash(robots: &[Robot], arena: &Arena) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for robot in robots {
+        let status = match robot.status {
+            RobotStatus::Idle => 0u8,
+            RobotStatus::Active => 1,
+            RobotStatus::Destroyed => 2,
+            RobotStatus::Stunned(_) => 3,
+        };
+        hash = fnv1a(hash, &robot.id.to_le_bytes());
+        hash = fnv1a(hash, &robot.position.x.to_bits().to_le_bytes());
+        hash = fnv1a(hash, &robot.position.y.to_bits().to_le_bytes());
+        hash = fnv1a(hash, &robot.health.to_bits().to_le_bytes());
+        hash = fnv1a(hash, &[status]);
+    }
+    for projectile in &arena.projectiles {
+        hash = fnv1a(hash, &projectile.source_robot.to_le_bytes());
+        hash = fnv1a(hash, &projectile.position.x.to_bits().to_le_bytes());
+        hash = fnv1a(hash, &projectile.position.y.to_bits().to_le_bytes());
+    }
+    hash
+}
+
+// One command as issued by a robot
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedCommand {
+    pub turn: u32,
+    pub cycle: u32,
+    pub robot_id: u32,
+    pub command: ArenaCommand,
+}
+
+/// Commands issued during a match with a running hash per turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionLog {
+    pub commands: Vec<LoggedCommand>,
+    pub turn_hashes: Vec<(u32, u64)>, // Chained hash at the end of each turn
+    pub final_state_hash: Option<u64>,
+    hash: u64, // Running hash over every command so far
+}
+
+impl Default for ActionLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionLog {
+    pub fn new() -> Self {
+        ActionLog {
+            commands: Vec::new(),
+            turn_hashes: Vec::new(),
+            final_state_hash: None,
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    /// Adds a command issued by `robot_id` to the running hash
+    pub fn record(&mut self, turn: u32, cycle: u32, robot_id: u32, command: &ArenaCommand) {
+        let entry = LoggedCommand {
+            turn,
+            cycle,
+            robot_id,
+            command: command.clone(),
+        };
+        self.hash = fnv1a(self.hash, entry.to_line().as_bytes());
+        self.commands.push(entry);
+    }
+
+    /// Seals `turn`, recording the running hash as its checkpoint
+    pub fn end_turn(&mut self, turn: u32) {
+        self.hash = fnv1a(self.hash, &turn.to_le_bytes());
+        self.turn_hashes.push((turn, self.hash));
+    }
+
+    /// Records the hash of the state the match ended in
+    pub fn finish(&mut self, robots: &[Robot], arena: &Arena) {
+        self.final_state_hash = Some(state_hash(robots, arena));
+    }
+
+    /// Recomputes the hash chain from the logged commands, describing the first
+    /// turn whose checkpoint doesn't match (a command was added, dropped or edited)
+    pub fn check_hashes(&self) -> Result<(), String> {
+        let mut rehashed = ActionLog::new();
+        let mut commands = self.commands.iter().peekable();
+        for (turn, hash) in &self.turn_hashes {
+            while let Some(command) = commands.next_if(|c| c.turn <= *turn) {
+                rehashed.record(
+                    command.turn,
+                    command.cycle,
+                    command.robot_id,
+                    &command.command,
+                );
+            }
+            rehashed.end_turn(*turn);
+            if rehashed.hash != *hash {
+                return Err(format!("commands differ in turn {}", turn));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the state a replay of this log ended in against the recorded final state
+    pub fn check_final_state(&self, robots: &[Robot], arena: &Arena) -> Result<(), String> {
+        match self.final_state_hash {
+            Some(hash) if hash == state_hash(robots, arena) => Ok(()),
+            Some(_) => Err("final state differs".to_string()),
+            None => Err("log has no final state hash".to_string()),
+        }
+    }
+
+    /// Serializes the log: one `cmd` line per command, a `turn` line with the
+    /// hash after each turn and a closing `final` state hash line
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let mut commands = self.commands.iter().peekable();
+        for (turn, hash) in &self.turn_hashes {
+            while let Some(command) = commands.next_if(|c| c.turn <= *turn) {
+                let _ = writeln!(text, "{}", command.to_line());
+            }
+            let _ = writeln!(text, "turn {} {:016x}", turn, hash);
+        }
+        for command in commands {
+            let _ = writeln!(text, "{}", command.to_line());
+        }
+        if let Some(hash) = self.final_state_hash {
+            let _ = writeln!(text, "final {:016x}", hash);
+        }
+        text
+    }
+
+    /// Parses a log written by `to_text`
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut log = ActionLog::new();
+        for (index, line) in text.lines().enumerate() {
+            let invalid = || format!("Invalid action log entry on line {}", index + 1);
+            let mut fields = line.splitn(5, ' ');
+            match fields.next() {
+                Some("cmd") => {
+                    let mut number = || fields.next().and_then(|f| f.parse::<u32>().ok());
+                    let (Some(turn), Some(cycle), Some(robot_id)) = (number(), number(), number())
+                    else {
+                        return Err(invalid());
+                    };
+                    let command = fields
+                        .next()
+                        .and_then(|f| serde_json::from_str(f).ok())
+                        .ok_or_else(invalid)?;
+                    log.commands.push(LoggedCommand {
+                        turn,
+                        cycle,
+                        robot_id,
+                        command,
+                    });
+                }
+                Some("turn") => {
+                    let turn = fields.next().and_then(|f| f.parse().ok());
+                    let hash = fields.next().and_then(|f| u64::from_str_radix(f, 16).ok());
+                    let (Some(turn), Some(hash)) = (turn, hash) else {
+                        return Err(invalid());
+                    };
+                    log.turn_hashes.push((turn, hash));
+                }
+                Some("final") => {
+                    let hash = fields.next().and_then(|f| u64::from_str_radix(f, 16).ok());
+                    log.final_state_hash = Some(hash.ok_or_else(invalid)?);
+                }
+                Some("") | None => {}
+                Some(_) => return Err(invalid()),
+            }
+        }
+        Ok(log)
+    }
+}
+
+impl LoggedCommand {
+    // The command is written as JSON so a log can be parsed back and replayed
+    fn to_line(&self) -> String {
+        format!(
+            "cmd {} {} {} {}",
+            self.turn,
+            self.cycle,
+            self.robot_id,
+            serde_json::to_string(&self.command).expect("arena commands always serialize")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Point;
+
+    fn detonate(source_robot: u32) -> ArenaCommand {
+        ArenaCommand::Detonate {
+            source_robot,
+            source_team: 0,
+            position: Point { x: 0.5, y: 0.5 },
+            power: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut log = ActionLog::new();
+        log.record(1, 3, 2, &detonate(2));
+        log.end_turn(1);
+        log.end_turn(2);
+        log.final_state_hash = Some(0xdead_beef);
+
+        let parsed = ActionLog::from_text(&log.to_text()).unwrap();
+        assert_eq!(parsed.commands, log.commands);
+        assert_eq!(parsed.turn_hashes, log.turn_hashes);
+        assert_eq!(parsed.final_state_hash, log.final_state_hash);
+        assert!(parsed.check_hashes().is_ok());
+        assert!(ActionLog::from_text("turn x 12").is_err());
+        assert!(ActionLog::from_text("cmd 1 0 1 {\"Bogus\":1}").is_err());
+    }
+
+    #[test]
+    fn test_check_hashes_reports_first_edited_turn() {
+        let mut log = ActionLog::new();
+        for turn in 1..=3 {
+            log.record(turn, 0, 1, &detonate(1));
+            log.end_turn(turn);
+        }
+        assert_eq!(log.check_hashes(), Ok(()));
+
+        // Editing a command without recomputing the chain breaks its turn's checkpoint
+        let mut edited = log.clone();
+        edited.commands[1].robot_id = 2;
+        assert_eq!(
+            edited.check_hashes(),
+            Err("commands differ in turn 2".to_string())
+        );
+        let mut dropped = log.clone();
+        dropped.commands.remove(2);
+        assert_eq!(
+            dropped.check_hashes(),
+            Err("commands differ in turn 3".to_string())
+        );
+    }
+}
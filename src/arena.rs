@@ -1,17 +1,75 @@
 use crate::audio::AudioManager;
 use crate::config;
 use crate::config::*;
+use crate::events::Event;
 use crate::particles::ParticleSystem;
 use crate::robot::{Robot, RobotStatus};
 use crate::types::*;
 use ::rand::prelude::*;
 use macroquad::prelude::*;
 use macroquad::prelude::{ORANGE, SKYBLUE, Vec2, YELLOW};
+use std::collections::HashMap;
 
 // Represents an obstacle in the arena
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Obstacle {
-    pub position: Point, // Center position in coordinate units
+    pub position: Point,     // Center position in coordinate units
+    pub health: Option<f64>, // Remaining health of a destructible obstacle (None = indestructible)
+    pub rubble: bool,        // Left behind by a destroyed obstacle; crumbles without more rubble
+}
+
+// Uniform spatial hash bucketing live robots by the grid cell they stand in,
+// so a point only has to be tested against robots in the surrounding cells
+#[derive(Debug)]
+pub struct RobotGrid {
+    cell_size: f64,                         // Side of one cell in coordinate units
+    reach: i64, // Cells to search around a point to cover the largest robot
+    cells: HashMap<(i64, i64), Vec<usize>>, // Cell -> indices into the robot slice
+}
+
+impl RobotGrid {
+    pub fn new(robots: &[Robot], cell_size: f64) -> Self {
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        let mut max_radius: f64 = 0.0;
+        for (index, robot) in robots.iter().enumerate() {
+            if robot.status == RobotStatus::Destroyed {
+                continue;
+            }
+            max_radius = max_radius.max(robot.radius);
+            cells
+                .entry(Self::cell(robot.position, cell_size))
+                .or_default()
+                .push(index);
+        }
+        RobotGrid {
+            cell_size,
+            reach: (max_radius / cell_size).ceil() as i64,
+            cells,
+        }
+    }
+
+    // Same indexing as Arena::world_to_grid, without clamping at zero
+    fn cell(point: Point, cell_size: f64) -> (i64, i64) {
+        (
+            (point.x / cell_size).floor() as i64,
+            (point.y / cell_size).floor() as i64,
+        )
+    }
+
+    /// Fills `out` with the indices of every robot that could overlap `point`,
+    /// in ascending order so callers see robots in the same order as the slice
+    pub fn nearby(&self, point: Point, out: &mut Vec<usize>) {
+        out.clear();
+        let (cell_x, cell_y) = Self::cell(point, self.cell_size);
+        for x in cell_x - self.reach..=cell_x + self.reach {
+            for y in cell_y - self.reach..=cell_y + self.reach {
+                if let Some(indices) = self.cells.get(&(x, y)) {
+                    out.extend_from_slice(indices);
+                }
+            }
+        }
+        out.sort_unstable();
+    }
 }
 
 // Represents the game arena
@@ -23,7 +81,15 @@ pub struct Arena {
     pub grid_height: u32, // Height in grid units
     pub unit_size: f64,   // Size of one grid unit in coordinate units
     pub obstacles: Vec<Obstacle>,
+    pub pickups: Vec<Pickup>, // Health and energy pickups waiting to be collected
     pub projectiles: Vec<Projectile>,
+    pub mines: Vec<Mine>,               // Proximity mines waiting to go off
+    pub beams: Vec<Beam>,               // Beams fired this cycle, for rendering
+    pub damage_multiplier: f64,         // Scales all damage dealt (raised during overtime)
+    pub obstacle_revision: u32, // Bumped whenever the layout changes, invalidating cached distances
+    pub crumbled_obstacles: Vec<Point>, // Where obstacles were destroyed this cycle, for rubble
+    pub destructible_obstacles: bool, // Set by make_obstacles_destructible; wreckage added later gets health too
+    contacts: Vec<(u32, u32)>,        // Robot pairs that overlapped last cycle (lower id first)
 }
 
 impl Arena {
@@ -38,18 +104,32 @@ impl Arena {
             grid_height: ARENA_HEIGHT_UNITS,
             unit_size: UNIT_SIZE,
             obstacles: Vec::new(),
+            pickups: Vec::new(),
             projectiles: Vec::new(),
+            mines: Vec::new(),
+            beams: Vec::new(),
+            damage_multiplier: 1.0,
+            obstacle_revision: 0,
+            crumbled_obstacles: Vec::new(),
+            destructible_obstacles: false,
+            contacts: Vec::new(),
         }
     }
 
+    /// Invalidates every robot's cached wall/obstacle distances. Called by the methods
+    /// that change the layout; call it after editing `obstacles` directly.
+    pub fn invalidate_distance_cache(&mut self) {
+        self.obstacle_revision = self.obstacle_revision.wrapping_add(1);
+    }
+
     // Places obstacles randomly based on configured density
-    pub fn place_obstacles(&mut self) {
-        let mut rng = thread_rng();
+    pub fn place_obstacles(&mut self, rng: &mut impl Rng) {
         let total_cells = self.grid_width * self.grid_height;
         let num_obstacles = (total_cells as f32 * OBSTACLE_DENSITY).floor() as u32;
 
         log::info!("Placing {} obstacles...", num_obstacles);
         self.obstacles.clear(); // Clear existing obstacles
+        self.invalidate_distance_cache();
 
         // Keep track of occupied grid cells to avoid duplicates
         let mut occupied_cells = std::collections::HashSet::new();
@@ -64,7 +144,11 @@ impl Arena {
 
                 if occupied_cells.insert((grid_x, grid_y)) {
                     let position = self.grid_to_world(grid_x, grid_y);
-                    self.obstacles.push(Obstacle { position });
+                    self.obstacles.push(Obstacle {
+                        position,
+                        health: None,
+                        rubble: false,
+                    });
                     break; // Found an empty cell, move to next obstacle
                 }
                 // If cell is already occupied, loop again
@@ -73,11 +157,161 @@ impl Arena {
         log::info!("Obstacles placed.");
     }
 
+    /// Drops up to RUBBLE_PIECES rubble obstacles with RUBBLE_HEALTH into free
+    /// cells next to (grid_x, grid_y), picked with `rng` so a seeded match drops
+    /// the same rubble. Cells holding an obstacle or overlapped by a live robot
+    /// are skipped. Returns the number of pieces placed.
+    pub fn drop_rubble(
+        &mut self,
+        grid_x: u32,
+        grid_y: u32,
+        robots: &[Robot],
+        rng: &mut impl Rng,
+    ) -> usize {
+        let mut free_cells = Vec::new();
+        for dy in -1i64..=1 {
+            for dx in -1i64..=1 {
+                let (x, y) = (grid_x as i64 + dx, grid_y as i64 + dy);
+                if (dx, dy) == (0, 0)
+                    || x < 0
+                    || y < 0
+                    || x >= self.grid_width as i64
+                    || y >= self.grid_height as i64
+                {
+                    continue;
+                }
+                let cell = (x as u32, y as u32);
+                let center = self.grid_to_world(cell.0, cell.1);
+                // A robot blocks every cell its collision circle reaches into
+                let occupied = self
+                    .obstacles
+                    .iter()
+                    .any(|o| self.world_to_grid(o.position) == cell)
+                    || robots.iter().any(|r| {
+                        r.status != RobotStatus::Destroyed
+                            && self.circle_overlaps_cell(r.position, r.radius, center)
+                    });
+                if !occupied {
+                    free_cells.push(cell);
+                }
+            }
+        }
+
+        let pieces: Vec<_> = free_cells
+            .choose_multiple(rng, RUBBLE_PIECES)
+            .copied()
+            .collect();
+        for &(x, y) in &pieces {
+            let position = self.grid_to_world(x, y);
+            self.obstacles.push(Obstacle {
+                position,
+                health: Some(RUBBLE_HEALTH),
+                rubble: true,
+            });
+        }
+        if !pieces.is_empty() {
+            self.invalidate_distance_cache();
+        }
+        pieces.len()
+    }
+
+    /// Drops a pickup of `kind` into a random cell that holds no obstacle,
+    /// live robot or other pickup. Returns false if every cell is taken.
+    pub fn spawn_pickup(&mut self, kind: PickupKind, robots: &[Robot], rng: &mut impl Rng) -> bool {
+        let mut free_cells = Vec::new();
+        for grid_y in 0..self.grid_height {
+            for grid_x in 0..self.grid_width {
+                let cell = (grid_x, grid_y);
+                let taken = self
+                    .obstacles
+                    .iter()
+                    .map(|o| o.position)
+                    .chain(self.pickups.iter().map(|p| p.position))
+                    .chain(
+                        robots
+                            .iter()
+                            .filter(|r| r.status != RobotStatus::Destroyed)
+                            .map(|r| r.position),
+                    )
+                    .any(|position| self.world_to_grid(position) == cell);
+                if !taken {
+                    free_cells.push(cell);
+                }
+            }
+        }
+        let Some(&(grid_x, grid_y)) = free_cells.choose(rng) else {
+            return false;
+        };
+        self.pickups.push(Pickup {
+            position: self.grid_to_world(grid_x, grid_y),
+            kind,
+        });
+        true
+    }
+
+    /// Hands each pickup within UNIT_SIZE of a live robot to that robot,
+    /// restoring its health or power, and removes it. Returns the collected
+    /// pickups with the id of the robot that took each.
+    pub fn collect_pickups(&mut self, robots: &mut [Robot]) -> Vec<(u32, Pickup)> {
+        let mut collected = Vec::new();
+        self.pickups.retain(|pickup| {
+            let collector = robots.iter_mut().find(|r| {
+                r.status != RobotStatus::Destroyed
+                    && r.position.distance(&pickup.position) <= UNIT_SIZE
+            });
+            let Some(robot) = collector else {
+                return true;
+            };
+            match pickup.kind {
+                PickupKind::Health => {
+                    robot.health = (robot.health + PICKUP_HEALTH).min(DEFAULT_INITIAL_HEALTH);
+                }
+                PickupKind::Energy => {
+                    robot.power = (robot.power + PICKUP_ENERGY).min(1.0);
+                }
+            }
+            collected.push((robot.id, *pickup));
+            false
+        });
+        collected
+    }
+
+    /// Gives every obstacle currently in the arena OBSTACLE_HEALTH (rubble keeps
+    /// its own), so projectiles wear it down until it's removed. Obstacles added
+    /// afterwards get OBSTACLE_HEALTH as well.
+    pub fn make_obstacles_destructible(&mut self) {
+        self.destructible_obstacles = true;
+        for obstacle in self.obstacles.iter_mut().filter(|o| !o.rubble) {
+            obstacle.health = Some(OBSTACLE_HEALTH);
+        }
+    }
+
+    // Whether a circle reaches into the grid cell centered on `cell_center`
+    // (merely touching its edge doesn't count)
+    fn circle_overlaps_cell(&self, center: Point, radius: f64, cell_center: Point) -> bool {
+        let half_unit = self.unit_size / 2.0;
+        let dx = ((center.x - cell_center.x).abs() - half_unit).max(0.0);
+        let dy = ((center.y - cell_center.y).abs() - half_unit).max(0.0);
+        dx.hypot(dy) < radius - 1e-9
+    }
+
+    /// True if a circle (e.g. a robot's body) reaches into any obstacle
+    pub fn circle_collides(&self, center: Point, radius: f64) -> bool {
+        self.obstacles
+            .iter()
+            .any(|o| self.circle_overlaps_cell(center, radius, o.position))
+    }
+
     // Checks if a given point collides with any obstacle's bounding box
     // Note: This checks the point itself, not a robot's bounding box yet.
     pub fn check_collision(&self, point: Point) -> bool {
+        self.obstacle_at(point).is_some()
+    }
+
+    // Index of the obstacle whose bounding box contains `point`
+    fn obstacle_at(&self, point: Point) -> Option<usize> {
         let half_unit = self.unit_size / 2.0;
-        for obstacle in &self.obstacles {
+        for (index, obstacle) in self.obstacles.iter().enumerate() {
             let obs_x = obstacle.position.x;
             let obs_y = obstacle.position.y;
 
@@ -87,10 +321,10 @@ impl Arena {
                 && point.y >= obs_y - half_unit
                 && point.y < obs_y + half_unit
             {
-                return true; // Collision detected
+                return Some(index); // Collision detected
             }
         }
-        false // No collision detected
+        None // No collision detected
     }
 
     // Converts grid coordinates (u32) to world coordinates (f64)
@@ -102,6 +336,165 @@ impl Arena {
         }
     }
 
+    // Converts a world coordinate point to the grid cell containing it
+    pub fn world_to_grid(&self, point: Point) -> (u32, u32) {
+        (
+            (point.x / self.unit_size).floor().max(0.0) as u32,
+            (point.y / self.unit_size).floor().max(0.0) as u32,
+        )
+    }
+
+    /// Serializes the static arena layout (dimensions, unit size, obstacle grid cells) as JSON
+    pub fn to_map_json(&self) -> String {
+        let cells: Vec<String> = self
+            .obstacles
+            .iter()
+            .map(|o| {
+                let (x, y) = self.world_to_grid(o.position);
+                format!("[{}, {}]", x, y)
+            })
+            .collect();
+        format!(
+            "{{\n  \"width\": {},\n  \"height\": {},\n  \"grid_width\": {},\n  \"grid_height\": {},\n  \"unit_size\": {},\n  \"obstacles\": [{}]\n}}\n",
+            self.width,
+            self.height,
+            self.grid_width,
+            self.grid_height,
+            self.unit_size,
+            cells.join(", ")
+        )
+    }
+
+    /// Loads an ASCII grid map, one row per line: `#` is an obstacle and `.`
+    /// empty ground. The grid's size replaces the arena's, keeping `unit_size`.
+    pub fn load_map(&mut self, contents: &str) -> Result<(), String> {
+        let rows: Vec<&str> = contents
+            .lines()
+            .map(str::trim_end)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let grid_width = rows.first().ok_or("Map has no rows")?.chars().count();
+        let mut cells = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != grid_width {
+                return Err(format!(
+                    "Map row {} is {} cells wide, expected {}",
+                    y + 1,
+                    row.chars().count(),
+                    grid_width
+                ));
+            }
+            for (x, cell) in row.chars().enumerate() {
+                match cell {
+                    '#' => cells.push((x as u32, y as u32)),
+                    '.' => {}
+                    _ => {
+                        return Err(format!(
+                            "Unexpected '{}' in map row {} (use '#' or '.')",
+                            cell,
+                            y + 1
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.grid_width = grid_width as u32;
+        self.grid_height = rows.len() as u32;
+        self.width = self.grid_width as f64 * self.unit_size;
+        self.height = self.grid_height as f64 * self.unit_size;
+        self.obstacles = cells
+            .into_iter()
+            .map(|(x, y)| Obstacle {
+                position: self.grid_to_world(x, y),
+                health: None,
+                rubble: false,
+            })
+            .collect();
+        self.invalidate_distance_cache();
+        Ok(())
+    }
+
+    /// Loads a layout written by `to_map_json`, replacing the dimensions and obstacles
+    pub fn load_map_json(&mut self, contents: &str) -> Result<(), String> {
+        // Finds the raw text of a top-level field value
+        fn field<'a>(contents: &'a str, name: &str) -> Result<&'a str, String> {
+            let key = format!("\"{}\"", name);
+            let start = contents
+                .find(&key)
+                .ok_or_else(|| format!("Map is missing field '{}'", name))?;
+            let rest = contents[start + key.len()..].trim_start();
+            let rest = rest
+                .strip_prefix(':')
+                .ok_or_else(|| format!("Malformed field '{}'", name))?
+                .trim_start();
+            let end = if rest.starts_with('[') {
+                // Take the whole list, including nested cell arrays
+                let mut depth = 0;
+                rest.char_indices().find_map(|(i, c)| {
+                    match c {
+                        '[' => depth += 1,
+                        ']' => depth -= 1,
+                        _ => {}
+                    }
+                    (depth == 0).then_some(i + 1)
+                })
+            } else {
+                rest.find([',', '}', '\n'])
+            };
+            Ok(rest[..end.unwrap_or(rest.len())].trim())
+        }
+        fn number<T: std::str::FromStr>(contents: &str, name: &str) -> Result<T, String> {
+            field(contents, name)?
+                .parse()
+                .map_err(|_| format!("Invalid value for field '{}'", name))
+        }
+
+        let width: f64 = number(contents, "width")?;
+        let height: f64 = number(contents, "height")?;
+        let grid_width: u32 = number(contents, "grid_width")?;
+        let grid_height: u32 = number(contents, "grid_height")?;
+        let unit_size: f64 = number(contents, "unit_size")?;
+
+        let list = field(contents, "obstacles")?;
+        let inner = list
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+            .ok_or("Field 'obstacles' must be a list")?;
+        let mut cells = Vec::new();
+        for cell in inner.split(']') {
+            let cell = cell.trim_start_matches([',', ' ', '\n', '[']).trim();
+            if cell.is_empty() {
+                continue;
+            }
+            let coords: Vec<u32> = cell
+                .split(',')
+                .map(|c| c.trim().parse::<u32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("Invalid obstacle cell [{}]", cell))?;
+            match coords[..] {
+                [x, y] if x < grid_width && y < grid_height => cells.push((x, y)),
+                _ => return Err(format!("Invalid obstacle cell [{}]", cell)),
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.grid_width = grid_width;
+        self.grid_height = grid_height;
+        self.unit_size = unit_size;
+        self.obstacles = cells
+            .into_iter()
+            .map(|(x, y)| Obstacle {
+                position: self.grid_to_world(x, y),
+                health: None,
+                rubble: false,
+            })
+            .collect();
+        self.invalidate_distance_cache();
+        Ok(())
+    }
+
     // Adds a projectile to the arena's list
     pub fn spawn_projectile(&mut self, projectile: Projectile) {
         log::debug!(
@@ -120,9 +513,22 @@ impl Arena {
         robots: &mut [Robot],
         particle_system: &mut ParticleSystem,
         audio_manager: &AudioManager,
+    ) {
+        // Robots don't move while projectiles fly, so one grid covers the whole cycle
+        let grid = RobotGrid::new(robots, self.unit_size);
+        self.update_projectiles_with_grid(robots, &grid, particle_system, audio_manager);
+    }
+
+    fn update_projectiles_with_grid(
+        &mut self,
+        robots: &mut [Robot],
+        grid: &RobotGrid,
+        particle_system: &mut ParticleSystem,
+        audio_manager: &AudioManager,
     ) {
         let mut i = 0;
         let sub_steps = config::PROJECTILE_SUB_STEPS;
+        let mut nearby = Vec::new();
 
         while i < self.projectiles.len() {
             let mut projectile_removed = false;
@@ -134,23 +540,98 @@ impl Arena {
             let total_dy = angle_rad.sin() * projectile.speed * self.unit_size;
 
             // Calculate movement per sub-step
-            let step_dx = total_dx / sub_steps as f64;
-            let step_dy = total_dy / sub_steps as f64;
+            let mut step_dx = total_dx / sub_steps as f64;
+            let mut step_dy = total_dy / sub_steps as f64;
 
             // Update previous position only once at the beginning of the cycle
             self.projectiles[i].prev_position = self.projectiles[i].position;
 
+            let source_team = projectile.source_team;
+
+            // Mortar shells fly over robots and obstacles and burst where they land
+            if let Some(arc) = projectile.ballistic {
+                let shell = &mut self.projectiles[i];
+                shell.position.x += total_dx;
+                shell.position.y += total_dy;
+                let height = arc.height + arc.vertical_velocity;
+                shell.ballistic = Some(Ballistic {
+                    height,
+                    vertical_velocity: arc.vertical_velocity - config::MORTAR_GRAVITY,
+                });
+                let position = shell.position;
+                if position.x < 0.0
+                    || position.x > self.width
+                    || position.y < 0.0
+                    || position.y > self.height
+                {
+                    // Sailed out of the arena
+                    self.projectiles.swap_remove(i);
+                } else if height <= 0.0 {
+                    self.projectiles.swap_remove(i);
+                    particle_system.spawn_explosion(
+                        Vec2::new(position.x as f32, position.y as f32),
+                        ORANGE,
+                        (projectile.power * 120.0 + 40.0) as usize,
+                        config::MORTAR_BLAST_RADIUS as f32 * 3.0,
+                        0.6 + projectile.power as f32 * 0.4,
+                    );
+                    audio_manager.play_wallhit();
+                    Self::apply_blast(
+                        robots,
+                        projectile.source_robot,
+                        source_team,
+                        position,
+                        config::MORTAR_BLAST_RADIUS,
+                        config::MORTAR_DAMAGE * projectile.power * self.damage_multiplier,
+                        audio_manager,
+                    );
+                } else {
+                    i += 1;
+                }
+                continue;
+            }
+
             // --- Sub-step Loop ---
             for step in 0..sub_steps {
                 // Move projectile by one sub-step
                 self.projectiles[i].position.x += step_dx;
                 self.projectiles[i].position.y += step_dy;
 
-                let current_pos = self.projectiles[i].position;
+                let mut current_pos = self.projectiles[i].position;
                 let source_id = projectile.source_robot;
                 let proj_power = projectile.power;
                 let proj_base_damage = projectile.base_damage;
 
+                // Bank shots reflect off the walls while they have bounces left,
+                // then carry on with the rest of the sub-steps
+                let out_x = current_pos.x < 0.0 || current_pos.x > self.width;
+                let out_y = current_pos.y < 0.0 || current_pos.y > self.height;
+                if (out_x || out_y) && self.projectiles[i].bounces_remaining > 0 {
+                    let shot = &mut self.projectiles[i];
+                    shot.bounces_remaining -= 1;
+                    if out_x {
+                        shot.direction = 180.0 - shot.direction;
+                        shot.position.x =
+                            shot.position.x.clamp(0.0, self.width) * 2.0 - shot.position.x;
+                        step_dx = -step_dx;
+                    }
+                    if out_y {
+                        shot.direction = -shot.direction;
+                        shot.position.y =
+                            shot.position.y.clamp(0.0, self.height) * 2.0 - shot.position.y;
+                        step_dy = -step_dy;
+                    }
+                    shot.direction = shot.direction.rem_euclid(360.0);
+                    current_pos = shot.position;
+                    log::debug!(
+                        "Projectile bounced at ({:.2}, {:.2}), new direction {:.2}",
+                        current_pos.x,
+                        current_pos.y,
+                        shot.direction
+                    );
+                    audio_manager.play_wallhit();
+                }
+
                 // Check for collisions with arena boundaries
                 if current_pos.x < 0.0
                     || current_pos.x > self.width
@@ -178,7 +659,7 @@ impl Arena {
                 }
 
                 // Check for collisions with obstacles
-                if self.check_collision(current_pos) {
+                if let Some(obstacle_index) = self.obstacle_at(current_pos) {
                     log::debug!(
                         "Projectile hit obstacle at ({:.2}, {:.2}) on sub-step {}",
                         current_pos.x,
@@ -194,19 +675,48 @@ impl Arena {
                         0.5,
                     );
                     audio_manager.play_wallhit();
+                    // Destructible obstacles soak up the hit and crumble at zero health
+                    if let Some(health) = &mut self.obstacles[obstacle_index].health {
+                        *health -= proj_base_damage * proj_power * self.damage_multiplier;
+                        if *health <= 0.0 {
+                            let obstacle = self.obstacles.remove(obstacle_index);
+                            self.invalidate_distance_cache();
+                            if !obstacle.rubble {
+                                self.crumbled_obstacles.push(obstacle.position);
+                            }
+                            log::debug!(
+                                "Obstacle at ({:.2}, {:.2}) destroyed",
+                                obstacle.position.x,
+                                obstacle.position.y
+                            );
+                            particle_system.spawn_explosion(
+                                Vec2::new(obstacle.position.x as f32, obstacle.position.y as f32),
+                                GRAY,
+                                120,
+                                config::UNIT_SIZE as f32 * 8.0,
+                                1.0,
+                            );
+                        }
+                    }
                     self.projectiles.swap_remove(i);
                     projectile_removed = true;
                     break; // Exit sub-step loop
                 }
 
-                // Check for collisions with robots
-                for robot in robots.iter_mut() {
-                    if robot.id == source_id || robot.status == RobotStatus::Destroyed {
+                // Check for collisions with robots; shots pass through the shooter's teammates
+                let mut hit: Option<(f64, bool)> = None; // (damage, target destroyed)
+                grid.nearby(current_pos, &mut nearby);
+                for &index in &nearby {
+                    let robot = &mut robots[index];
+                    if robot.id == source_id
+                        || robot.status == RobotStatus::Destroyed
+                        || (source_team != 0 && robot.team == source_team)
+                    {
                         continue;
                     }
                     let dist_sq = (robot.position.x - current_pos.x).powi(2)
                         + (robot.position.y - current_pos.y).powi(2);
-                    let collision_radius_sq = (self.unit_size / 2.0).powi(2);
+                    let collision_radius_sq = robot.radius.powi(2);
 
                     if dist_sq < collision_radius_sq {
                         log::debug!(
@@ -227,26 +737,25 @@ impl Arena {
                             particle_lifetime as f32,
                         );
 
-                        let damage = proj_base_damage * proj_power;
-                        robot.health -= damage;
-                        audio_manager.play_bothit();
-                        log::info!(
-                            "Robot {} took {:.2} damage, health remaining: {:.2}",
-                            robot.id,
-                            damage,
-                            robot.health
+                        let damage = projectile.damage_type.against(
+                            proj_base_damage * proj_power * self.damage_multiplier,
+                            robot.armor,
                         );
-                        if robot.health <= 0.0 {
-                            robot.health = 0.0;
-                            robot.status = RobotStatus::Destroyed;
-                            audio_manager.play_death();
-                            log::info!("Robot {} destroyed!", robot.id);
-                        }
+                        hit = Some(Self::apply_hit(
+                            robot,
+                            Some(source_id),
+                            damage,
+                            audio_manager,
+                        ));
                         self.projectiles.swap_remove(i);
                         projectile_removed = true;
                         break; // Exit robot loop
                     }
                 }
+                // Credit the shooter for the hit
+                if let Some((damage, destroyed)) = hit {
+                    Self::credit_hit(robots, source_id, damage, destroyed);
+                }
                 if projectile_removed {
                     break;
                 } // Exit sub-step loop if robot was hit
@@ -260,6 +769,239 @@ impl Arena {
         }
     }
 
+    /// Standard hit path for every kind of damage: an active shield soaks up what it
+    /// can and the rest comes off health, destroying the robot at zero health.
+    /// `source_id` is the robot credited with the hit, if any.
+    /// Returns the damage that got through the shield and whether the hit destroyed the robot.
+    fn apply_hit(
+        robot: &mut Robot,
+        source_id: Option<u32>,
+        damage: f64,
+        audio_manager: &AudioManager,
+    ) -> (f64, bool) {
+        let damage = robot.absorb_with_shield(damage);
+        robot.health -= damage;
+        robot.record_combat();
+        audio_manager.play_bothit();
+        log::info!(
+            "Robot {} took {:.2} damage, health remaining: {:.2}",
+            robot.id,
+            damage,
+            robot.health
+        );
+        let destroyed = robot.health <= 0.0;
+        if destroyed {
+            robot.health = 0.0;
+            robot.status = RobotStatus::Destroyed;
+        }
+        robot.events.push(Event::RobotHit {
+            robot_id: robot.id,
+            source_robot: source_id,
+            damage,
+            health: robot.health,
+        });
+        if destroyed {
+            audio_manager.play_death();
+            log::info!("Robot {} destroyed!", robot.id);
+            robot.events.push(Event::RobotDestroyed {
+                robot_id: robot.id,
+                source_robot: source_id,
+            });
+        }
+        (damage, destroyed)
+    }
+
+    /// Credits the attacking robot with damage dealt and any kill
+    fn credit_hit(robots: &mut [Robot], source_id: u32, damage: f64, destroyed: bool) {
+        if let Some(attacker) = robots.iter_mut().find(|r| r.id == source_id) {
+            attacker.damage_dealt += damage;
+            attacker.record_combat();
+            if destroyed {
+                attacker.kills += 1;
+            }
+        }
+    }
+
+    /// Resolves a self-destruct: every other live robot within DETONATION_RADIUS, except
+    /// teammates, takes damage scaled by blast power and proximity, credited to the
+    /// detonating robot
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_detonation(
+        &self,
+        robots: &mut [Robot],
+        source_id: u32,
+        source_team: u8,
+        center: Point,
+        power: f64,
+        particle_system: &mut ParticleSystem,
+        audio_manager: &AudioManager,
+    ) {
+        particle_system.spawn_explosion(
+            Vec2::new(center.x as f32, center.y as f32),
+            ORANGE,
+            (power * 200.0 + 50.0) as usize,
+            config::UNIT_SIZE as f32 * 12.0,
+            0.8 + power as f32 * 0.6,
+        );
+        audio_manager.play_death();
+
+        Self::apply_blast(
+            robots,
+            source_id,
+            source_team,
+            center,
+            config::DETONATION_RADIUS,
+            config::DETONATION_DAMAGE * power * self.damage_multiplier,
+            audio_manager,
+        );
+    }
+
+    /// Sets off every mine that a live robot other than its owner or a teammate has come
+    /// within MINE_TRIGGER_RADIUS of. Each blast damages robots within MINE_BLAST_RADIUS,
+    /// scaled by the mine's power, except its owner, who is credited with the damage,
+    /// and the owner's teammates.
+    pub fn update_mines(
+        &mut self,
+        robots: &mut [Robot],
+        particle_system: &mut ParticleSystem,
+        audio_manager: &AudioManager,
+    ) {
+        let (triggered, armed): (Vec<Mine>, Vec<Mine>) = self.mines.iter().partition(|mine| {
+            robots.iter().any(|robot| {
+                robot.id != mine.owner
+                    && (mine.team == 0 || robot.team != mine.team)
+                    && robot.status != RobotStatus::Destroyed
+                    && robot.position.distance(&mine.position) < config::MINE_TRIGGER_RADIUS
+            })
+        });
+        self.mines = armed;
+
+        for mine in triggered {
+            particle_system.spawn_explosion(
+                Vec2::new(mine.position.x as f32, mine.position.y as f32),
+                ORANGE,
+                (mine.power * 100.0 + 30.0) as usize,
+                config::UNIT_SIZE as f32 * 6.0,
+                0.5 + mine.power as f32 * 0.4,
+            );
+            audio_manager.play_death();
+
+            Self::apply_blast(
+                robots,
+                mine.owner,
+                mine.team,
+                mine.position,
+                config::MINE_BLAST_RADIUS,
+                config::MINE_DAMAGE * mine.power * self.damage_multiplier,
+                audio_manager,
+            );
+        }
+    }
+
+    /// Area damage: every other live robot within `radius` of `center` takes `max_damage`
+    /// falling off linearly to 0 at the radius, credited to `source_id`. Robots on the
+    /// source's team (`source_team`, 0 for none) are spared.
+    fn apply_blast(
+        robots: &mut [Robot],
+        source_id: u32,
+        source_team: u8,
+        center: Point,
+        radius: f64,
+        max_damage: f64,
+        audio_manager: &AudioManager,
+    ) {
+        let mut hits = Vec::new();
+        for robot in robots.iter_mut() {
+            if robot.id == source_id
+                || robot.status == RobotStatus::Destroyed
+                || (source_team != 0 && robot.team == source_team)
+            {
+                continue;
+            }
+            let distance = robot.position.distance(&center);
+            if distance >= radius {
+                continue;
+            }
+            let damage = max_damage * (1.0 - distance / radius);
+            if damage <= 0.0 {
+                continue;
+            }
+            hits.push(Self::apply_hit(
+                robot,
+                Some(source_id),
+                damage,
+                audio_manager,
+            ));
+        }
+        for (damage, destroyed) in hits {
+            Self::credit_hit(robots, source_id, damage, destroyed);
+        }
+    }
+
+    /// Laser weapon damage at the given power and distance from the shooter, before
+    /// the damage multiplier: BEAM_DAMAGE fades by BEAM_FALLOFF per unit travelled.
+    pub fn beam_damage(power: f64, distance: f64) -> f64 {
+        config::BEAM_DAMAGE * power * (1.0 - config::BEAM_FALLOFF * distance).max(0.0)
+    }
+
+    /// Resolves a beam shot: the first live robot along `direction` from `start`, short of
+    /// any wall or obstacle, takes BEAM_DAMAGE scaled by power (faded by distance with
+    /// `falloff`, see `beam_damage`), credited to the shooter.
+    /// The beam is recorded in `beams` for rendering.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fire_beam(
+        &mut self,
+        robots: &mut [Robot],
+        source_id: u32,
+        source_team: u8,
+        start: Point,
+        direction: f64,
+        power: f64,
+        falloff: bool,
+        audio_manager: &AudioManager,
+    ) {
+        // distance_to_collision sweeps a robot-sized circle; add the radius back for a thin ray
+        let range = self.distance_to_collision(start, direction) + self.unit_size / 2.0;
+        let mut hit: Option<(usize, f64)> = None;
+        for (index, robot) in robots.iter().enumerate() {
+            // Like shots, beams pass through the shooter's teammates
+            if robot.id == source_id
+                || robot.status == RobotStatus::Destroyed
+                || (source_team != 0 && robot.team == source_team)
+            {
+                continue;
+            }
+            let distance =
+                self.distance_to_robots(start, direction, 0.0, &[(robot.position, robot.radius)]);
+            if distance < range && hit.is_none_or(|(_, closest)| distance < closest) {
+                hit = Some((index, distance));
+            }
+        }
+
+        let length = hit.map_or(range, |(_, distance)| distance);
+        let angle_rad = direction.to_radians();
+        self.beams.push(Beam {
+            start,
+            end: Point {
+                x: start.x + angle_rad.cos() * length,
+                y: start.y + angle_rad.sin() * length,
+            },
+            source_robot: source_id,
+            power,
+        });
+
+        if let Some((index, distance)) = hit {
+            let damage = if falloff {
+                Self::beam_damage(power, distance)
+            } else {
+                config::BEAM_DAMAGE * power
+            } * self.damage_multiplier;
+            let (damage, destroyed) =
+                Self::apply_hit(&mut robots[index], Some(source_id), damage, audio_manager);
+            Self::credit_hit(robots, source_id, damage, destroyed);
+        }
+    }
+
     /// Calculates the distance from a robot's center point to the point where its edge
     /// would first collide with a wall or obstacle along a given angle.
     pub fn distance_to_collision(&self, start_point: Point, angle_degrees: f64) -> f64 {
@@ -357,6 +1099,42 @@ impl Arena {
         min_dist_wall_edge
     }
 
+    /// Distance a robot of `radius` at `start_point` can travel along `angle_degrees`
+    /// before touching one of the given robot circles (position, radius).
+    /// Returns INFINITY if nothing lies in the path.
+    pub fn distance_to_robots(
+        &self,
+        start_point: Point,
+        angle_degrees: f64,
+        radius: f64,
+        others: &[(Point, f64)],
+    ) -> f64 {
+        let angle_rad = angle_degrees.to_radians();
+        let (dir_x, dir_y) = (angle_rad.cos(), angle_rad.sin());
+
+        let mut min_dist = f64::INFINITY;
+        for (center, other_radius) in others {
+            // Ray from our center against the other circle grown by our radius
+            let m_x = start_point.x - center.x;
+            let m_y = start_point.y - center.y;
+            let combined = radius + other_radius;
+            let c = m_x * m_x + m_y * m_y - combined * combined;
+            if c <= 0.0 {
+                return 0.0; // Already touching
+            }
+            let b = m_x * dir_x + m_y * dir_y;
+            if b >= 0.0 {
+                continue; // Heading away from this robot
+            }
+            let discriminant = b * b - c;
+            if discriminant < 0.0 {
+                continue; // Path misses this robot
+            }
+            min_dist = min_dist.min(-b - discriminant.sqrt());
+        }
+        min_dist
+    }
+
     /// First pass of the AOI (area of interest) detector
     /// Takes a slice of mutable robots to update their AOI fields
     pub fn update_all_robots_aoi(&mut self, robots: &mut [Robot]) {
@@ -390,11 +1168,141 @@ impl Arena {
         }
     }
 
+    /// Shoves live robots near an explosion radially outward, scaled by proximity.
+    /// Shoves stop short of walls and obstacles.
+    pub fn apply_explosion_push(&self, robots: &mut [Robot], center: Point) {
+        for robot in robots.iter_mut() {
+            if robot.status == RobotStatus::Destroyed {
+                continue;
+            }
+            let distance = robot.position.distance(&center);
+            if !(1e-9..config::EXPLOSION_PUSH_RADIUS).contains(&distance) {
+                continue; // Out of range, or no defined outward direction
+            }
+
+            let falloff = 1.0 - distance / config::EXPLOSION_PUSH_RADIUS;
+            let angle_deg = (robot.position.y - center.y)
+                .atan2(robot.position.x - center.x)
+                .to_degrees();
+            let max_safe_distance = self.distance_to_collision(robot.position, angle_deg);
+            let push = (config::EXPLOSION_PUSH_DISTANCE * falloff)
+                .min(max_safe_distance - config::UNIT_SIZE * 0.01)
+                .max(0.0);
+
+            let angle_rad = angle_deg.to_radians();
+            robot.position.x += angle_rad.cos() * push;
+            robot.position.y += angle_rad.sin() * push;
+            log::debug!(
+                "Robot {} pushed {:.4} by explosion at ({:.2}, {:.2})",
+                robot.id,
+                push,
+                center.x,
+                center.y
+            );
+        }
+    }
+
+    /// Separates live robots whose collision circles overlap: each pair is pushed
+    /// apart along the line between them (stopping short of walls and obstacles;
+    /// when one robot is pinned the other takes the whole push) and both stop
+    /// driving. Both take COLLISION_DAMAGE when the contact starts, not again while
+    /// they stay pressed together. Returns the number of colliding pairs.
+    pub fn resolve_robot_collisions(
+        &mut self,
+        robots: &mut [Robot],
+        audio_manager: &AudioManager,
+    ) -> usize {
+        let mut collisions = 0;
+        let previous_contacts = std::mem::take(&mut self.contacts);
+        for i in 0..robots.len() {
+            for j in (i + 1)..robots.len() {
+                let (head, tail) = robots.split_at_mut(j);
+                let (a, b) = (&mut head[i], &mut tail[0]);
+                if a.status == RobotStatus::Destroyed || b.status == RobotStatus::Destroyed {
+                    continue;
+                }
+                let distance = a.position.distance(&b.position);
+                let overlap = a.radius + b.radius - distance;
+                if overlap <= 0.0 {
+                    continue;
+                }
+                collisions += 1;
+                let pair = (a.id.min(b.id), a.id.max(b.id));
+                let new_contact = !previous_contacts.contains(&pair);
+                self.contacts.push(pair);
+
+                // Robots on the same spot have no defined direction; split them along x
+                let angle_deg = if distance > 1e-9 {
+                    (b.position.y - a.position.y)
+                        .atan2(b.position.x - a.position.x)
+                        .to_degrees()
+                } else {
+                    0.0
+                };
+                let (a_direction, b_direction) = (angle_deg + 180.0, angle_deg);
+                let safe_push = |position: Point, direction: f64| {
+                    (self.distance_to_collision(position, direction) - config::UNIT_SIZE * 0.01)
+                        .max(0.0)
+                };
+                let (a_room, b_room) = (
+                    safe_push(a.position, a_direction),
+                    safe_push(b.position, b_direction),
+                );
+                // Split the overlap; whatever one robot has no room for goes to the other
+                let b_push = (overlap - (overlap / 2.0).min(a_room)).min(b_room);
+                let a_push = (overlap - b_push).min(a_room);
+                for (robot, direction, push) in [
+                    (&mut *a, a_direction, a_push),
+                    (&mut *b, b_direction, b_push),
+                ] {
+                    let angle_rad = direction.to_radians();
+                    robot.position.x += angle_rad.cos() * push;
+                    robot.position.y += angle_rad.sin() * push;
+                    robot.drive.velocity = 0.0;
+                    if new_contact {
+                        let damage = config::COLLISION_DAMAGE * self.damage_multiplier;
+                        Self::apply_hit(robot, None, damage, audio_manager);
+                    }
+                }
+                log::debug!("Robots {} and {} collided", a.id, b.id);
+            }
+        }
+        collisions
+    }
+
+    /// Removes every in-flight projectile fired by the given robot, leaving a small
+    /// fizzle of particles where each one was. Returns how many were removed.
+    pub fn despawn_projectiles_from(
+        &mut self,
+        robot_id: u32,
+        particle_system: &mut ParticleSystem,
+    ) -> usize {
+        let before = self.projectiles.len();
+        self.projectiles.retain(|projectile| {
+            if projectile.source_robot != robot_id {
+                return true;
+            }
+            let position = Vec2::new(projectile.position.x as f32, projectile.position.y as f32);
+            particle_system.spawn_explosion(
+                position,
+                GRAY,
+                8,
+                config::UNIT_SIZE as f32 * 1.5,
+                0.25,
+            );
+            false
+        });
+        before - self.projectiles.len()
+    }
+
     /// Adds an obstacle at the given robot's position (for wreckage)
     pub fn add_obstacle_at_robot(&mut self, robot: &Robot) {
         self.obstacles.push(Obstacle {
             position: robot.position,
+            health: self.destructible_obstacles.then_some(OBSTACLE_HEALTH),
+            rubble: false,
         });
+        self.invalidate_distance_cache();
     }
 }
 
@@ -411,7 +1319,7 @@ mod tests {
     use crate::config;
     use crate::particles::ParticleSystem;
     use crate::robot::Robot;
-    use crate::types::{Point, Projectile};
+    use crate::types::{Ballistic, Point, Projectile};
 
     #[test]
     fn test_projectile_movement() {
@@ -425,6 +1333,10 @@ mod tests {
             power: 1.0,
             base_damage: 10.0,
             source_robot: 0,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile);
 
@@ -453,6 +1365,10 @@ mod tests {
             power: 1.0,
             base_damage: 10.0,
             source_robot: 0,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile);
 
@@ -467,6 +1383,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_drop_rubble_fills_free_adjacent_cells_deterministically() {
+        let corner_drop = |seed: u64| {
+            let mut arena = Arena::new();
+            // Corner cell: only (1, 0), (0, 1) and (1, 1) are adjacent
+            arena.obstacles.push(Obstacle {
+                position: arena.grid_to_world(1, 0),
+                health: None,
+                rubble: false,
+            });
+            let mut robot = Robot::new(
+                1,
+                "Bystander".to_string(),
+                arena.grid_to_world(1, 1),
+                arena.grid_to_world(10, 10),
+            );
+            robot.status = RobotStatus::Active;
+            let mut rng = StdRng::seed_from_u64(seed);
+            let placed = arena.drop_rubble(0, 0, &[robot], &mut rng);
+            (placed, arena.obstacles)
+        };
+
+        // The only free neighbour is (0, 1)
+        let (placed, obstacles) = corner_drop(7);
+        assert_eq!(placed, 1);
+        assert_eq!(obstacles.len(), 2);
+        assert_eq!(obstacles[1].position, Arena::new().grid_to_world(0, 1));
+        assert_eq!(obstacles[1].health, Some(config::RUBBLE_HEALTH));
+        assert!(obstacles[1].rubble);
+
+        // In open ground the full pile lands in adjacent cells, the same way every time
+        let pile = |seed: u64| {
+            let mut arena = Arena::new();
+            let mut rng = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                arena.drop_rubble(5, 5, &[], &mut rng),
+                config::RUBBLE_PIECES
+            );
+            arena
+                .obstacles
+                .iter()
+                .map(|o| arena.world_to_grid(o.position))
+                .collect::<Vec<_>>()
+        };
+        let cells = pile(42);
+        assert_eq!(cells, pile(42));
+        for (x, y) in cells {
+            assert!(x.abs_diff(5) <= 1 && y.abs_diff(5) <= 1 && (x, y) != (5, 5));
+        }
+    }
+
+    #[test]
+    fn test_drop_rubble_skips_cells_a_robot_reaches_into() {
+        let mut arena = Arena::new();
+        // Every neighbour of (5, 5) is taken except (6, 5)
+        for (x, y) in [(4, 4), (5, 4), (6, 4), (4, 5), (4, 6), (5, 6), (6, 6)] {
+            arena.obstacles.push(Obstacle {
+                position: arena.grid_to_world(x, y),
+                health: None,
+                rubble: false,
+            });
+        }
+        // The robot stands in (7, 5) but its body pokes into (6, 5)
+        let mut position = arena.grid_to_world(7, 5);
+        position.x -= config::UNIT_SIZE * 0.4;
+        let mut robot = Robot::new(1, "Edge".to_string(), position, position);
+        robot.status = RobotStatus::Active;
+        assert_eq!(arena.world_to_grid(position), (7, 5));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(arena.drop_rubble(5, 5, &[robot], &mut rng), 0);
+    }
+
     #[test]
     fn test_projectile_obstacle_collision() {
         let mut arena = Arena::new();
@@ -474,6 +1463,8 @@ mod tests {
         let obstacle_pos = arena.grid_to_world(10, 10); // Middle obstacle
         arena.obstacles.push(Obstacle {
             position: obstacle_pos,
+            health: None,
+            rubble: false,
         });
 
         // Spawn projectile just left of the obstacle, moving right
@@ -489,6 +1480,10 @@ mod tests {
             power: 1.0,
             base_damage: 10.0,
             source_robot: 0,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile);
 
@@ -529,6 +1524,10 @@ mod tests {
             power: 0.5,        // Power affects damage
             base_damage: 20.0, // Base damage
             source_robot: 1,   // Fired by robot 1
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile);
 
@@ -557,6 +1556,8 @@ mod tests {
             robots[0].health, 100.0,
             "Robot 1 health should be unchanged"
         ); // Verify R1 health
+        assert!((robots[0].damage_dealt - expected_damage).abs() < 1e-9);
+        assert_eq!(robots[0].kills, 0);
 
         // Test lethal hit
         robots[1].health = 5.0; // Low health
@@ -575,6 +1576,10 @@ mod tests {
             power: 0.5,
             base_damage: 20.0,
             source_robot: 1,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile2);
         arena.update_projectiles(
@@ -595,6 +1600,7 @@ mod tests {
             crate::robot::RobotStatus::Destroyed,
             "Robot 2 should be destroyed"
         );
+        assert_eq!(robots[0].kills, 1, "Robot 1 should be credited the kill");
     }
 
     #[test]
@@ -621,6 +1627,10 @@ mod tests {
             power: 1.0,
             base_damage: 100.0,
             source_robot: 1, // Fired by robot 1
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
         };
         arena.spawn_projectile(projectile);
 
@@ -640,4 +1650,999 @@ mod tests {
             "Source robot health should be unchanged"
         );
     }
+
+    #[test]
+    fn test_armor_reduces_kinetic_but_not_energy_damage() {
+        let center = Point { x: 0.5, y: 0.5 };
+        let target_pos = Point { x: 0.75, y: 0.5 };
+        let start = Point {
+            x: 0.25 + config::UNIT_SIZE,
+            y: 0.5,
+        };
+        let audio_manager = AudioManager::new();
+
+        for (damage_type, expected_damage) in [
+            (DamageType::Kinetic, 10.0 - 4.0),
+            (DamageType::Energy, 10.0),
+        ] {
+            let mut arena = Arena::new();
+            let mut particle_system = ParticleSystem::new();
+            let mut shooter =
+                Robot::new(1, "Shooter".to_string(), Point { x: 0.25, y: 0.5 }, center);
+            shooter.status = RobotStatus::Active;
+            let mut target = Robot::new(2, "Armored".to_string(), target_pos, center);
+            target.status = RobotStatus::Active;
+            target.armor = 4.0;
+            arena.spawn_projectile(Projectile {
+                position: start,
+                prev_position: start,
+                direction: 0.0,
+                speed: 9.0, // Lands on the target center after 1 cycle
+                power: 1.0,
+                base_damage: 10.0,
+                source_robot: 1,
+                source_team: 0,
+                ballistic: None,
+                bounces_remaining: 0,
+                damage_type,
+            });
+            let mut robots = vec![shooter, target];
+
+            arena.update_projectiles(&mut robots, &mut particle_system, &audio_manager);
+
+            assert!(arena.projectiles.is_empty());
+            assert!((robots[1].health - (100.0 - expected_damage)).abs() < 1e-9);
+            assert!((robots[0].damage_dealt - expected_damage).abs() < 1e-9);
+        }
+
+        // Armor never turns a weak kinetic hit into healing
+        assert_eq!(DamageType::Kinetic.against(2.0, 4.0), 0.0);
+    }
+
+    #[test]
+    fn test_projectile_hits_larger_robot_radius() {
+        let target_pos = Point { x: 0.5, y: 0.5 };
+        // Passes 0.7 units from the robot center: outside the default radius (0.5 units)
+        let proj_start_pos = Point {
+            x: target_pos.x - config::UNIT_SIZE,
+            y: target_pos.y + config::UNIT_SIZE * 0.7,
+        };
+        let projectile = Projectile {
+            position: proj_start_pos,
+            prev_position: proj_start_pos,
+            direction: 0.0,
+            speed: 1.0,
+            power: 1.0,
+            base_damage: 10.0,
+            source_robot: 2,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
+        };
+        let mut particle_system = ParticleSystem::new();
+        let audio_manager = AudioManager::new();
+
+        // Default radius: the projectile misses
+        let mut arena = Arena::new();
+        arena.spawn_projectile(projectile);
+        let mut robots = vec![Robot::new(1, "Small".to_string(), target_pos, target_pos)];
+        arena.update_projectiles(&mut robots, &mut particle_system, &audio_manager);
+        assert_eq!(arena.projectiles.len(), 1);
+        assert_eq!(robots[0].health, config::DEFAULT_INITIAL_HEALTH);
+
+        // Larger radius: the same projectile hits
+        let mut arena = Arena::new();
+        arena.spawn_projectile(projectile);
+        let mut big = Robot::new(1, "Big".to_string(), target_pos, target_pos);
+        big.radius = config::UNIT_SIZE;
+        let mut robots = vec![big];
+        arena.update_projectiles(&mut robots, &mut particle_system, &audio_manager);
+        assert!(arena.projectiles.is_empty());
+        assert!(robots[0].health < config::DEFAULT_INITIAL_HEALTH);
+    }
+
+    #[test]
+    fn test_robots_driving_into_each_other_collide() {
+        let mut arena = Arena::new();
+        let audio = AudioManager::new();
+        let mut robots = vec![
+            Robot::with_heading(1, "East".to_string(), Point { x: 0.4, y: 0.5 }, 0.0),
+            Robot::with_heading(2, "West".to_string(), Point { x: 0.6, y: 0.5 }, 180.0),
+        ];
+        for robot in robots.iter_mut() {
+            robot.drive.velocity = config::UNIT_SIZE / 4.0;
+        }
+
+        let mut collisions = 0;
+        for _ in 0..40 {
+            for robot in robots.iter_mut() {
+                robot.process_cycle_updates(&arena);
+            }
+            collisions += arena.resolve_robot_collisions(&mut robots, &audio);
+            if collisions > 0 {
+                break;
+            }
+        }
+
+        assert_eq!(collisions, 1);
+        for robot in &robots {
+            assert_eq!(
+                robot.health,
+                config::DEFAULT_INITIAL_HEALTH - config::COLLISION_DAMAGE
+            );
+            assert_eq!(robot.drive.velocity, 0.0);
+        }
+        // Pushed apart to just touching
+        let gap = robots[0].position.distance(&robots[1].position);
+        assert!(gap >= robots[0].radius + robots[1].radius - 1e-9);
+        assert!(robots[0].position.x < robots[1].position.x);
+    }
+
+    #[test]
+    fn test_robot_pinned_against_wall_is_damaged_once() {
+        let mut arena = Arena::new();
+        let audio = AudioManager::new();
+        let center = Point { x: 0.5, y: 0.5 };
+        let radius = Robot::new(1, String::new(), center, center).radius;
+        let mut robots = vec![
+            Robot::new(1, "Pinned".to_string(), Point { x: radius, y: 0.5 }, center),
+            Robot::new(
+                2,
+                "Pusher".to_string(),
+                Point {
+                    x: 3.0 * radius - 0.005,
+                    y: 0.5,
+                },
+                center,
+            ),
+        ];
+
+        let mut collisions = 0;
+        for _ in 0..100 {
+            collisions += arena.resolve_robot_collisions(&mut robots, &audio);
+        }
+
+        // The free robot takes the whole push, so the contact ends after one cycle
+        assert_eq!(collisions, 1);
+        assert_eq!(robots[0].position.x, radius);
+        let gap = robots[0].position.distance(&robots[1].position);
+        assert!(gap >= 2.0 * radius - 1e-9);
+        for robot in &robots {
+            assert_eq!(
+                robot.health,
+                config::DEFAULT_INITIAL_HEALTH - config::COLLISION_DAMAGE
+            );
+        }
+    }
+
+    #[test]
+    fn test_robots_pressed_together_take_damage_once() {
+        let mut arena = Arena::new();
+        let audio = AudioManager::new();
+        let mut robots = vec![
+            Robot::with_heading(1, "East".to_string(), Point { x: 0.48, y: 0.5 }, 0.0),
+            Robot::with_heading(2, "West".to_string(), Point { x: 0.52, y: 0.5 }, 180.0),
+        ];
+
+        // Driving into each other every cycle keeps them in contact
+        for _ in 0..20 {
+            robots[0].position.x += 0.001;
+            robots[1].position.x -= 0.001;
+            arena.resolve_robot_collisions(&mut robots, &audio);
+        }
+        for robot in &robots {
+            assert_eq!(
+                robot.health,
+                config::DEFAULT_INITIAL_HEALTH - config::COLLISION_DAMAGE
+            );
+        }
+    }
+
+    #[test]
+    fn test_explosion_push() {
+        let arena = Arena::new();
+        let center = Point { x: 0.5, y: 0.5 };
+        let mut robots = vec![
+            Robot::new(1, "Near".to_string(), Point { x: 0.55, y: 0.5 }, center),
+            Robot::new(2, "Far".to_string(), Point { x: 0.1, y: 0.1 }, center),
+        ];
+
+        arena.apply_explosion_push(&mut robots, center);
+
+        // Adjacent robot moves directly away from the explosion
+        assert!(robots[0].position.x > 0.55);
+        assert!((robots[0].position.y - 0.5).abs() < 1e-9);
+        // Distant robot is unaffected
+        assert_eq!(robots[1].position, Point { x: 0.1, y: 0.1 });
+    }
+
+    #[test]
+    fn test_map_json_round_trip() {
+        let mut arena = Arena::new();
+        arena.place_obstacles(&mut StdRng::seed_from_u64(1));
+        arena.obstacles.push(Obstacle {
+            position: arena.grid_to_world(3, 7),
+            health: None,
+            rubble: false,
+        });
+        let json = arena.to_map_json();
+
+        let mut loaded = Arena::new();
+        loaded.obstacles.clear();
+        loaded.load_map_json(&json).unwrap();
+
+        assert_eq!(loaded.grid_width, arena.grid_width);
+        assert_eq!(loaded.grid_height, arena.grid_height);
+        assert_eq!(loaded.unit_size, arena.unit_size);
+        assert_eq!(loaded.obstacles, arena.obstacles);
+
+        assert!(loaded.load_map_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_mortar_lands_at_expected_range() {
+        let mut arena = Arena::new();
+        let launch = Point { x: 0.1, y: 0.5 };
+        let horizontal_step = config::MORTAR_SPEED * config::UNIT_SIZE;
+        let vertical_velocity = config::MORTAR_LAUNCH_VELOCITY;
+        // Airborne for 2v/g cycles, covering one horizontal step per cycle
+        let flight_cycles = 2.0 * vertical_velocity / config::MORTAR_GRAVITY;
+        let landing = Point {
+            x: launch.x + horizontal_step * flight_cycles,
+            y: launch.y,
+        };
+        let overflown = Point {
+            x: launch.x + (landing.x - launch.x) / 2.0,
+            y: launch.y,
+        };
+        let mut robots = vec![
+            Robot::new(1, "Gunner".to_string(), launch, landing),
+            Robot::new(2, "Overflown".to_string(), overflown, landing),
+            Robot::new(3, "Target".to_string(), landing, launch),
+        ];
+        arena.spawn_projectile(Projectile {
+            position: launch,
+            prev_position: launch,
+            direction: 0.0,
+            speed: config::MORTAR_SPEED,
+            power: 1.0,
+            base_damage: 10.0,
+            source_robot: 1,
+            source_team: 0,
+            damage_type: DamageType::Kinetic,
+            ballistic: Some(Ballistic {
+                height: 0.0,
+                vertical_velocity,
+            }),
+            bounces_remaining: 0,
+        });
+
+        let mut cycles = 0;
+        while !arena.projectiles.is_empty() && cycles < 1000 {
+            arena.update_projectiles(
+                &mut robots,
+                &mut ParticleSystem::new(),
+                &AudioManager::new(),
+            );
+            cycles += 1;
+        }
+
+        // The discrete arc touches down within a cycle of the continuous one
+        assert!(
+            (cycles as f64 - flight_cycles).abs() <= 2.0,
+            "landed after {} cycles",
+            cycles
+        );
+        assert_eq!(robots[1].health, config::DEFAULT_INITIAL_HEALTH);
+        assert!(robots[2].health < config::DEFAULT_INITIAL_HEALTH);
+        assert!(robots[0].damage_dealt > 0.0);
+    }
+
+    #[test]
+    fn test_detonation_credits_kills() {
+        let arena = Arena::new();
+        let center = Point { x: 0.5, y: 0.5 };
+        let mut bomber = Robot::new(1, "Bomber".to_string(), center, center);
+        bomber.status = RobotStatus::Destroyed; // Already self-destructed
+        let near = Point {
+            x: center.x + config::UNIT_SIZE,
+            y: center.y,
+        };
+        let mut victim = Robot::new(2, "Victim".to_string(), near, center);
+        victim.health = 1.0;
+        let mut robots = vec![bomber, victim];
+
+        arena.apply_detonation(
+            &mut robots,
+            1,
+            0,
+            center,
+            1.0,
+            &mut ParticleSystem::new(),
+            &AudioManager::new(),
+        );
+
+        assert_eq!(robots[1].status, RobotStatus::Destroyed);
+        assert_eq!(robots[0].kills, 1);
+        assert!(robots[0].damage_dealt > 0.0);
+    }
+
+    #[test]
+    fn test_in_combat_register_after_hit() {
+        use crate::vm::registers::Register;
+
+        let mut arena = Arena::new();
+        let center = Point { x: 0.5, y: 0.5 };
+        let target_pos = Point { x: 0.75, y: 0.5 };
+        let shooter = Robot::new(1, "Shooter".to_string(), center, center);
+        let target = Robot::new(2, "Target".to_string(), target_pos, center);
+        let mut robots = vec![shooter, target];
+        let start = Point {
+            x: target_pos.x - config::UNIT_SIZE,
+            y: target_pos.y,
+        };
+        arena.spawn_projectile(Projectile {
+            position: start,
+            prev_position: start,
+            direction: 0.0,
+            speed: 1.0,
+            power: 0.5,
+            base_damage: 10.0,
+            source_robot: 1,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
+        });
+
+        robots[1].update_vm_state_registers(&arena, 0.0);
+        assert_eq!(
+            robots[1]
+                .vm_state
+                .registers
+                .get(Register::InCombat)
+                .unwrap(),
+            0.0
+        );
+
+        arena.update_projectiles(
+            &mut robots,
+            &mut ParticleSystem::new(),
+            &AudioManager::new(),
+        );
+        for robot in robots.iter_mut() {
+            robot.update_vm_state_registers(&arena, 0.0);
+            assert_eq!(
+                robot.vm_state.registers.get(Register::InCombat).unwrap(),
+                1.0
+            );
+        }
+
+        // Decays once the recency window passes without further damage
+        let target = &mut robots[1];
+        target.vm_state.cycle = config::COMBAT_RECENCY_CYCLES - 1;
+        target.update_vm_state_registers(&arena, 0.0);
+        assert_eq!(
+            target.vm_state.registers.get(Register::InCombat).unwrap(),
+            1.0
+        );
+        target.vm_state.turn += config::COMBAT_RECENCY_CYCLES / config::CYCLES_PER_TURN + 1;
+        target.update_vm_state_registers(&arena, 0.0);
+        assert_eq!(
+            target.vm_state.registers.get(Register::InCombat).unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_destructible_obstacle_crumbles_after_enough_hits() {
+        let mut arena = Arena::new();
+        let obstacle_pos = arena.grid_to_world(10, 10);
+        arena.obstacles.push(Obstacle {
+            position: obstacle_pos,
+            health: None,
+            rubble: false,
+        });
+        arena.make_obstacles_destructible();
+        let mut particle_system = ParticleSystem::new();
+        let audio_manager = AudioManager::new();
+
+        let start_pos = Point {
+            x: obstacle_pos.x - config::UNIT_SIZE * 0.6,
+            y: obstacle_pos.y,
+        };
+        let shot = Projectile {
+            position: start_pos,
+            prev_position: start_pos,
+            direction: 0.0, // Moving right
+            speed: 1.0,
+            power: 1.0,
+            base_damage: 10.0,
+            source_robot: 0,
+            source_team: 0,
+            ballistic: None,
+            bounces_remaining: 0,
+            damage_type: DamageType::Kinetic,
+        };
+        let hits_needed = (config::OBSTACLE_HEALTH / 10.0).ceil() as usize;
+        for hit in 1..=hits_needed {
+            arena.spawn_projectile(shot);
+            arena.update_projectiles(&mut [], &mut particle_system, &audio_manager);
+            assert!(
+                arena.projectiles.is_empty(),
+                "Shot {} should stop at the obstacle",
+                hit
+            );
+            if hit < hits_needed {
+                assert_eq!(arena.obstacles.len(), 1, "Obstacle gone after {} hits", hit);
+            }
+        }
+        assert!(arena.obstacles.is_empty());
+        assert!(!arena.check_collision(obstacle_pos));
+        assert_eq!(arena.crumbled_obstacles, vec![obstacle_pos]);
+
+        // Rubble goes down to a single shot and leaves nothing behind
+        arena.crumbled_obstacles.clear();
+        arena.obstacles.push(Obstacle {
+            position: obstacle_pos,
+            health: Some(config::RUBBLE_HEALTH),
+            rubble: true,
+        });
+        arena.spawn_projectile(shot);
+        arena.update_projectiles(&mut [], &mut particle_system, &audio_manager);
+        assert!(arena.obstacles.is_empty());
+        assert!(arena.crumbled_obstacles.is_empty());
+
+        // Wreckage added once the mode is on can be shot down too
+        let wreck = Robot::new(9, "Wreck".to_string(), obstacle_pos, obstacle_pos);
+        arena.add_obstacle_at_robot(&wreck);
+        assert_eq!(arena.obstacles[0].health, Some(config::OBSTACLE_HEALTH));
+        arena.obstacles.clear();
+        let mut plain = Arena::new();
+        plain.add_obstacle_at_robot(&wreck);
+        assert_eq!(plain.obstacles[0].health, None);
+
+        // Without the mode obstacles stop shots forever
+        arena.obstacles.push(Obstacle {
+            position: obstacle_pos,
+            health: None,
+            rubble: false,
+        });
+        for _ in 0..hits_needed * 2 {
+            arena.spawn_projectile(shot);
+            arena.update_projectiles(&mut [], &mut particle_system, &audio_manager);
+        }
+        assert_eq!(arena.obstacles.len(), 1);
+    }
+
+    #[test]
+    fn test_projectile_bounces_off_wall() {
+        let mut arena = Arena::new();
+        let mut particle_system = ParticleSystem::new();
+        let audio_manager = AudioManager::new();
+        let start = Point {
+            x: arena.width - 0.002,
+            y: 0.5,
+        };
+        let mut shot = Projectile {
+            position: start,
+            prev_position: start,
+            direction: 45.0, // Towards the right wall
+            speed: 0.2,
+            power: 1.0,
+            base_damage: 10.0,
+            source_robot: 0,
+            source_team: 0,
+            ballistic: None,
+            damage_type: DamageType::Kinetic,
+            bounces_remaining: 1,
+        };
+        arena.spawn_projectile(shot);
+        arena.update_projectiles(&mut [], &mut particle_system, &audio_manager);
+
+        // Mirrored off the right wall: 45 degrees becomes 135, back into the arena
+        assert_eq!(arena.projectiles.len(), 1);
+        let bounced = arena.projectiles[0];
+        assert!((bounced.direction - 135.0).abs() < 1e-9);
+        assert_eq!(bounced.bounces_remaining, 0);
+        assert!(bounced.position.x <= arena.width);
+        assert!(bounced.position.y > 0.5);
+
+        // Out of bounces, the next wall ends it as before
+        shot.bounces_remaining = 0;
+        arena.projectiles = vec![shot];
+        arena.update_projectiles(&mut [], &mut particle_system, &audio_manager);
+        assert!(arena.projectiles.is_empty());
+    }
+
+    #[test]
+    fn test_bounced_projectile_still_hits_robots() {
+        let mut arena = Arena::new();
+        let mut particle_system = ParticleSystem::new();
+        let audio_manager = AudioManager::new();
+        let mut target = Robot::new(
+            2,
+            "Target".to_string(),
+            Point { x: 0.5, y: 0.4 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        target.status = RobotStatus::Active;
+        let mut robots = vec![target];
+        let start = Point { x: 0.5, y: 0.2 };
+        arena.spawn_projectile(Projectile {
+            position: start,
+            prev_position: start,
+            direction: 270.0, // Up, away from the target, then back down off the top wall
+            speed: 0.2,
+            power: 1.0,
+            base_damage: 10.0,
+            source_robot: 1,
+            source_team: 0,
+            ballistic: None,
+            damage_type: DamageType::Kinetic,
+            bounces_remaining: 1,
+        });
+        for _ in 0..100 {
+            arena.update_projectiles(&mut robots, &mut particle_system, &audio_manager);
+            if arena.projectiles.is_empty() {
+                break;
+            }
+        }
+        assert!(arena.projectiles.is_empty());
+        assert!(robots[0].health < config::DEFAULT_INITIAL_HEALTH);
+    }
+
+    #[test]
+    fn test_load_ascii_map() {
+        let mut arena = Arena::new();
+        arena.load_map("#....\n..#..\n....#\n").unwrap();
+
+        assert_eq!((arena.grid_width, arena.grid_height), (5, 3));
+        assert!((arena.width - 5.0 * arena.unit_size).abs() < 1e-12);
+        assert!((arena.height - 3.0 * arena.unit_size).abs() < 1e-12);
+        let cells: Vec<_> = arena
+            .obstacles
+            .iter()
+            .map(|o| arena.world_to_grid(o.position))
+            .collect();
+        assert_eq!(cells, vec![(0, 0), (2, 1), (4, 2)]);
+        assert!(arena.check_collision(arena.grid_to_world(2, 1)));
+        assert!(!arena.check_collision(arena.grid_to_world(1, 1)));
+
+        assert!(arena.load_map("").is_err());
+        assert!(arena.load_map("#..\n.#").unwrap_err().contains("row 2"));
+        assert!(arena.load_map("#.x").unwrap_err().contains("'x'"));
+    }
+
+    #[test]
+    fn test_pickups_restore_health_and_energy() {
+        let mut arena = Arena::new();
+        let mut robot = Robot::new(
+            1,
+            "Collector".to_string(),
+            arena.grid_to_world(5, 5),
+            Point { x: 0.5, y: 0.5 },
+        );
+        robot.status = RobotStatus::Active;
+        robot.health = 50.0;
+        robot.power = 0.8;
+        let mut robots = vec![robot];
+        let near = Point {
+            x: robots[0].position.x + config::UNIT_SIZE * 0.5,
+            y: robots[0].position.y,
+        };
+        let far = arena.grid_to_world(15, 15);
+        arena.pickups = vec![
+            Pickup {
+                position: near,
+                kind: PickupKind::Health,
+            },
+            Pickup {
+                position: near,
+                kind: PickupKind::Energy,
+            },
+            Pickup {
+                position: far,
+                kind: PickupKind::Health,
+            },
+        ];
+
+        let collected = arena.collect_pickups(&mut robots);
+        assert_eq!(collected.len(), 2);
+        assert!(collected.iter().all(|(id, _)| *id == 1));
+        assert_eq!(robots[0].health, 50.0 + config::PICKUP_HEALTH);
+        assert_eq!(robots[0].power, 1.0); // Capped at full power
+        assert_eq!(arena.pickups.len(), 1);
+        assert_eq!(arena.pickups[0].position, far);
+
+        // Health never goes above the starting health
+        robots[0].position = far;
+        robots[0].health = config::DEFAULT_INITIAL_HEALTH - 1.0;
+        arena.collect_pickups(&mut robots);
+        assert_eq!(robots[0].health, config::DEFAULT_INITIAL_HEALTH);
+        assert!(arena.pickups.is_empty());
+
+        // Spawned pickups land in free cells
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(arena.spawn_pickup(PickupKind::Energy, &robots, &mut rng));
+        let cell = arena.world_to_grid(arena.pickups[0].position);
+        assert_ne!(cell, arena.world_to_grid(robots[0].position));
+    }
+
+    #[test]
+    fn test_shield_absorbs_projectile_damage() {
+        // Health lost by a robot hit by one 20 damage shot
+        let health_lost = |shielded: bool| {
+            let mut arena = Arena::new();
+            let mut target = Robot::new(
+                2,
+                "Target".to_string(),
+                Point { x: 0.5, y: 0.5 },
+                Point { x: 0.0, y: 0.5 },
+            );
+            target.status = RobotStatus::Active;
+            target.set_shield(shielded);
+            let mut robots = vec![target];
+            let start = Point { x: 0.49, y: 0.5 };
+            arena.spawn_projectile(Projectile {
+                position: start,
+                prev_position: start,
+                direction: 0.0,
+                speed: 0.2,
+                power: 1.0,
+                base_damage: 20.0,
+                source_robot: 1,
+                source_team: 0,
+                ballistic: None,
+                damage_type: DamageType::Kinetic,
+                bounces_remaining: 0,
+            });
+            arena.update_projectiles(
+                &mut robots,
+                &mut ParticleSystem::new(),
+                &AudioManager::new(),
+            );
+            assert!(arena.projectiles.is_empty());
+            (
+                config::DEFAULT_INITIAL_HEALTH - robots[0].health,
+                robots[0].shield,
+            )
+        };
+
+        let (unshielded_loss, _) = health_lost(false);
+        let (shielded_loss, shield) = health_lost(true);
+        assert_eq!(unshielded_loss, 20.0);
+        assert_eq!(shielded_loss, 0.0);
+        assert_eq!(shield.strength, config::SHIELD_CAPACITY - 20.0);
+
+        // A shield with less strength than the hit lets the rest through and drops
+        let mut robot = Robot::new(
+            1,
+            "R".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.0, y: 0.0 },
+        );
+        robot.shield.strength = 5.0;
+        robot.set_shield(true);
+        assert_eq!(robot.absorb_with_shield(20.0), 15.0);
+        assert!(!robot.shield.active);
+        robot.set_shield(true);
+        assert!(
+            !robot.shield.active,
+            "A depleted shield can't be turned back on"
+        );
+    }
+
+    #[test]
+    fn test_shield_absorbs_beams_and_blasts() {
+        let mut arena = Arena::new();
+        let audio = AudioManager::new();
+        let mut shooter = Robot::new(
+            1,
+            "Shooter".to_string(),
+            Point { x: 0.3, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        shooter.status = RobotStatus::Active;
+        let mut target = Robot::new(
+            2,
+            "Target".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.0, y: 0.5 },
+        );
+        target.status = RobotStatus::Active;
+        target.set_shield(true);
+        let mut robots = vec![shooter, target];
+
+        arena.fire_beam(
+            &mut robots,
+            1,
+            0,
+            Point { x: 0.3, y: 0.5 },
+            0.0,
+            1.0,
+            false,
+            &audio,
+        );
+        arena.mines.push(Mine {
+            position: Point { x: 0.5, y: 0.5 },
+            power: 0.5,
+            owner: 1,
+            team: 0,
+        });
+        arena.update_mines(&mut robots, &mut ParticleSystem::new(), &audio);
+
+        // Both hits land on the shield; nothing reaches health or counts as damage dealt
+        assert!(arena.mines.is_empty());
+        assert_eq!(robots[1].health, config::DEFAULT_INITIAL_HEALTH);
+        let absorbed = config::BEAM_DAMAGE + config::MINE_DAMAGE * 0.5;
+        assert!((robots[1].shield.strength - (config::SHIELD_CAPACITY - absorbed)).abs() < 1e-9);
+        assert_eq!(robots[0].damage_dealt, 0.0);
+    }
+
+    #[test]
+    fn test_mines_detonate_near_other_robots_but_not_their_owner() {
+        let mut arena = Arena::new();
+        let mut particles = ParticleSystem::new();
+        let audio = AudioManager::new();
+        let mut owner = Robot::new(
+            1,
+            "Owner".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.0, y: 0.0 },
+        );
+        owner.status = RobotStatus::Active;
+        let mut other = Robot::new(
+            2,
+            "Other".to_string(),
+            Point { x: 0.9, y: 0.9 },
+            Point { x: 0.0, y: 0.0 },
+        );
+        other.status = RobotStatus::Active;
+        let mut robots = vec![owner, other];
+        arena.mines.push(Mine {
+            position: Point { x: 0.5, y: 0.5 },
+            power: 1.0,
+            owner: 1,
+            team: 0,
+        });
+
+        // The owner sitting on its own mine doesn't set it off
+        arena.update_mines(&mut robots, &mut particles, &audio);
+        assert_eq!(arena.mines.len(), 1);
+        assert_eq!(robots[0].health, config::DEFAULT_INITIAL_HEALTH);
+
+        // Another robot wandering close does, and the owner is spared the blast
+        robots[1].position = Point {
+            x: 0.5 + config::MINE_TRIGGER_RADIUS * 0.5,
+            y: 0.5,
+        };
+        arena.update_mines(&mut robots, &mut particles, &audio);
+        assert!(arena.mines.is_empty());
+        assert_eq!(robots[0].health, config::DEFAULT_INITIAL_HEALTH);
+        let expected = config::MINE_DAMAGE
+            * (1.0 - config::MINE_TRIGGER_RADIUS * 0.5 / config::MINE_BLAST_RADIUS);
+        assert!((config::DEFAULT_INITIAL_HEALTH - robots[1].health - expected).abs() < 1e-9);
+        assert!((robots[0].damage_dealt - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shots_spare_teammates_but_hit_other_teams() {
+        // Robot 1 (on team 1) attacks robot 2 (on `target_team`) at the arena center
+        let matchup = |target_team: u8| {
+            let mut shooter = Robot::new(
+                1,
+                "Shooter".to_string(),
+                Point { x: 0.1, y: 0.1 },
+                Point { x: 0.0, y: 0.0 },
+            );
+            shooter.status = RobotStatus::Active;
+            shooter.team = 1;
+            let mut target = Robot::new(
+                2,
+                "Target".to_string(),
+                Point { x: 0.5, y: 0.5 },
+                Point { x: 0.0, y: 0.0 },
+            );
+            target.status = RobotStatus::Active;
+            target.team = target_team;
+            (Arena::new(), vec![shooter, target])
+        };
+
+        // Health the target has left after one shot from robot 1, fired before
+        // the shooter was destroyed and cleared away if `shooter_gone`
+        let health_after_shot = |target_team: u8, shooter_gone: bool| {
+            let (mut arena, mut robots) = matchup(target_team);
+            if shooter_gone {
+                robots.remove(0);
+            }
+            let start = Point { x: 0.49, y: 0.5 };
+            arena.spawn_projectile(Projectile {
+                position: start,
+                prev_position: start,
+                direction: 0.0,
+                speed: 0.2,
+                power: 1.0,
+                base_damage: 20.0,
+                source_robot: 1,
+                source_team: 1,
+                ballistic: None,
+                damage_type: DamageType::Kinetic,
+                bounces_remaining: 0,
+            });
+            arena.update_projectiles(
+                &mut robots,
+                &mut ParticleSystem::new(),
+                &AudioManager::new(),
+            );
+            robots.last().unwrap().health
+        };
+
+        assert_eq!(health_after_shot(1, false), config::DEFAULT_INITIAL_HEALTH);
+        assert_eq!(
+            health_after_shot(2, false),
+            config::DEFAULT_INITIAL_HEALTH - 20.0
+        );
+        // Robots without a team are fair game for everyone
+        assert_eq!(
+            health_after_shot(0, false),
+            config::DEFAULT_INITIAL_HEALTH - 20.0
+        );
+        // A shot still in flight when its shooter is gone keeps sparing the team
+        assert_eq!(health_after_shot(1, true), config::DEFAULT_INITIAL_HEALTH);
+        assert_eq!(
+            health_after_shot(2, true),
+            config::DEFAULT_INITIAL_HEALTH - 20.0
+        );
+
+        // Health the target has left after a mortar shell lands on it
+        let health_after_mortar = |target_team: u8| {
+            let (mut arena, mut robots) = matchup(target_team);
+            let landing = Point { x: 0.5, y: 0.5 };
+            arena.spawn_projectile(Projectile {
+                position: landing,
+                prev_position: landing,
+                direction: 0.0,
+                speed: 0.0,
+                power: 1.0,
+                base_damage: 10.0,
+                source_robot: 1,
+                source_team: 1,
+                ballistic: Some(Ballistic {
+                    height: 0.0,
+                    vertical_velocity: 0.0,
+                }),
+                damage_type: DamageType::Kinetic,
+                bounces_remaining: 0,
+            });
+            arena.update_projectiles(
+                &mut robots,
+                &mut ParticleSystem::new(),
+                &AudioManager::new(),
+            );
+            assert!(arena.projectiles.is_empty());
+            robots[1].health
+        };
+        assert_eq!(health_after_mortar(1), config::DEFAULT_INITIAL_HEALTH);
+        assert_eq!(
+            health_after_mortar(2),
+            config::DEFAULT_INITIAL_HEALTH - config::MORTAR_DAMAGE
+        );
+
+        // A mine robot 1 laid under the target: (mine still armed, target's health).
+        // Robot 3 on team 2 wanders over too when `enemy_nearby` is set.
+        let mine_outcome = |target_team: u8, enemy_nearby: bool| {
+            let (mut arena, mut robots) = matchup(target_team);
+            let mine_position = Point { x: 0.5, y: 0.5 };
+            if enemy_nearby {
+                let mut enemy = Robot::new(
+                    3,
+                    "Enemy".to_string(),
+                    mine_position,
+                    Point { x: 0.0, y: 0.0 },
+                );
+                enemy.status = RobotStatus::Active;
+                enemy.team = 2;
+                robots.push(enemy);
+            }
+            arena.mines.push(Mine {
+                position: mine_position,
+                power: 1.0,
+                owner: 1,
+                team: 1,
+            });
+            arena.update_mines(
+                &mut robots,
+                &mut ParticleSystem::new(),
+                &AudioManager::new(),
+            );
+            (!arena.mines.is_empty(), robots[1].health)
+        };
+        // A teammate doesn't set it off, and isn't hurt when an enemy does
+        assert_eq!(
+            mine_outcome(1, false),
+            (true, config::DEFAULT_INITIAL_HEALTH)
+        );
+        assert_eq!(
+            mine_outcome(1, true),
+            (false, config::DEFAULT_INITIAL_HEALTH)
+        );
+        assert_eq!(
+            mine_outcome(2, false),
+            (false, config::DEFAULT_INITIAL_HEALTH - config::MINE_DAMAGE)
+        );
+    }
+
+    #[test]
+    fn test_robot_grid_matches_brute_force_collisions() {
+        let center = Point { x: 0.5, y: 0.5 };
+        let crowd = || {
+            (0..16u32)
+                .map(|id| {
+                    let position = Point {
+                        x: 0.1 + (id % 4) as f64 * 0.25,
+                        y: 0.1 + (id / 4) as f64 * 0.25,
+                    };
+                    let mut robot =
+                        Robot::new(id + 1, format!("Robot{}", id + 1), position, center);
+                    robot.status = RobotStatus::Active;
+                    robot.radius = config::DEFAULT_ROBOT_RADIUS * (1.0 + (id % 3) as f64);
+                    robot
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut rng = StdRng::seed_from_u64(42);
+        let shots: Vec<Projectile> = (0..400)
+            .map(|_| {
+                let position = Point {
+                    x: rng.gen_range(0.0..1.0),
+                    y: rng.gen_range(0.0..1.0),
+                };
+                Projectile {
+                    position,
+                    prev_position: position,
+                    direction: rng.gen_range(0.0..360.0),
+                    speed: rng.gen_range(0.5..3.0),
+                    power: 1.0,
+                    base_damage: 1.0,
+                    source_robot: rng.gen_range(0..=16),
+                    source_team: 0,
+                    ballistic: None,
+                    bounces_remaining: 1,
+                    damage_type: DamageType::Kinetic,
+                }
+            })
+            .collect();
+
+        let mut particle_system = ParticleSystem::new();
+        let audio_manager = AudioManager::new();
+        let (mut grid_arena, mut naive_arena) = (Arena::new(), Arena::new());
+        grid_arena.projectiles = shots.clone();
+        naive_arena.projectiles = shots;
+        let (mut grid_robots, mut naive_robots) = (crowd(), crowd());
+        for _ in 0..20 {
+            grid_arena.update_projectiles(&mut grid_robots, &mut particle_system, &audio_manager);
+            // A single cell holding every robot is the brute-force scan
+            let everyone = RobotGrid::new(&naive_robots, f64::INFINITY);
+            naive_arena.update_projectiles_with_grid(
+                &mut naive_robots,
+                &everyone,
+                &mut particle_system,
+                &audio_manager,
+            );
+        }
+
+        let hits: f64 = grid_robots.iter().map(|robot| robot.damage_dealt).sum();
+        assert!(hits > 0.0, "the crowd should take some hits");
+        for (grid_robot, naive_robot) in grid_robots.iter().zip(&naive_robots) {
+            assert_eq!(grid_robot.health, naive_robot.health);
+            assert_eq!(grid_robot.damage_dealt, naive_robot.damage_dealt);
+            assert_eq!(grid_robot.status, naive_robot.status);
+        }
+        assert_eq!(grid_arena.projectiles.len(), naive_arena.projectiles.len());
+        for (grid_shot, naive_shot) in grid_arena.projectiles.iter().zip(&naive_arena.projectiles) {
+            assert_eq!(grid_shot.position, naive_shot.position);
+        }
+    }
 }
@@ -1,14 +1,28 @@
 use crate::assets::get_asset_bytes;
 use log::warn;
 use macroquad::audio::load_sound_from_bytes;
-use macroquad::audio::{Sound, play_sound_once};
+use macroquad::audio::{PlaySoundParams, Sound, play_sound};
 
-#[derive(Default)]
 pub struct AudioManager {
     fire_sound: Option<Sound>,
     bothit_sound: Option<Sound>,
     death_sound: Option<Sound>,
     wallhit_sound: Option<Sound>,
+    volume: f32, // Playback volume, 0.0 (silent) to 1.0 (full)
+    muted: bool, // Silenced with the M key; the volume is kept for unmuting
+}
+
+impl Default for AudioManager {
+    fn default() -> Self {
+        AudioManager {
+            fire_sound: None,
+            bothit_sound: None,
+            death_sound: None,
+            wallhit_sound: None,
+            volume: 1.0,
+            muted: false,
+        }
+    }
 }
 
 impl AudioManager {
@@ -16,6 +30,38 @@ impl AudioManager {
         Default::default()
     }
 
+    /// Sets the volume every sound plays at, clamped to 0.0..=1.0
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// Mutes or unmutes all sounds. Returns whether audio is now muted.
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.muted
+    }
+
+    // Volume sounds actually play at right now
+    fn effective_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+
+    // Plays a sound once at the current volume, if it is loaded and audible
+    fn play(&self, sound: &Option<Sound>) {
+        let volume = self.effective_volume();
+        if let Some(sound) = sound
+            && volume > 0.0
+        {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume,
+                },
+            );
+        }
+    }
+
     // Load all required sound assets
     pub async fn load_assets(&mut self) {
         self.fire_sound = match get_asset_bytes("fire1.ogg") {
@@ -53,28 +99,48 @@ impl AudioManager {
 
     // Play the fire sound if loaded
     pub fn play_fire(&self) {
-        if let Some(ref sound) = self.fire_sound {
-            play_sound_once(sound);
-        }
+        self.play(&self.fire_sound);
     }
 
     // Play the hit sound if loaded
     pub fn play_bothit(&self) {
-        if let Some(ref sound) = self.bothit_sound {
-            play_sound_once(sound);
-        }
+        self.play(&self.bothit_sound);
     }
 
     // Play the death sound if loaded
     pub fn play_death(&self) {
-        if let Some(ref sound) = self.death_sound {
-            play_sound_once(sound);
-        }
+        self.play(&self.death_sound);
     }
 
     pub fn play_wallhit(&self) {
-        if let Some(ref sound) = self.wallhit_sound {
-            play_sound_once(sound);
-        }
+        self.play(&self.wallhit_sound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silent_playback_and_mute_round_trip() {
+        let mut audio = AudioManager::new();
+        assert_eq!(audio.effective_volume(), 1.0);
+
+        audio.set_volume(0.0);
+        audio.play_fire();
+        audio.play_bothit();
+        audio.play_death();
+        audio.play_wallhit();
+        assert_eq!(audio.effective_volume(), 0.0);
+
+        audio.set_volume(0.4);
+        assert!(audio.toggle_mute());
+        assert_eq!(audio.effective_volume(), 0.0);
+        assert!(!audio.toggle_mute());
+        assert_eq!(audio.effective_volume(), 0.4);
+
+        // Out-of-range volumes are clamped
+        audio.set_volume(3.0);
+        assert_eq!(audio.effective_volume(), 1.0);
     }
 }
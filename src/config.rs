@@ -1,12 +1,22 @@
 //! Configuration constants for the robot arena game.
 
+use crate::types::DamageType;
+
 // Arena and movement
 pub const UNIT_SIZE: f64 = 0.05; // 1 unit = 5% of arena width/height
 pub const POWER_REGEN_RATE: f64 = 0.01; // Power units regenerated per cycle (1.0 per turn @ 100 cycles/turn)
 pub const ARENA_WIDTH_UNITS: u32 = 20; // Default arena width in grid units
 pub const ARENA_HEIGHT_UNITS: u32 = 20; // Default arena height in grid units
 pub const OBSTACLE_DENSITY: f32 = 0.01; // Default density of obstacles (1%)
+pub const OBSTACLE_HEALTH: f64 = 50.0; // Health of each obstacle with --destructible-obstacles (five full-power shots)
+pub const RUBBLE_PIECES: usize = 2; // Obstacles dropped around a destroyed obstacle's cell
+pub const RUBBLE_HEALTH: f64 = 10.0; // Health of each rubble piece (one full-power shot); rubble leaves no rubble
+pub const DISTANCE_CACHE_EPSILON: f64 = 1e-9; // Move/turn beyond which a robot's cached wall distances are recomputed
 pub const SCAN_DISTANCE: f64 = 1.0; // Maximum distance for robot scanning (10 grid units)
+pub const SCAN_ENERGY_COST: f64 = 0.02; // Power drawn by each successful `scan`
+pub const SWEEP_POWER_COST: f64 = 0.08; // Power drawn by each successful `sweep` (all-around scan)
+pub const SCAN_DISTANCE_NOISE: f64 = 0.02; // Std dev of the scanned distance error per unit of distance
+pub const SCAN_DIRECTION_NOISE: f64 = 2.0; // Std dev (degrees) of the scanned direction error per unit of distance
 
 // Rendering configuration
 pub const WINDOW_WIDTH: i32 = 1000; // Increased width for UI panel
@@ -14,25 +24,88 @@ pub const WINDOW_HEIGHT: i32 = 800;
 pub const UI_PANEL_WIDTH: i32 = 200; // Width of the side panel
 pub const ARENA_WIDTH: i32 = WINDOW_WIDTH - UI_PANEL_WIDTH; // Width for the arena rendering
 pub const ARENA_HEIGHT: i32 = WINDOW_HEIGHT; // Arena uses full height
+pub const ARENA_MIN_ZOOM: f32 = 1.0; // Fully zoomed out shows the whole arena
+pub const ARENA_MAX_ZOOM: f32 = 8.0; // Closest arena zoom
+pub const ARENA_ZOOM_STEP: f32 = 1.15; // Zoom factor per mouse wheel notch
+pub const ARENA_PAN_SPEED: f32 = 600.0; // Arrow key pan speed in screen pixels per second
+pub const PROJECTILE_TRAIL_SEGMENTS: usize = 4; // Cycles of travel drawn as a fading trail behind each projectile
 
 // Scanner configuration
 pub const DEFAULT_SCANNER_FOV: f64 = 22.5; // +/- 11.25 degrees from center
 pub const DEFAULT_SCANNER_RANGE: f64 = 1.414; // Maximum arena diagonal (1.0 width + 1.0 height)
+pub const SCANNER_MIN_FOV: f64 = 5.0; // Narrowest field of view a .scanner directive may ask for (degrees)
+pub const SCANNER_MAX_FOV: f64 = 90.0; // Widest field of view a .scanner directive may ask for (degrees)
+pub const SCANNER_MIN_RANGE: f64 = 0.1; // Shortest range a .scanner directive may ask for
+pub const SCANNER_MAX_RANGE: f64 = DEFAULT_SCANNER_RANGE; // Longest range a .scanner directive may ask for
+pub const SCANNER_COVERAGE: f64 = DEFAULT_SCANNER_FOV * DEFAULT_SCANNER_RANGE; // Max fov x range: wider scanners reach less far
 
 // Ranged weapon configuration
 pub const DEFAULT_RANGED_DAMAGE: f64 = 10.0; // Base damage before power/distance scaling
 pub const DEFAULT_PROJECTILE_SPEED: f64 = 0.2; // Units per cycle
+pub const WEAPON_COOLDOWN_CYCLES: u32 = 10; // Cycles after a shot before the weapon fires again
+pub const DEFAULT_DAMAGE_TYPE: DamageType = DamageType::Kinetic; // Damage type of a robot's ranged weapon
+pub const DEFAULT_ROBOT_ARMOR: f64 = 0.0; // Flat reduction of each kinetic hit (0 = kinetic hits land in full)
+pub const SHIELD_CAPACITY: f64 = 30.0; // Damage a robot's shield absorbs before it's depleted (it does not recharge)
+pub const SHIELD_POWER_DRAIN: f64 = 0.02; // Power drawn each cycle while the shield is on (regen is 0.01)
+
+// Mortar configuration (arena units and cycles)
+pub const MORTAR_SPEED: f64 = 0.2; // Horizontal speed, in the same units as projectile speed
+pub const MORTAR_LAUNCH_VELOCITY: f64 = 0.01; // Vertical launch velocity at full power (range scales with power)
+pub const MORTAR_GRAVITY: f64 = 0.0005; // Vertical velocity lost per cycle
+pub const MORTAR_BLAST_RADIUS: f64 = UNIT_SIZE * 2.0; // Robots within this distance of the landing point take damage
+pub const MORTAR_DAMAGE: f64 = 20.0; // Damage at the landing point with full power, falling off linearly to 0 at the radius
+
+// Beam weapon configuration
+pub const BEAM_DAMAGE: f64 = 6.0; // Damage at full beam power
+pub const BEAM_POWER_COST: f64 = 0.75; // Power drawn per unit of beam power (less damage per power than a shell, but it can't miss)
+pub const BEAM_FALLOFF: f64 = 0.5; // Fraction of laser weapon damage lost per arena unit between the shooter and the target
+
+// Mine configuration
+pub const MINE_TRIGGER_RADIUS: f64 = UNIT_SIZE; // Another robot this close to a mine sets it off
+pub const MINE_BLAST_RADIUS: f64 = UNIT_SIZE * 2.0; // Robots within this distance of the mine take damage
+pub const MINE_DAMAGE: f64 = 25.0; // Damage at the mine with full power, falling off linearly to 0 at the radius
+
+// Self-destruct configuration
+pub const DETONATION_RADIUS: f64 = UNIT_SIZE * 4.0; // Robots within this distance of a detonation take damage
+pub const DETONATION_DAMAGE: f64 = 100.0; // Damage at point blank with full power, falling off linearly to 0 at the radius
 pub const PROJECTILE_SUB_STEPS: u32 = 1; // Number of steps for projectile collision checks per cycle
+pub const PROJECTILE_MAX_BOUNCES: u32 = 3; // Most wall bounces a single `fire` can ask for
 
 // Game rules
 pub const CYCLES_PER_TURN: u32 = 100; // Default simulation cycles per turn
 pub const DEFAULT_INITIAL_HEALTH: f64 = 100.0;
 pub const DEFAULT_INITIAL_POWER: f64 = 1.0;
+pub const COMBAT_RECENCY_CYCLES: u32 = 200; // @in_combat stays set this many cycles after dealing or taking damage
+pub const DEFAULT_ROBOT_RADIUS: f64 = UNIT_SIZE / 2.0; // Collision radius of a standard robot
+pub const RESPAWN_DELAY_CYCLES: u32 = CYCLES_PER_TURN * 3; // With --respawn, cycles a destroyed robot waits before returning
+pub const OVERTIME_TURNS: u32 = 100; // With --overtime, the final turns in which damage is boosted
+pub const OVERTIME_DAMAGE_GROWTH: f64 = 0.05; // Extra damage multiplier gained per turn into overtime
+pub const PICKUP_SPAWN_INTERVAL_CYCLES: u32 = CYCLES_PER_TURN * 5; // With --pickups, cycles between pickup drops
+pub const PICKUP_MAX: usize = 3; // Most pickups lying in the arena at once
+pub const PICKUP_HEALTH: f64 = 25.0; // Health restored by a health pickup (capped at DEFAULT_INITIAL_HEALTH)
+pub const PICKUP_ENERGY: f64 = 0.5; // Power restored by an energy pickup (capped at 1.0)
+
+// @score register weights (score = health*H + damage_dealt*D + kills*K + alive_cycles*A)
+pub const SCORE_WEIGHT_HEALTH: f64 = 1.0;
+pub const SCORE_WEIGHT_DAMAGE: f64 = 1.0;
+pub const SCORE_WEIGHT_KILLS: f64 = 50.0;
+pub const SCORE_WEIGHT_ALIVE: f64 = 0.01;
 
 // Robot Physics/Movement Configuration
 pub const MAX_DRIVE_UNITS_PER_TURN: f64 = 5.0;
 pub const DRIVE_VELOCITY_FACTOR: f64 = UNIT_SIZE / CYCLES_PER_TURN as f64;
+pub const EXPLOSION_PUSH_RADIUS: f64 = UNIT_SIZE * 3.0; // Robots within this distance of a destroyed robot get shoved
+pub const EXPLOSION_PUSH_DISTANCE: f64 = UNIT_SIZE * 1.5; // Shove distance at point blank, falling off linearly to 0 at the radius
+pub const COLLISION_DAMAGE: f64 = 2.0; // Damage each robot takes when two robots run into each other
+pub const VELOCITY_DEADZONE: f64 = 1e-6; // Drive velocities (coordinate units/cycle) below this snap to 0
+pub const ROTATION_DEADZONE: f64 = 1e-3; // Rotation requests (degrees) below this are ignored
 pub const MAX_ROTATION_PER_CYCLE: f64 = 90.0 / CYCLES_PER_TURN as f64; // Degrees/cycle (scaled automatically, e.g., 3.6 deg/cycle for 100 cycles/turn)
+pub const SPEED_TURN_MIN_FACTOR: f64 = 0.25; // With --speed-turn-coupling, fraction of the drive turn rate left at full speed
+pub const SPEED_TURN_CURVE_EXPONENT: f64 = 1.0; // Shape of the falloff from 0 to full speed (1.0 = linear, >1.0 = gentler at low speed)
+
+// Debug console
+pub const DEBUG_CONSOLE_LINES: usize = 12; // Most recent `dbg` lines kept for the on-screen console
+pub const EVENT_STREAM_FLUSH_CYCLES: u32 = 10; // Cycles between flushes of the --event-stream file
 
 // VM configuration
 pub const MAX_CALL_STACK_SIZE: usize = 10; // Maximum depth of the call stack for subroutines
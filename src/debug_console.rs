@@ -0,0 +1,78 @@
+// On-screen debug console: a fixed-size ring buffer of `dbg` instruction output
+// collected by the game loop and drawn by the renderer when toggled on
+
+use std::collections::VecDeque;
+
+// A single `dbg` emission
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugLine {
+    pub robot_id: u32,
+    pub turn: u32,
+    pub cycle: u32,
+    pub value: f64,
+}
+
+#[derive(Debug)]
+pub struct DebugConsole {
+    lines: VecDeque<DebugLine>,
+    capacity: usize,
+    pub visible: bool,
+}
+
+impl DebugConsole {
+    pub fn new(capacity: usize) -> Self {
+        DebugConsole {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            visible: false,
+        }
+    }
+
+    /// Appends a line, evicting the oldest once the console is full
+    pub fn push(&mut self, line: DebugLine) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Lines currently held, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &DebugLine> {
+        self.lines.iter()
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(value: f64) -> DebugLine {
+        DebugLine {
+            robot_id: 1,
+            turn: 1,
+            cycle: 0,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_console_evicts_oldest_at_capacity() {
+        let mut console = DebugConsole::new(3);
+        for v in 0..5 {
+            console.push(line(v as f64));
+        }
+        let values: Vec<f64> = console.lines().map(|l| l.value).collect();
+        assert_eq!(values, vec![2.0, 3.0, 4.0]);
+
+        let mut empty = DebugConsole::new(0);
+        empty.push(line(1.0));
+        assert_eq!(empty.lines().count(), 0);
+    }
+}
@@ -0,0 +1,123 @@
+// Match event stream: one JSON object per line for external visualizers (--event-stream)
+
+use crate::config;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+
+/// Something that happened in the match worth showing on a live dashboard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    ProjectileSpawned {
+        robot_id: u32,
+        x: f64,
+        y: f64,
+        direction: f64,
+        power: f64,
+    },
+    RobotHit {
+        robot_id: u32,
+        source_robot: Option<u32>, // None for damage nobody is credited with (collisions)
+        damage: f64,
+        health: f64, // Health left after the hit
+    },
+    RobotDestroyed {
+        robot_id: u32,
+        source_robot: Option<u32>,
+    },
+    ScanPerformed {
+        robot_id: u32,
+        target_id: Option<u32>, // None when the scan found nothing
+        distance: f64,
+        direction: f64,
+    },
+}
+
+/// An event stamped with the turn and cycle it happened in; this is the shape
+/// of one line in the stream
+#[derive(Serialize)]
+struct StampedEvent<'a> {
+    turn: u32,
+    cycle: u32,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Appends events to a file as JSON lines. Output is flushed every
+/// EVENT_STREAM_FLUSH_CYCLES cycles so a tailing reader keeps up with the match.
+#[derive(Debug)]
+pub struct EventStream {
+    writer: BufWriter<File>,
+    cycles_since_flush: u32,
+}
+
+impl EventStream {
+    /// Opens `path` for appending, creating it if needed
+    pub fn open(path: &str) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventStream {
+            writer: BufWriter::new(file),
+            cycles_since_flush: 0,
+        })
+    }
+
+    pub fn write(&mut self, turn: u32, cycle: u32, event: &Event) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &StampedEvent { turn, cycle, event })?;
+        writeln!(self.writer)
+    }
+
+    /// Called once per simulation cycle; flushes when the interval is up
+    pub fn end_cycle(&mut self) -> io::Result<()> {
+        self.cycles_since_flush += 1;
+        if self.cycles_since_flush >= config::EVENT_STREAM_FLUSH_CYCLES {
+            self.cycles_since_flush = 0;
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.cycles_since_flush = 0;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(turn: u32, cycle: u32, event: &Event) -> String {
+        serde_json::to_string(&StampedEvent { turn, cycle, event }).unwrap()
+    }
+
+    #[test]
+    fn test_event_json_lines() {
+        let hit = Event::RobotHit {
+            robot_id: 2,
+            source_robot: Some(1),
+            damage: 5.0,
+            health: 95.0,
+        };
+        assert_eq!(
+            line(3, 41, &hit),
+            "{\"turn\":3,\"cycle\":41,\"event\":\"robot_hit\",\"robot_id\":2,\"source_robot\":1,\"damage\":5.0,\"health\":95.0}"
+        );
+        let miss = Event::ScanPerformed {
+            robot_id: 4,
+            target_id: None,
+            distance: 0.0,
+            direction: 0.0,
+        };
+        assert!(line(0, 0, &miss).contains("\"target_id\":null"));
+
+        // A scan that found nothing can report an infinite distance
+        let far = Event::ScanPerformed {
+            robot_id: 4,
+            target_id: None,
+            distance: f64::INFINITY,
+            direction: f64::NAN,
+        };
+        assert!(line(0, 0, &far).ends_with("\"distance\":null,\"direction\":null}"));
+    }
+}
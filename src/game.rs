@@ -1,16 +1,173 @@
+use crate::action_log::{ActionLog, LoggedCommand};
 use crate::arena::Arena;
 use crate::audio::AudioManager;
 use crate::config;
+use crate::debug_console::{DebugConsole, DebugLine};
+use crate::events::{Event, EventStream};
 use crate::particles::ParticleSystem;
-use crate::render::Renderer;
+use crate::render::{ArenaView, Renderer, pickup_color};
+use crate::replay::{ReplayFrame, status_name};
 use crate::robot::{Robot, RobotStatus};
-use crate::types::{ArenaCommand, Point};
+use crate::types::{ArenaCommand, PickupKind, Point, WeaponKind};
+use crate::vm::parser::{SourceLine, parse_register};
+use crate::vm::registers::Register;
 use log::{error, info};
-use macroquad::prelude::{Vec2, get_frame_time, next_frame};
+use macroquad::prelude::{KeyCode, Vec2, get_frame_time, is_key_pressed, next_frame};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use rayon::prelude::*;
+use serde::{Serialize, Serializer};
+use serde_json::json;
 use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::Path;
 use std::process;
+use std::str::FromStr;
+
+/// How robots are oriented when the match starts
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum InitialHeading {
+    /// Face the center of the arena
+    #[default]
+    Center,
+    /// Explicit heading in degrees per robot (robots without an entry face the center)
+    Fixed(Vec<f64>),
+    /// Random headings drawn from a seeded RNG (None: derived from the match seed)
+    Random(Option<u64>),
+}
+
+/// Initial register values for one robot, applied right after its program loads.
+/// Parsed from `id:@reg=value,@reg=value`, e.g. `1:@d0=5,@d1=10`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterPreset {
+    pub robot_id: u32,
+    pub values: Vec<(Register, f64)>,
+}
+
+impl FromStr for RegisterPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, assignments) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected id:@reg=value,..., got '{}'", s))?;
+        let robot_id = id
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("invalid robot id '{}'", id))?;
+
+        let mut values = Vec::new();
+        for assignment in assignments.split(',') {
+            let (name, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| format!("expected @reg=value, got '{}'", assignment))?;
+            let name = name.trim();
+            let register = parse_register(Some(&name), &HashMap::new(), SourceLine::new(0, name))
+                .map_err(|e| e.message)?;
+            if !register.is_writable() {
+                return Err(format!("register {} is read-only", name));
+            }
+            let value = value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid value '{}' for {}", value, name))?;
+            values.push((register, value));
+        }
+        Ok(RegisterPreset { robot_id, values })
+    }
+}
+
+/// Robot programs that play together on one team.
+/// Parsed from `team:file,file`, e.g. `1:a.rasm,b.rasm`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamSpec {
+    pub team: u8,
+    pub files: Vec<String>,
+}
+
+impl FromStr for TeamSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (team, files) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected team:file,..., got '{}'", s))?;
+        let team = match team.trim().parse::<u8>() {
+            Ok(0) | Err(_) => return Err(format!("invalid team '{}' (expected 1-255)", team)),
+            Ok(team) => team,
+        };
+        let files: Vec<String> = files
+            .split(',')
+            .map(|file| file.trim().to_string())
+            .collect();
+        if files.iter().any(|file| file.is_empty()) {
+            return Err(format!("empty robot file in team '{}'", s));
+        }
+        Ok(TeamSpec { team, files })
+    }
+}
+
+/// Final (or current) standing of a single robot in a match. Serializes as the
+/// tournament tooling expects: status and turns survived rather than the raw fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RobotResult {
+    pub id: u32,
+    pub name: String,
+    #[serde(skip)]
+    pub source: String, // Program file the robot was loaded from
+    #[serde(skip)]
+    pub team: u8, // 0 = no team
+    #[serde(rename = "turns_survived", serialize_with = "serialize_turns")]
+    pub alive_cycles: u32, // Cycles survived before being destroyed (or until now)
+    #[serde(rename = "status", serialize_with = "serialize_status")]
+    pub destroyed: bool,
+    pub health: f64,
+    pub shots_fired: u32,
+    pub damage_dealt: f64,
+}
+
+impl RobotResult {
+    fn from_robot(robot: &Robot, destroyed: bool) -> Self {
+        RobotResult {
+            id: robot.id,
+            name: robot.name.clone(),
+            source: robot.source.clone(),
+            team: robot.team,
+            alive_cycles: robot.alive_cycles,
+            destroyed,
+            health: robot.health,
+            shots_fired: robot.shots_fired,
+            damage_dealt: robot.damage_dealt,
+        }
+    }
+}
+
+fn serialize_turns<S: Serializer>(alive_cycles: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u32(alive_cycles / config::CYCLES_PER_TURN)
+}
+
+fn serialize_status<S: Serializer>(destroyed: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if *destroyed { "Destroyed" } else { "Active" })
+}
+
+/// Summary of a match, used for scoring and analytics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchResult {
+    pub winner: Option<u32>,
+    pub winning_team: Option<u8>, // Set when the match ended with only one team's robots alive
+    pub total_cycles: u32,
+    pub robots: Vec<RobotResult>, // Sorted by robot id
+}
+
+impl MatchResult {
+    /// Serializes the result for tournament tooling: the winner and winning team (null
+    /// for a draw) and each robot's final health, status, turns survived, shots and damage
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).expect("match results always serialize");
+        json.push('\n');
+        json
+    }
+}
 
 /// The Game struct encapsulates the state and logic for running the bot arena simulation
 pub struct Game {
@@ -21,19 +178,108 @@ pub struct Game {
     pub current_turn: u32,
     pub current_cycle: u32,
     pub max_turns: u32,
+    pub trace_robot: Option<u32>, // Robot id to restrict instruction tracing to (None = all)
+    pub replay: Option<Vec<ReplayFrame>>, // Recorded frames when replay recording is enabled
+    pub total_cycles: u32,        // Simulation cycles elapsed in the match
+    pub explosions_push: bool,    // Destroyed robots shove nearby robots away
+    pub despawn_dead_shots: bool, // Destroyed robots' in-flight projectiles fizzle out
+    pub overtime: bool,           // Damage grows through the final OVERTIME_TURNS turns
+    pub respawn: bool, // Destroyed robots return after RESPAWN_DELAY_CYCLES; the match runs to max_turns
+    pub pickups: bool, // Health and energy pickups drop every PICKUP_SPAWN_INTERVAL_CYCLES
+    pub rubble: bool,  // Destroyed obstacles drop rubble into neighbouring cells
+    pub parallel: bool, // Run the robots' VM cycles on the rayon thread pool
+    pub debug_console: DebugConsole, // Recent `dbg` output, toggled on screen with `~`
+    pub sim_paused: bool, // Simulation frozen with Space; `.` steps a single cycle
+    pub debug_focus: usize, // Index of the robot shown in the pause overlay, cycled with Tab
+    pub action_log: Option<ActionLog>, // Hashed per-turn command log, when enabled
+    pub event_stream: Option<EventStream>, // JSON-lines event feed for external visualizers
+    pub rng: StdRng,   // Match RNG behind obstacle placement and @rand
+    coin_seed: u64,    // Seed behind every robot's @coin, including robots loaded later
+    random_seed: u64,  // Seed behind every robot's rand_normal and sensor noise
+    replay_commands: Option<VecDeque<LoggedCommand>>, // Logged commands the arena takes instead of the robots' own
+    eliminated: Vec<RobotResult>,                     // Results of robots already removed from play
+    respawn_templates: Vec<Robot>, // Robots as they started the match, restored on respawn
+    respawning: Vec<(Robot, u32)>, // Destroyed robots waiting to respawn, with cycles left
     time_accumulator: f32,
     cycle_duration: f32,
     game_over: bool,
     winner: Option<u32>,
+    winning_team: Option<u8>, // Team whose members were the only robots left standing
+}
+
+// Random streams derived from the match seed (see `stream_seed`)
+const COIN_STREAM: u64 = 1;
+const RANDOM_STREAM: u64 = 2;
+const HEADING_STREAM: u64 = 3;
+
+// Seed for one of the match's random streams, derived from the match seed so a single
+// --seed reproduces obstacles, @rand, @coin, rand_normal, randrange, noise and headings
+fn stream_seed(seed: u64, stream: u64) -> u64 {
+    StdRng::seed_from_u64(seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_u64()
+}
+
+// Starting position of the robot in slot `index` (0-3), one per corner of the arena
+fn start_position(arena: &Arena, index: usize) -> Point {
+    let offset = 2.0 * arena.unit_size;
+    let positions = [
+        Point {
+            x: offset,
+            y: offset,
+        }, // Top-left  (Index 0)
+        Point {
+            x: arena.width - offset,
+            y: arena.height - offset,
+        }, // Bottom-right (Index 1 - was 2)
+        Point {
+            x: arena.width - offset,
+            y: offset,
+        }, // Top-right (Index 2 - was 1)
+        Point {
+            x: offset,
+            y: arena.height - offset,
+        }, // Bottom-left (Index 3)
+    ];
+    positions[index]
+}
+
+// Heading in degrees from `from` toward `to`, in [0, 360) like robot headings
+fn heading_toward(from: Point, to: Point) -> f64 {
+    (to.y - from.y)
+        .atan2(to.x - from.x)
+        .to_degrees()
+        .rem_euclid(360.0)
+}
+
+/// Built-in constants available to every robot program in the given arena
+pub fn predefined_constants(arena: &Arena) -> HashMap<String, f64> {
+    let mut constants = HashMap::new();
+    constants.insert("ARENA_WIDTH".to_string(), arena.grid_width as f64);
+    constants.insert("ARENA_HEIGHT".to_string(), arena.grid_height as f64);
+    constants
 }
 
 impl Game {
-    /// Create a new game instance with the provided robot files and audio manager
+    /// Create a new game instance with the provided robot files and audio manager.
+    /// Every random stream in the match is derived from `seed`; without one, each
+    /// match plays out differently.
     pub fn new(
         robot_files: &[String],
         max_turns: u32,
+        initial_heading: &InitialHeading,
+        presets: &[RegisterPreset],
+        seed: Option<u64>,
         audio_manager: AudioManager,
     ) -> Result<Self, Box<dyn std::error::Error>> {
+        // Turn/cycle counts are used as divisors in the UI and movement math
+        if max_turns < 1 {
+            return Err("max_turns must be at least 1".into());
+        }
+        if config::CYCLES_PER_TURN < 1 {
+            return Err("CYCLES_PER_TURN must be at least 1".into());
+        }
+
+        let seed = seed.unwrap_or_else(rand::random);
+
         // Create arena
         let arena = Arena::new();
         info!(
@@ -41,51 +287,71 @@ impl Game {
             arena.grid_width, arena.grid_height
         );
 
-        // Create predefined constants for robot programs
-        let mut predefined_constants = HashMap::new();
-        predefined_constants.insert("ARENA_WIDTH".to_string(), arena.grid_width as f64);
-        predefined_constants.insert("ARENA_HEIGHT".to_string(), arena.grid_height as f64);
-
         // Check robot count
         let num_robots = robot_files.len();
         if num_robots > 4 {
             error!("Error: Maximum of 4 robots allowed.");
             process::exit(1);
         }
+        if let Some(preset) = presets
+            .iter()
+            .find(|p| p.robot_id < 1 || p.robot_id as usize > num_robots)
+        {
+            return Err(format!("register preset for unknown robot id {}", preset.robot_id).into());
+        }
 
-        // Load robots
-        let mut robots = Vec::with_capacity(num_robots);
         info!("Simulating for a maximum of {} turns.", max_turns);
 
-        // Define starting positions
-        let offset = 2.0 * config::UNIT_SIZE;
-        let positions = [
-            Point {
-                x: offset,
-                y: offset,
-            }, // Top-left  (Index 0)
-            Point {
-                x: 1.0 - offset,
-                y: 1.0 - offset,
-            }, // Bottom-right (Index 1 - was 2)
-            Point {
-                x: 1.0 - offset,
-                y: offset,
-            }, // Top-right (Index 2 - was 1)
-            Point {
-                x: offset,
-                y: 1.0 - offset,
-            }, // Bottom-left (Index 3)
-        ];
+        // Initialize particle system
+        let particle_system = ParticleSystem::new();
+        info!("Particle system initialized.");
+
+        let mut game = Game {
+            arena,
+            robots: Vec::with_capacity(num_robots),
+            particle_system,
+            audio_manager,
+            current_turn: 1,
+            current_cycle: 0,
+            max_turns,
+            time_accumulator: 0.0,
+            cycle_duration: 1.0 / config::CYCLES_PER_TURN as f32,
+            game_over: false,
+            winner: None,
+            winning_team: None,
+            trace_robot: None,
+            replay: None,
+            total_cycles: 0,
+            explosions_push: false,
+            despawn_dead_shots: false,
+            overtime: false,
+            respawn: false,
+            pickups: false,
+            rubble: false,
+            parallel: false,
+            debug_console: DebugConsole::new(config::DEBUG_CONSOLE_LINES),
+            sim_paused: false,
+            debug_focus: 0,
+            action_log: None,
+            replay_commands: None,
+            event_stream: None,
+            rng: StdRng::seed_from_u64(seed),
+            coin_seed: stream_seed(seed, COIN_STREAM),
+            random_seed: stream_seed(seed, RANDOM_STREAM),
+            eliminated: Vec::new(),
+            respawn_templates: Vec::new(),
+            respawning: Vec::new(),
+        };
 
         // Load robot programs
-        let center = Point {
-            x: arena.width / 2.0,
-            y: arena.height / 2.0,
-        }; // Calculate center
+        let mut heading_rng = match initial_heading {
+            InitialHeading::Random(heading_seed) => Some(StdRng::seed_from_u64(
+                heading_seed.unwrap_or_else(|| stream_seed(seed, HEADING_STREAM)),
+            )),
+            _ => None,
+        };
         for (i, filename) in robot_files.iter().enumerate() {
             let robot_id = (i + 1) as u32;
-            let position = positions[i];
 
             // Extract filename stem for the name
             let robot_name = Path::new(filename)
@@ -106,41 +372,30 @@ impl Game {
                 }
             };
 
-            // Parse the program using the predefined constants
-            match crate::vm::parser::parse_assembly(&program_content, Some(&predefined_constants)) {
-                Ok(parsed_program) => {
-                    let mut robot = Robot::new(robot_id, robot_name, position, center);
-                    robot.load_program(parsed_program);
-                    robots.push(robot);
+            let heading = match initial_heading {
+                InitialHeading::Center => None,
+                InitialHeading::Fixed(headings) => headings.get(i).copied(),
+                InitialHeading::Random(_) => {
+                    heading_rng.as_mut().map(|rng| rng.gen_range(0.0..360.0))
                 }
-                Err(e) => {
-                    error!(
-                        "Error parsing program for Robot {} (file: {}): Line {}, {}",
-                        robot_id, filename, e.line, e.message
-                    );
-                    process::exit(1);
+            };
+            if let Err(e) = game.load_robot(&robot_name, &program_content, heading) {
+                error!(
+                    "Error loading program for Robot {} (file: {}): {}",
+                    robot_id, filename, e
+                );
+                process::exit(1);
+            }
+            let robot = game.robots.last_mut().expect("robot was just loaded");
+            robot.source = filename.clone();
+            for preset in presets.iter().filter(|p| p.robot_id == robot_id) {
+                for (register, value) in &preset.values {
+                    robot.vm_state.registers.set(*register, *value)?;
                 }
             }
         }
-        info!("Loaded {} robots.", robots.len());
-
-        // Initialize particle system
-        let particle_system = ParticleSystem::new();
-        info!("Particle system initialized.");
-
-        Ok(Game {
-            arena,
-            robots,
-            particle_system,
-            audio_manager,
-            current_turn: 1,
-            current_cycle: 0,
-            max_turns,
-            time_accumulator: 0.0,
-            cycle_duration: 1.0 / config::CYCLES_PER_TURN as f32,
-            game_over: false,
-            winner: None,
-        })
+        info!("Loaded {} robots.", game.robots.len());
+        Ok(game)
     }
 
     /// Run the main game loop using the provided renderer
@@ -149,6 +404,7 @@ impl Game {
 
         let mut announcement: Option<String> = None;
         let mut game_ended = false;
+        let mut view = ArenaView::default();
 
         while !Renderer::window_should_close()
             && self.current_turn <= self.max_turns
@@ -156,7 +412,39 @@ impl Game {
         {
             // Time accumulation
             let frame_time = get_frame_time();
-            self.time_accumulator += frame_time;
+            if is_key_pressed(KeyCode::GraveAccent) {
+                self.debug_console.toggle();
+            }
+            if is_key_pressed(KeyCode::Space) {
+                self.sim_paused = !self.sim_paused;
+            }
+            if is_key_pressed(KeyCode::G) {
+                renderer.set_glow_enabled(!renderer.glow_enabled());
+            }
+            if is_key_pressed(KeyCode::M) {
+                self.audio_manager.toggle_mute();
+            }
+            view.handle_input(frame_time);
+            // With the console open, number keys pause/resume that robot's VM
+            if self.debug_console.visible {
+                let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+                for (robot_id, key) in (1..).zip(keys) {
+                    if is_key_pressed(key) {
+                        self.toggle_vm_pause(robot_id);
+                    }
+                }
+            }
+            // While paused the clock stands still; `.` runs exactly one cycle
+            if self.sim_paused {
+                if is_key_pressed(KeyCode::Tab) {
+                    self.cycle_debug_focus();
+                }
+                if is_key_pressed(KeyCode::Period) {
+                    self.update_simulation();
+                }
+            } else {
+                self.time_accumulator += frame_time;
+            }
 
             // Fixed simulation update loop
             while self.time_accumulator >= self.cycle_duration {
@@ -183,17 +471,25 @@ impl Game {
                 self.time_accumulator,
                 self.cycle_duration,
                 None,
+                self.overtime_multiplier(),
+                &self.debug_console,
+                &view,
             );
+            if self.sim_paused
+                && let Some(robot) = self.robots.get(self.debug_focus)
+            {
+                renderer.draw_debug_overlay(robot);
+            }
             next_frame().await;
         }
 
         // Prepare announcement message
         if self.game_over {
             game_ended = true;
-            announcement = Some(if let Some(winner_id) = self.winner {
-                format!("Robot {} Wins!", winner_id)
-            } else {
-                "Draw!".to_string()
+            announcement = Some(match (self.winner, self.winning_team) {
+                (Some(winner_id), _) => format!("Robot {} Wins!", winner_id),
+                (None, Some(team)) => format!("Team {} Wins!", team),
+                (None, None) => "Draw!".to_string(),
             });
         }
         info!("Exiting Bot Arena.");
@@ -213,6 +509,9 @@ impl Game {
                     self.time_accumulator,
                     self.cycle_duration,
                     announcement.as_deref(),
+                    self.overtime_multiplier(),
+                    &self.debug_console,
+                    &view,
                 );
                 if Renderer::is_key_down(macroquad::prelude::KeyCode::Escape) {
                     break;
@@ -223,13 +522,469 @@ impl Game {
         Ok(())
     }
 
+    /// Assigns each robot's @coin tiebreaker from the given seed
+    pub fn seed_coins(&mut self, seed: u64) {
+        self.coin_seed = seed;
+        for robot in self.robots.iter_mut() {
+            robot.coin = crate::robot::coin_flip(seed, robot.id);
+        }
+    }
+
+    /// Moves the pause overlay on to the next robot, wrapping around
+    pub fn cycle_debug_focus(&mut self) {
+        if !self.robots.is_empty() {
+            self.debug_focus = (self.debug_focus + 1) % self.robots.len();
+        }
+    }
+
+    /// Pauses or resumes a robot's VM, for watching one robot while the others
+    /// keep acting. A paused robot still moves, takes hits and regenerates power.
+    /// Returns whether the robot is now paused (false if it isn't in play).
+    pub fn toggle_vm_pause(&mut self, robot_id: u32) -> bool {
+        match self.robots.iter_mut().find(|r| r.id == robot_id) {
+            Some(robot) => {
+                robot.vm_paused = !robot.vm_paused;
+                info!(
+                    "Robot {} VM {}",
+                    robot_id,
+                    if robot.vm_paused { "paused" } else { "resumed" }
+                );
+                robot.vm_paused
+            }
+            None => false,
+        }
+    }
+
+    /// Places random obstacles in the arena using the match RNG
+    pub fn place_obstacles(&mut self) {
+        self.arena.place_obstacles(&mut self.rng);
+    }
+
+    /// Replaces the arena layout with a map file: an ASCII grid (see
+    /// `Arena::load_map`) or JSON written by `--export-map`. Robots still on their
+    /// starting corner move to the same corner of the new map (still facing the
+    /// center if they were). Fails if a robot would start touching an obstacle or
+    /// outside the map.
+    pub fn load_map(&mut self, contents: &str) -> Result<(), String> {
+        let old_corners: Vec<Point> = (0..4).map(|i| start_position(&self.arena, i)).collect();
+        let old_center = Point {
+            x: self.arena.width / 2.0,
+            y: self.arena.height / 2.0,
+        };
+        if contents.trim_start().starts_with('{') {
+            self.arena.load_map_json(contents)?;
+        } else {
+            self.arena.load_map(contents)?;
+        }
+        let center = Point {
+            x: self.arena.width / 2.0,
+            y: self.arena.height / 2.0,
+        };
+        for robot in self.robots.iter_mut() {
+            let Some(index) = old_corners.iter().position(|c| *c == robot.position) else {
+                continue;
+            };
+            let facing_center =
+                (heading_toward(robot.position, old_center) - robot.drive.direction).abs() < 1e-9;
+            let position = start_position(&self.arena, index);
+            robot.position = position;
+            robot.prev_position = position;
+            if facing_center {
+                robot.set_heading(heading_toward(position, center));
+            }
+        }
+        self.robots
+            .iter()
+            .try_for_each(|robot| self.check_spawn(robot))
+    }
+
+    // Fails if the robot's body reaches outside the arena or into an obstacle
+    fn check_spawn(&self, robot: &Robot) -> Result<(), String> {
+        let (position, radius) = (robot.position, robot.radius);
+        let outside = position.x - radius < 0.0
+            || position.y - radius < 0.0
+            || position.x + radius > self.arena.width
+            || position.y + radius > self.arena.height;
+        if outside || self.arena.circle_collides(position, radius) {
+            return Err(format!(
+                "Robot {} ({}) would start {} at ({:.2}, {:.2})",
+                robot.id,
+                robot.name,
+                if outside {
+                    "outside the map"
+                } else {
+                    "inside an obstacle"
+                },
+                position.x,
+                position.y
+            ));
+        }
+        Ok(())
+    }
+
+    /// Seeds every robot's reproducible RNG (`rand_normal`, sensor noise) from a match seed
+    pub fn seed_random(&mut self, seed: u64) {
+        self.random_seed = seed;
+        for robot in self.robots.iter_mut() {
+            robot.seeded_rng = crate::robot::robot_rng(seed, robot.id);
+        }
+    }
+
+    /// Enables or disables the speed-dependent drive turn rate for every robot
+    pub fn set_speed_turn_coupling(&mut self, enabled: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.speed_turn_coupling = enabled;
+        }
+    }
+
+    /// Makes @target_* registers readable only right after a successful scan for every robot
+    pub fn set_blind_targeting(&mut self, enabled: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.blind_targeting = enabled;
+        }
+    }
+
+    /// Puts each robot on a team, in robot order (0 = no team). Shots pass
+    /// through teammates without hurting them.
+    pub fn set_teams(&mut self, teams: &[u8]) {
+        for (robot, &team) in self.robots.iter_mut().zip(teams) {
+            robot.team = team;
+        }
+        self.update_teammates();
+    }
+
+    // Rebuilds every robot's teammate list from the robots' teams
+    fn update_teammates(&mut self) {
+        let robot_teams: Vec<(u32, u8)> = self.robots.iter().map(|r| (r.id, r.team)).collect();
+        for robot in self.robots.iter_mut() {
+            robot.teammates = robot_teams
+                .iter()
+                .filter(|(id, team)| robot.team != 0 && *team == robot.team && *id != robot.id)
+                .map(|(id, _)| *id)
+                .collect();
+        }
+    }
+
+    /// Makes every robot's scans look past its teammates, or see all robots
+    pub fn set_skip_teammates(&mut self, enabled: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.skip_teammates = enabled;
+        }
+    }
+
+    /// Arms every robot's `fire` with a hit-scan laser, or with regular projectiles
+    pub fn set_laser_weapons(&mut self, enabled: bool) {
+        let kind = if enabled {
+            WeaponKind::Laser
+        } else {
+            WeaponKind::Projectile
+        };
+        for robot in self.robots.iter_mut() {
+            robot.turret.ranged.kind = kind;
+        }
+    }
+
+    /// Adds distance-scaled noise to every robot's scan readings, or keeps them exact
+    pub fn set_sensor_noise(&mut self, enabled: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.sensor_noise = enabled;
+        }
+    }
+
+    /// Switches every robot between continuous and cell-by-cell grid movement
+    pub fn set_grid_movement(&mut self, enabled: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.grid_movement = enabled;
+        }
+    }
+
+    /// Turns respawn mode on or off. Respawned robots are restored from their
+    /// state at the time of this call, so call it once the robots are set up.
+    pub fn set_respawn(&mut self, enabled: bool) {
+        self.respawn = enabled;
+        self.respawn_templates = if enabled {
+            self.robots.clone()
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Makes out-of-range bitwise operands fault instead of saturating for every robot
+    pub fn set_strict_bitwise(&mut self, strict: bool) {
+        for robot in self.robots.iter_mut() {
+            robot.vm_state.strict_bitwise = strict;
+        }
+    }
+
+    /// Damage multiplier while overtime is in effect: it starts once the match
+    /// enters its final OVERTIME_TURNS turns and grows with every turn after that.
+    /// None outside overtime, or when overtime is off.
+    pub fn overtime_multiplier(&self) -> Option<f64> {
+        let start = self.max_turns.saturating_sub(config::OVERTIME_TURNS);
+        if !self.overtime || self.current_turn <= start {
+            return None;
+        }
+        Some(1.0 + config::OVERTIME_DAMAGE_GROWTH * (self.current_turn - start) as f64)
+    }
+
+    /// Summarizes the match so far: winner, elapsed cycles and each robot's survival and stats
+    pub fn match_result(&self) -> MatchResult {
+        let mut robots: Vec<RobotResult> = self
+            .robots
+            .iter()
+            .map(|r| RobotResult::from_robot(r, false))
+            .chain(
+                self.respawning
+                    .iter()
+                    .map(|(r, _)| RobotResult::from_robot(r, false)),
+            )
+            .chain(self.eliminated.iter().cloned())
+            .collect();
+        robots.sort_by_key(|r| r.id);
+
+        MatchResult {
+            winner: self.winner,
+            winning_team: self.winning_team,
+            total_cycles: self.total_cycles,
+            robots,
+        }
+    }
+
+    /// True once the match has a winner or ended in a draw
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Runs the match to completion as fast as possible, without a window
+    pub fn run_headless(&mut self) {
+        while self.current_turn <= self.max_turns && !self.game_over {
+            self.step_turn();
+        }
+        self.finish_action_log();
+    }
+
+    /// Verifies a recorded action log: checks its hash chain, then plays the match
+    /// headless with the arena taking the logged commands instead of the ones the
+    /// robots issue, and compares the state it ends in with the logged final state
+    pub fn verify_action_log(&mut self, log: &ActionLog) -> Result<(), String> {
+        log.check_hashes()?;
+        self.action_log = None;
+        self.replay_commands = Some(log.commands.iter().cloned().collect());
+        self.run_headless();
+        if let Some(unplayed) = self.replay_commands.take().and_then(|mut c| c.pop_front()) {
+            return Err(format!(
+                "match ended before the commands of turn {}",
+                unplayed.turn
+            ));
+        }
+        log.check_final_state(&self.robots, &self.arena)
+    }
+
+    /// Adds a robot from program source text in the first unoccupied corner, facing
+    /// `heading` degrees or the arena center. It gets the next unused id, its @coin and
+    /// seeded RNG from the match seeds and no team. Returns the new robot's id.
+    pub fn load_robot(
+        &mut self,
+        name: &str,
+        source: &str,
+        heading: Option<f64>,
+    ) -> Result<u32, String> {
+        let Some(position) = (0..4)
+            .map(|i| start_position(&self.arena, i))
+            .find(|corner| {
+                self.robots
+                    .iter()
+                    .all(|r| r.position.distance(corner) > 2.0 * r.radius)
+            })
+        else {
+            return Err("Maximum of 4 robots allowed".to_string());
+        };
+        let constants = predefined_constants(&self.arena);
+        let program = crate::vm::parser::parse_assembly(source, Some(&constants))
+            .map_err(|e| format!("{}: {}", e.location(), e.message))?;
+        let id = self.robots.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let center = Point {
+            x: self.arena.width / 2.0,
+            y: self.arena.height / 2.0,
+        };
+        let mut robot = match heading {
+            Some(heading) => Robot::with_heading(id, name.to_string(), position, heading),
+            None => Robot::new(id, name.to_string(), position, center),
+        };
+        self.check_spawn(&robot)?;
+        robot.load_program(program);
+        robot.coin = crate::robot::coin_flip(self.coin_seed, id);
+        robot.seeded_rng = crate::robot::robot_rng(self.random_seed, id);
+        self.robots.push(robot);
+        self.update_teammates();
+        Ok(id)
+    }
+
+    /// Advances the match by one full turn (or until it ends)
+    pub fn step_turn(&mut self) {
+        let turn = self.current_turn;
+        while self.current_turn == turn && self.current_turn <= self.max_turns && !self.game_over {
+            self.update_simulation();
+        }
+    }
+
+    /// Snapshot of the match for an external renderer: turn, cycle, winner, every
+    /// robot's position, headings, health and status, and every projectile in flight
+    pub fn state_json(&self) -> String {
+        let robots: Vec<_> = self
+            .robots
+            .iter()
+            .map(|r| {
+                json!({
+                    "id": r.id,
+                    "name": r.name,
+                    "x": r.position.x,
+                    "y": r.position.y,
+                    "drive_heading": r.drive.direction,
+                    "turret_heading": r.turret.direction,
+                    "health": r.health,
+                    "status": status_name(r.status),
+                })
+            })
+            .collect();
+        let projectiles: Vec<_> = self
+            .arena
+            .projectiles
+            .iter()
+            .map(|p| {
+                json!({
+                    "source_robot": p.source_robot,
+                    "x": p.position.x,
+                    "y": p.position.y,
+                    "direction": p.direction,
+                })
+            })
+            .collect();
+        let state = json!({
+            "turn": self.current_turn,
+            "cycle": self.current_cycle,
+            "game_over": self.game_over,
+            "winner": self.winner,
+            "robots": robots,
+            "projectiles": projectiles,
+        });
+        let mut json =
+            serde_json::to_string_pretty(&state).expect("state snapshots always serialize");
+        json.push('\n');
+        json
+    }
+
+    /// Closes the action log once the match is over: checkpoints a turn cut
+    /// short by the end of the match and records the final state hash
+    pub fn finish_action_log(&mut self) {
+        if let Some(log) = self.action_log.as_mut() {
+            if self.current_cycle > 0 {
+                log.end_turn(self.current_turn);
+            }
+            log.finish(&self.robots, &self.arena);
+        }
+    }
+
+    // Counts down waiting robots and brings back those whose delay is up, with
+    // full health and a fresh VM at the free cell farthest from other robots.
+    // Damage, kill and alive-time tallies carry over.
+    fn process_respawns(&mut self) {
+        for (_, cycles_left) in self.respawning.iter_mut() {
+            *cycles_left = cycles_left.saturating_sub(1);
+        }
+        let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.respawning)
+            .into_iter()
+            .partition(|(_, cycles_left)| *cycles_left == 0);
+        self.respawning = waiting;
+
+        for (wreck, _) in ready {
+            let Some(template) = self.respawn_templates.iter().find(|r| r.id == wreck.id) else {
+                continue;
+            };
+            let mut robot = template.clone();
+            let position = self.free_spawn_point();
+            robot.position = position;
+            robot.prev_position = position;
+            robot.damage_dealt = wreck.damage_dealt;
+            robot.kills = wreck.kills;
+            robot.alive_cycles = wreck.alive_cycles;
+            robot.vm_state.turn = self.current_turn;
+            robot.vm_state.cycle = self.current_cycle;
+            info!(
+                "Robot {} respawned at ({:.2}, {:.2})",
+                robot.id, position.x, position.y
+            );
+            self.robots.push(robot);
+        }
+        self.robots.sort_by_key(|r| r.id); // Keep execution order stable
+    }
+
+    // Drops a new pickup every PICKUP_SPAWN_INTERVAL_CYCLES (while fewer than
+    // PICKUP_MAX are out) and hands out any a robot has reached
+    fn update_pickups(&mut self) {
+        if self
+            .total_cycles
+            .is_multiple_of(config::PICKUP_SPAWN_INTERVAL_CYCLES)
+            && self.arena.pickups.len() < config::PICKUP_MAX
+        {
+            let kind = if self.rng.gen_bool(0.5) {
+                PickupKind::Health
+            } else {
+                PickupKind::Energy
+            };
+            self.arena.spawn_pickup(kind, &self.robots, &mut self.rng);
+        }
+        for (robot_id, pickup) in self.arena.collect_pickups(&mut self.robots) {
+            info!("Robot {} collected a {:?} pickup", robot_id, pickup.kind);
+            self.particle_system.spawn_explosion(
+                Vec2::new(pickup.position.x as f32, pickup.position.y as f32),
+                pickup_color(pickup.kind),
+                30,
+                config::UNIT_SIZE as f32 * 3.0,
+                0.4,
+            );
+        }
+    }
+
+    // Center of the obstacle-free cell farthest from every live robot
+    fn free_spawn_point(&self) -> Point {
+        let mut best = Point {
+            x: self.arena.width / 2.0,
+            y: self.arena.height / 2.0,
+        };
+        let mut best_distance = f64::NEG_INFINITY;
+        for grid_y in 0..self.arena.grid_height {
+            for grid_x in 0..self.arena.grid_width {
+                let cell = self.arena.grid_to_world(grid_x, grid_y);
+                if self.arena.check_collision(cell) {
+                    continue;
+                }
+                let nearest = self
+                    .robots
+                    .iter()
+                    .map(|r| r.position.distance(&cell))
+                    .fold(f64::INFINITY, f64::min);
+                if nearest > best_distance {
+                    best_distance = nearest;
+                    best = cell;
+                }
+            }
+        }
+        best
+    }
+
     /// Update the simulation state for one fixed time step
-    fn update_simulation(&mut self) {
+    pub(crate) fn update_simulation(&mut self) {
+        // Events are stamped with the cycle being simulated, even those written after the increment
+        let (event_turn, event_cycle) = (self.current_turn, self.current_cycle);
+
         // Update previous state
         for robot in self.robots.iter_mut() {
             robot.update_prev_state();
         }
         self.particle_system.update_prev_state();
+        self.arena.beams.clear(); // Beams are only drawn for the cycle they were fired in
+        self.arena.damage_multiplier = self.overtime_multiplier().unwrap_or(1.0);
 
         let mut command_queue: VecDeque<ArenaCommand> = VecDeque::new();
 
@@ -238,6 +993,11 @@ impl Game {
         for robot in self.robots.iter_mut() {
             robot.process_cycle_updates(&self.arena);
         }
+        self.arena
+            .resolve_robot_collisions(&mut self.robots, &self.audio_manager);
+        if self.pickups {
+            self.update_pickups();
+        }
 
         // Update robots' area of interest (AOI)
         self.arena.update_all_robots_aoi(&mut self.robots);
@@ -252,13 +1012,36 @@ impl Game {
             .map(|robot| (robot.id, (robot.position, robot.status)))
             .collect();
 
-        // Execute VM cycle for each robot
-        for i in 0..self.robots.len() {
-            let robot = &mut self.robots[i];
+        // Live robot collision circles for @forward_clear_distance
+        let robot_circles: Vec<(u32, Point, f64)> = self
+            .robots
+            .iter()
+            .filter(|robot| robot.status != RobotStatus::Destroyed)
+            .map(|robot| (robot.id, robot.position, robot.radius))
+            .collect();
+
+        // Live robot teams for @closest_ally_*
+        let robot_teams: Vec<(u32, u8, Point)> = self
+            .robots
+            .iter()
+            .filter(|robot| robot.status != RobotStatus::Destroyed)
+            .map(|robot| (robot.id, robot.team, robot.position))
+            .collect();
 
-            // Update VM registers before execution
-            robot.update_vm_state_registers(&self.arena);
+        // Update VM registers before execution, in robot order: @rand draws from the match RNG
+        for robot in self.robots.iter_mut() {
+            robot.update_vm_state_registers(&self.arena, self.rng.r#gen::<f64>());
+            robot.update_forward_clear_distance(&self.arena, &robot_circles);
+            robot.update_closest_ally(&robot_teams);
+        }
 
+        // Execute VM cycle for each robot. Robots only see each other through the
+        // precomputed robot_info, so their cycles are independent and can run in parallel;
+        // each one fills its own command queue.
+        let arena = &self.arena;
+        let trace_robot = self.trace_robot;
+        let execute = |robot: &mut Robot| -> VecDeque<ArenaCommand> {
+            let mut robot_commands = VecDeque::new();
             // Execute if not destroyed
             if robot.status != RobotStatus::Destroyed {
                 // Store robot's properties locally to avoid borrowing issues
@@ -282,11 +1065,49 @@ impl Game {
                 robot.execute_vm_cycle_with_provider(
                     get_robot_ids,
                     &mut get_robot_info,
-                    &self.arena,
-                    &mut command_queue,
+                    arena,
+                    &mut robot_commands,
+                    trace_robot,
                 );
             }
-        }
+            robot_commands
+        };
+        let robot_commands: Vec<VecDeque<ArenaCommand>> = if self.parallel {
+            self.robots.par_iter_mut().map(execute).collect()
+        } else {
+            self.robots.iter_mut().map(execute).collect()
+        };
+
+        // Merge the queues in robot order (robots are kept sorted by id), so parallel
+        // runs issue commands in the same order as serial ones
+        for (robot, commands) in self.robots.iter_mut().zip(robot_commands) {
+            // Attribute the commands this robot just issued
+            if let Some(log) = self.action_log.as_mut() {
+                for command in &commands {
+                    log.record(self.current_turn, self.current_cycle, robot.id, command);
+                }
+            }
+            if self.replay_commands.is_none() {
+                command_queue.extend(commands);
+            }
+
+            // Collect `dbg` output for the on-screen console
+            for value in robot.debug_output.drain(..) {
+                self.debug_console.push(DebugLine {
+                    robot_id: robot.id,
+                    turn: self.current_turn,
+                    cycle: self.current_cycle,
+                    value,
+                });
+            }
+        }
+        // Verifying an action log: the arena gets this cycle's logged commands instead
+        if let Some(logged) = self.replay_commands.as_mut() {
+            let now = (self.current_turn, self.current_cycle);
+            while let Some(entry) = logged.pop_front_if(|c| (c.turn, c.cycle) == now) {
+                command_queue.push_back(entry.command);
+            }
+        }
 
         // Update Phase 2: Physics and Interactions
         // Collect projectile movements for trail spawning *before* moving them
@@ -315,6 +1136,20 @@ impl Game {
             &mut self.particle_system,
             &self.audio_manager,
         );
+        // Obstacles shot to pieces leave rubble around their cell, placed by the match RNG
+        for position in std::mem::take(&mut self.arena.crumbled_obstacles) {
+            if self.rubble {
+                let (grid_x, grid_y) = self.arena.world_to_grid(position);
+                self.arena
+                    .drop_rubble(grid_x, grid_y, &self.robots, &mut self.rng);
+            }
+        }
+
+        self.arena.update_mines(
+            &mut self.robots,
+            &mut self.particle_system,
+            &self.audio_manager,
+        );
 
         // Update Phase 3.5: Spawn Trails based on pre-calculated movements
         // Note: We iterate using the collected movements, not the potentially modified projectile list
@@ -327,6 +1162,63 @@ impl Game {
 
         self.particle_system.update(self.cycle_duration);
 
+        // Update Phase 3.6: Beams hit instantly and self-destructs go off before wreckage
+        // is cleared, so the attacking robot is still present to be credited with the damage
+        let mut instant_hits = Vec::new();
+        command_queue.retain(|command| match command {
+            ArenaCommand::Detonate { .. } | ArenaCommand::FireBeam { .. } => {
+                instant_hits.push(command.clone());
+                false
+            }
+            _ => true,
+        });
+        for command in instant_hits {
+            match command {
+                ArenaCommand::Detonate {
+                    source_robot,
+                    source_team,
+                    position,
+                    power,
+                } => self.arena.apply_detonation(
+                    &mut self.robots,
+                    source_robot,
+                    source_team,
+                    position,
+                    power,
+                    &mut self.particle_system,
+                    &self.audio_manager,
+                ),
+                ArenaCommand::FireBeam {
+                    source_robot,
+                    source_team,
+                    start,
+                    direction,
+                    power,
+                    falloff,
+                } => self.arena.fire_beam(
+                    &mut self.robots,
+                    source_robot,
+                    source_team,
+                    start,
+                    direction,
+                    power,
+                    falloff,
+                    &self.audio_manager,
+                ),
+                _ => {}
+            }
+        }
+
+        // Hits and scans from this cycle, before destroyed robots are removed
+        let events: Vec<Event> = self
+            .robots
+            .iter_mut()
+            .flat_map(|robot| robot.events.drain(..))
+            .collect();
+        for event in events {
+            self.record_event(event_turn, event_cycle, event);
+        }
+
         // --- Remove destroyed robots, add obstacles, check win/draw ---
         // This block correctly calculates and uses its own `destroyed_robots`
         let destroyed_robots: Vec<Robot> = self
@@ -336,28 +1228,75 @@ impl Game {
             .cloned()
             .collect();
         for robot in &destroyed_robots {
+            if self.explosions_push {
+                self.arena
+                    .apply_explosion_push(&mut self.robots, robot.position);
+            }
+            if self.despawn_dead_shots {
+                self.arena
+                    .despawn_projectiles_from(robot.id, &mut self.particle_system);
+            }
+            if self.respawn {
+                // No wreckage: it would pile up over an endless match
+                self.respawning
+                    .push((robot.clone(), config::RESPAWN_DELAY_CYCLES));
+                continue;
+            }
             self.arena.add_obstacle_at_robot(robot);
+            self.eliminated.push(RobotResult::from_robot(robot, true));
         }
         // Remove destroyed robots from the robots vector
         self.robots.retain(|r| r.status != RobotStatus::Destroyed);
 
-        // Check for win/draw
-        let alive_robots: Vec<&Robot> = self
-            .robots
-            .iter()
-            .filter(|r| r.status != RobotStatus::Destroyed)
-            .collect();
-        if alive_robots.len() == 1 {
-            self.game_over = true;
-            self.winner = Some(alive_robots[0].id);
-        } else if alive_robots.is_empty() {
-            self.game_over = true;
-            self.winner = None;
+        if self.respawn {
+            // Robots keep coming back, so the match only ends at max_turns
+            self.process_respawns();
+        } else {
+            // Check for win/draw
+            let alive_robots: Vec<&Robot> = self
+                .robots
+                .iter()
+                .filter(|r| r.status != RobotStatus::Destroyed)
+                .collect();
+            // Teammates can't hurt each other, so a team left on its own has won
+            let alive_team = alive_robots
+                .first()
+                .map(|r| r.team)
+                .filter(|&team| team != 0 && alive_robots.iter().all(|r| r.team == team));
+            if alive_robots.len() == 1 {
+                self.game_over = true;
+                self.winner = Some(alive_robots[0].id);
+                self.winning_team = alive_team;
+            } else if alive_robots.is_empty() {
+                self.game_over = true;
+                self.winner = None;
+            } else if alive_team.is_some() {
+                self.game_over = true;
+                self.winning_team = alive_team;
+            }
+        }
+
+        // Survivors of this cycle accumulate alive time
+        for robot in self.robots.iter_mut() {
+            robot.alive_cycles += 1;
+        }
+        self.total_cycles += 1;
+
+        // Record the end-of-cycle state for replays
+        if let Some(replay) = self.replay.as_mut() {
+            replay.push(ReplayFrame::capture(
+                self.current_turn,
+                self.current_cycle,
+                &self.robots,
+            ));
         }
 
         // Cycle/Turn Increment
         self.current_cycle += 1;
         if self.current_cycle >= config::CYCLES_PER_TURN {
+            if let Some(log) = self.action_log.as_mut() {
+                log.end_turn(self.current_turn);
+            }
             self.current_cycle = 0;
             self.current_turn += 1;
 
@@ -377,9 +1316,27 @@ impl Game {
         for command in command_queue.drain(..) {
             match command {
                 ArenaCommand::SpawnProjectile(projectile) => {
+                    // Shots queued by a robot destroyed this cycle never leave the barrel
+                    if self.despawn_dead_shots
+                        && !self.robots.iter().any(|r| r.id == projectile.source_robot)
+                    {
+                        continue;
+                    }
+                    self.record_event(
+                        event_turn,
+                        event_cycle,
+                        Event::ProjectileSpawned {
+                            robot_id: projectile.source_robot,
+                            x: projectile.position.x,
+                            y: projectile.position.y,
+                            direction: projectile.direction,
+                            power: projectile.power,
+                        },
+                    );
                     self.arena.spawn_projectile(projectile);
                     self.audio_manager.play_fire();
                 }
+                ArenaCommand::SpawnMine(mine) => self.arena.mines.push(mine),
                 ArenaCommand::SpawnMuzzleFlash {
                     position,
                     direction,
@@ -396,8 +1353,27 @@ impl Game {
                     self.particle_system
                         .spawn_muzzle_flash(flash_pos_world, direction);
                 }
+                ArenaCommand::Detonate { .. } | ArenaCommand::FireBeam { .. } => {} // Resolved in Phase 3.6
             }
         }
+
+        if let Some(stream) = self.event_stream.as_mut()
+            && let Err(e) = stream.end_cycle()
+        {
+            error!("Failed to flush event stream, closing it: {}", e);
+            self.event_stream = None;
+        }
+    }
+
+    // Writes an event to the --event-stream file, if there is one. A failing
+    // stream is closed rather than stopping the match.
+    fn record_event(&mut self, turn: u32, cycle: u32, event: Event) {
+        if let Some(stream) = self.event_stream.as_mut()
+            && let Err(e) = stream.write(turn, cycle, &event)
+        {
+            error!("Failed to write event stream, closing it: {}", e);
+            self.event_stream = None;
+        }
     }
 }
 
@@ -405,7 +1381,7 @@ impl Game {
 mod tests {
     use super::*;
     use crate::robot::{Robot, RobotStatus};
-    use crate::types::Point;
+    use crate::types::{DamageType, Point};
 
     // Helper to create a dummy robot with a given id, position, and status
     fn dummy_robot(id: u32, pos: Point, status: RobotStatus) -> Robot {
@@ -416,24 +1392,134 @@ mod tests {
         robot
     }
 
-    #[test]
-    fn test_destroyed_robot_removal_and_obstacle_placement() {
-        let mut game = Game {
+    // Helper to create a game around the given robots without loading programs
+    fn test_game(robots: Vec<Robot>) -> Game {
+        Game {
             arena: Arena::new(),
-            robots: vec![
-                dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
-                dummy_robot(2, Point { x: 0.2, y: 0.2 }, RobotStatus::Destroyed),
-            ],
+            robots,
             particle_system: ParticleSystem::new(),
             audio_manager: AudioManager::new(),
             current_turn: 1,
             current_cycle: 0,
             max_turns: 10,
+            trace_robot: None,
+            replay: None,
+            total_cycles: 0,
+            explosions_push: false,
+            despawn_dead_shots: false,
+            overtime: false,
+            respawn: false,
+            pickups: false,
+            rubble: false,
+            parallel: false,
+            debug_console: DebugConsole::new(config::DEBUG_CONSOLE_LINES),
+            sim_paused: false,
+            debug_focus: 0,
+            action_log: None,
+            replay_commands: None,
+            event_stream: None,
+            rng: StdRng::seed_from_u64(0),
+            coin_seed: 0,
+            random_seed: 0,
+            eliminated: Vec::new(),
+            respawn_templates: Vec::new(),
+            respawning: Vec::new(),
             time_accumulator: 0.0,
             cycle_duration: 1.0,
             game_over: false,
             winner: None,
-        };
+            winning_team: None,
+        }
+    }
+
+    #[test]
+    fn test_load_map_rejects_robots_inside_obstacles() {
+        // 20x20 map with a single obstacle in cell (2, 2)
+        let mut rows = vec![".".repeat(20); 20];
+        rows[2] = "..#".to_string() + &".".repeat(17);
+        let map = rows.join("\n");
+
+        let mut game = test_game(vec![dummy_robot(
+            1,
+            Point { x: 0.5, y: 0.5 },
+            RobotStatus::Active,
+        )]);
+        assert!(game.load_map(&map).is_ok());
+        assert_eq!(game.arena.obstacles.len(), 1);
+
+        let inside = game.arena.grid_to_world(2, 2);
+        let mut game = test_game(vec![dummy_robot(1, inside, RobotStatus::Active)]);
+        let error = game.load_map(&map).unwrap_err();
+        assert!(error.contains("Robot 1"));
+        assert!(error.contains("inside an obstacle"));
+
+        // A robot placed away from the corners that a smaller map no longer contains
+        let mut game = test_game(vec![dummy_robot(
+            1,
+            Point { x: 0.5, y: 0.5 },
+            RobotStatus::Active,
+        )]);
+        assert!(
+            game.load_map("...\n...")
+                .unwrap_err()
+                .contains("outside the map")
+        );
+
+        // The robot's body, not just its center, has to be clear of obstacles:
+        // the top-left corner start sits where cells (1, 1) to (2, 2) meet
+        let mut rows = vec![".".repeat(20); 20];
+        rows[1] = ".#".to_string() + &".".repeat(18);
+        let corner = start_position(&Arena::new(), 0);
+        let mut game = test_game(vec![dummy_robot(1, corner, RobotStatus::Active)]);
+        assert_eq!(game.arena.world_to_grid(corner), (2, 2));
+        let error = game.load_map(&rows.join("\n")).unwrap_err();
+        assert!(error.contains("inside an obstacle"));
+    }
+
+    #[test]
+    fn test_load_map_moves_corner_starts_to_the_new_corners() {
+        let arena = Arena::new();
+        let robots = (0..4)
+            .map(|i| {
+                let position = start_position(&arena, i);
+                Robot::new(
+                    i as u32 + 1,
+                    format!("bot{}", i),
+                    position,
+                    Point { x: 0.5, y: 0.5 },
+                )
+            })
+            .collect();
+        let mut game = test_game(robots);
+        game.robots[3].set_heading(90.0);
+
+        // A map smaller than the default arena
+        let small = vec![".".repeat(8); 6].join("\n");
+        assert!(game.load_map(&small).is_ok());
+        let center = Point { x: 0.2, y: 0.15 };
+        for (i, robot) in game.robots.iter().enumerate() {
+            assert_eq!(robot.position, start_position(&game.arena, i));
+        }
+        assert_eq!(game.robots[0].position, Point { x: 0.1, y: 0.1 });
+        let toward_center = heading_toward(game.robots[1].position, center);
+        assert!((game.robots[1].drive.direction - toward_center).abs() < 1e-9);
+        assert!((game.robots[1].turret.direction - toward_center).abs() < 1e-9);
+        // A robot given its own heading keeps it
+        assert_eq!(game.robots[3].drive.direction, 90.0);
+
+        // And a larger one spreads them out to its far corners
+        let large = vec![".".repeat(40); 40].join("\n");
+        assert!(game.load_map(&large).is_ok());
+        let far = game.robots[1].position;
+        assert!((far.x - 1.9).abs() < 1e-9 && (far.y - 1.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_destroyed_robot_removal_and_obstacle_placement() {
+        let mut game = test_game(vec![
+            dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.2, y: 0.2 }, RobotStatus::Destroyed),
+        ]);
         // Before update: 2 robots, 0 obstacles
         assert_eq!(game.robots.len(), 2);
         assert_eq!(game.arena.obstacles.len(), 0);
@@ -448,45 +1534,866 @@ mod tests {
     }
 
     #[test]
-    fn test_win_and_draw_logic() {
-        // Test win condition: one robot left
-        let mut game = Game {
-            arena: Arena::new(),
-            robots: vec![dummy_robot(
+    fn test_destroyed_obstacle_drops_seeded_rubble() {
+        use crate::arena::Obstacle;
+        use crate::types::Projectile;
+
+        // Obstacle cells left after shooting down the obstacle at (10, 10)
+        let run = |seed: u64, rubble: bool| {
+            let mut game = test_game(vec![dummy_robot(
                 1,
                 Point { x: 0.1, y: 0.1 },
                 RobotStatus::Active,
-            )],
-            particle_system: ParticleSystem::new(),
-            audio_manager: AudioManager::new(),
-            current_turn: 1,
-            current_cycle: 0,
-            max_turns: 10,
-            time_accumulator: 0.0,
-            cycle_duration: 1.0,
-            game_over: false,
-            winner: None,
+            )]);
+            game.rubble = rubble;
+            game.rng = StdRng::seed_from_u64(seed);
+            let target = game.arena.grid_to_world(10, 10);
+            game.arena.obstacles.push(Obstacle {
+                position: target,
+                health: None,
+                rubble: false,
+            });
+            game.arena.make_obstacles_destructible();
+            let start = Point {
+                x: target.x - config::UNIT_SIZE * 0.6,
+                y: target.y,
+            };
+            for _ in 0..(config::OBSTACLE_HEALTH / 10.0).ceil() as usize {
+                game.arena.spawn_projectile(Projectile {
+                    position: start,
+                    prev_position: start,
+                    direction: 0.0,
+                    speed: 1.0,
+                    power: 1.0,
+                    base_damage: 10.0,
+                    source_robot: 1,
+                    source_team: 0,
+                    ballistic: None,
+                    bounces_remaining: 0,
+                    damage_type: DamageType::Kinetic,
+                });
+                game.update_simulation();
+            }
+            assert!(!game.arena.check_collision(target));
+            game.arena
+                .obstacles
+                .iter()
+                .map(|o| game.arena.world_to_grid(o.position))
+                .collect::<Vec<_>>()
+        };
+
+        let cells = run(3, true);
+        assert_eq!(cells.len(), config::RUBBLE_PIECES);
+        for &(x, y) in &cells {
+            assert!(x.abs_diff(10) <= 1 && y.abs_diff(10) <= 1 && (x, y) != (10, 10));
+        }
+        assert_eq!(run(3, true), cells);
+        // Without --rubble the obstacle just vanishes
+        assert!(run(3, false).is_empty());
+    }
+
+    #[test]
+    fn test_despawn_dead_shots() {
+        use crate::types::Projectile;
+
+        for despawn in [false, true] {
+            let mut game = test_game(vec![
+                dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
+                dummy_robot(2, Point { x: 0.9, y: 0.9 }, RobotStatus::Destroyed),
+                dummy_robot(3, Point { x: 0.9, y: 0.1 }, RobotStatus::Active),
+            ]);
+            game.despawn_dead_shots = despawn;
+            let start = Point { x: 0.5, y: 0.9 };
+            game.arena.spawn_projectile(Projectile {
+                position: start,
+                prev_position: start,
+                direction: 180.0,
+                speed: 0.01,
+                power: 0.5,
+                base_damage: 10.0,
+                source_robot: 2,
+                source_team: 0,
+                ballistic: None,
+                bounces_remaining: 0,
+                damage_type: DamageType::Kinetic,
+            });
+
+            game.update_simulation();
+
+            let retained = game.arena.projectiles.iter().any(|p| p.source_robot == 2);
+            assert_eq!(retained, !despawn);
+        }
+    }
+
+    #[test]
+    fn test_detonate_damages_nearby_robots() {
+        let mut bomber = dummy_robot(1, Point { x: 0.5, y: 0.5 }, RobotStatus::Active);
+        bomber.load_program(crate::vm::parser::parse_assembly("detonate 1.0", None).unwrap());
+        bomber.status = RobotStatus::Active;
+        let mut game = test_game(vec![
+            bomber,
+            dummy_robot(
+                2,
+                Point {
+                    x: 0.5 + config::UNIT_SIZE,
+                    y: 0.5,
+                },
+                RobotStatus::Active,
+            ),
+            dummy_robot(3, Point { x: 0.9, y: 0.9 }, RobotStatus::Active),
+        ]);
+
+        game.update_simulation();
+
+        let result = game.match_result();
+        assert!(result.robots.iter().any(|r| r.id == 1 && r.destroyed));
+        let health = |id: u32| game.robots.iter().find(|r| r.id == id).unwrap().health;
+        assert!(health(2) < config::DEFAULT_INITIAL_HEALTH);
+        assert_eq!(health(3), config::DEFAULT_INITIAL_HEALTH);
+    }
+
+    #[test]
+    fn test_respawn_restores_destroyed_robot() {
+        let mut game = test_game(vec![
+            dummy_robot(1, Point { x: 0.2, y: 0.2 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.8, y: 0.8 }, RobotStatus::Active),
+        ]);
+        game.set_respawn(true);
+        game.robots[1].damage_dealt = 12.0;
+        game.robots[1].kills = 1;
+        game.robots[1].health = 0.0;
+        game.robots[1].status = RobotStatus::Destroyed;
+
+        game.update_simulation();
+        assert_eq!(game.robots.len(), 1);
+        assert!(!game.is_game_over());
+        assert!(game.arena.obstacles.is_empty());
+
+        for _ in 1..config::RESPAWN_DELAY_CYCLES {
+            assert!(!game.robots.iter().any(|r| r.id == 2));
+            game.update_simulation();
+        }
+
+        let respawned = game.robots.iter().find(|r| r.id == 2).unwrap();
+        assert_eq!(respawned.health, config::DEFAULT_INITIAL_HEALTH);
+        assert_ne!(respawned.status, RobotStatus::Destroyed);
+        assert_eq!(respawned.vm_state.ip, 0);
+        assert_eq!(respawned.damage_dealt, 12.0);
+        assert_eq!(respawned.kills, 1);
+        assert!(respawned.position.distance(&game.robots[0].position) > 0.5);
+        assert!(!game.is_game_over());
+    }
+
+    #[test]
+    fn test_blind_targeting_clears_stale_scans() {
+        let program = "select 2\nscan\nmov @d0 @target_distance\nmov @d1 @target_distance";
+        for blind in [false, true] {
+            let mut scanner = dummy_robot(1, Point { x: 0.3, y: 0.5 }, RobotStatus::Active);
+            scanner.load_program(crate::vm::parser::parse_assembly(program, None).unwrap());
+            let mut game = test_game(vec![
+                scanner,
+                dummy_robot(2, Point { x: 0.6, y: 0.5 }, RobotStatus::Active),
+            ]);
+            game.set_blind_targeting(blind);
+            let register =
+                |game: &Game, reg: Register| game.robots[0].vm_state.registers.get(reg).unwrap();
+
+            // Cycle 2 runs the scan
+            game.update_simulation();
+            game.update_simulation();
+            assert!(register(&game, Register::TargetDistance) > 0.0);
+
+            // The instruction right after the scan still sees the result; the one after doesn't
+            game.update_simulation();
+            game.update_simulation();
+            assert!(register(&game, Register::D0) > 0.0);
+            if blind {
+                assert_eq!(register(&game, Register::TargetDistance), 0.0);
+                assert_eq!(register(&game, Register::D1), 0.0);
+            } else {
+                assert!(register(&game, Register::TargetDistance) > 0.0);
+                assert!(register(&game, Register::D1) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_beam_hits_instantly() {
+        let mut shooter = dummy_robot(1, Point { x: 0.3, y: 0.5 }, RobotStatus::Active);
+        shooter.load_program(crate::vm::parser::parse_assembly("beam 1.0", None).unwrap());
+        let mut game = test_game(vec![
+            shooter,
+            dummy_robot(2, Point { x: 0.6, y: 0.5 }, RobotStatus::Active),
+            dummy_robot(3, Point { x: 0.9, y: 0.9 }, RobotStatus::Active),
+        ]);
+
+        game.update_simulation();
+
+        let health = |id: u32| game.robots.iter().find(|r| r.id == id).unwrap().health;
+        assert_eq!(
+            health(2),
+            config::DEFAULT_INITIAL_HEALTH - config::BEAM_DAMAGE
+        );
+        assert_eq!(health(3), config::DEFAULT_INITIAL_HEALTH);
+        assert!(game.arena.projectiles.is_empty());
+        assert_eq!(game.arena.beams.len(), 1);
+        assert_eq!(game.robots[0].damage_dealt, config::BEAM_DAMAGE);
+    }
+
+    #[test]
+    fn test_team_spec_parsing() {
+        let spec: TeamSpec = "2: a.rasm, b.rasm".parse().unwrap();
+        assert_eq!(spec.team, 2);
+        assert_eq!(spec.files, vec!["a.rasm".to_string(), "b.rasm".to_string()]);
+        assert!("a.rasm".parse::<TeamSpec>().is_err());
+        assert!("0:a.rasm".parse::<TeamSpec>().is_err());
+        assert!("1:a.rasm,".parse::<TeamSpec>().is_err());
+    }
+
+    #[test]
+    fn test_set_teams_links_teammates() {
+        let mut game = test_game(vec![
+            dummy_robot(1, Point { x: 0.2, y: 0.2 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.8, y: 0.2 }, RobotStatus::Active),
+            dummy_robot(3, Point { x: 0.2, y: 0.8 }, RobotStatus::Active),
+            dummy_robot(4, Point { x: 0.8, y: 0.8 }, RobotStatus::Active),
+        ]);
+        game.set_teams(&[1, 2, 1, 0]);
+
+        assert_eq!(game.robots[0].teammates, vec![3]);
+        assert!(game.robots[1].teammates.is_empty());
+        assert_eq!(game.robots[2].teammates, vec![1]);
+        assert!(game.robots[3].teammates.is_empty());
+    }
+
+    #[test]
+    fn test_laser_weapon_fire_hits_instantly() {
+        let mut shooter = dummy_robot(1, Point { x: 0.3, y: 0.5 }, RobotStatus::Active);
+        shooter.load_program(crate::vm::parser::parse_assembly("fire 1.0", None).unwrap());
+        let mut game = test_game(vec![
+            shooter,
+            dummy_robot(2, Point { x: 0.6, y: 0.5 }, RobotStatus::Active),
+        ]);
+        game.set_laser_weapons(true);
+
+        game.update_simulation();
+
+        // The laser meets robot 2's edge 0.3 units out, less its radius, and has faded
+        let damage = Arena::beam_damage(1.0, 0.3 - game.robots[1].radius);
+        assert!(damage < config::BEAM_DAMAGE);
+        assert!((game.robots[1].health - (config::DEFAULT_INITIAL_HEALTH - damage)).abs() < 1e-9);
+        assert!(game.arena.projectiles.is_empty());
+        assert_eq!(game.arena.beams.len(), 1);
+
+        // Damage fades with distance; it's gone entirely past 1 / BEAM_FALLOFF
+        assert!(Arena::beam_damage(1.0, 0.8) < Arena::beam_damage(1.0, 0.2));
+        assert_eq!(Arena::beam_damage(1.0, 1.0 / config::BEAM_FALLOFF), 0.0);
+    }
+
+    #[test]
+    fn test_action_log_replay_reproduces_match() {
+        let duel = || {
+            let program = "select 2\nstart:\nfire 0.5\nrotate 15\njmp start";
+            let mut robots = vec![
+                dummy_robot(1, Point { x: 0.3, y: 0.5 }, RobotStatus::Active),
+                dummy_robot(2, Point { x: 0.7, y: 0.5 }, RobotStatus::Active),
+            ];
+            for robot in robots.iter_mut() {
+                robot.load_program(crate::vm::parser::parse_assembly(program, None).unwrap());
+            }
+            test_game(robots)
         };
+
+        let mut recorded = duel();
+        recorded.action_log = Some(ActionLog::new());
+        recorded.run_headless();
+        let log = recorded.action_log.unwrap();
+        assert!(!log.commands.is_empty());
+        assert!(log.final_state_hash.is_some());
+        // Verification works from the saved text form
+        let log = ActionLog::from_text(&log.to_text()).unwrap();
+        assert_eq!(duel().verify_action_log(&log), Ok(()));
+
+        // The arena really runs on the logged commands: a log missing a shot, even
+        // with a consistent hash chain, no longer leads to the recorded final state
+        let mut doctored = ActionLog::new();
+        let dropped = log
+            .commands
+            .iter()
+            .position(|c| matches!(c.command, ArenaCommand::SpawnProjectile(_)))
+            .unwrap();
+        let mut commands = log.commands.iter().enumerate().peekable();
+        for (turn, _) in &log.turn_hashes {
+            while let Some((index, c)) = commands.next_if(|(_, c)| c.turn <= *turn) {
+                if index != dropped {
+                    doctored.record(c.turn, c.cycle, c.robot_id, &c.command);
+                }
+            }
+            doctored.end_turn(*turn);
+        }
+        doctored.final_state_hash = log.final_state_hash;
+        assert_eq!(
+            duel().verify_action_log(&doctored),
+            Err("final state differs".to_string())
+        );
+
+        // Editing the log without fixing its hashes is caught before replaying
+        let mut edited = log.clone();
+        edited.commands.remove(dropped);
+        assert!(duel().verify_action_log(&edited).is_err());
+    }
+
+    #[test]
+    fn test_parallel_run_matches_serial() {
+        let bot = |name: &str| format!("{}/bots/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let run = |parallel: bool| {
+            let mut game = Game::new(
+                &[bot("chaos.rasm"), bot("jojo.rasm"), bot("chaos.rasm")],
+                20,
+                &InitialHeading::Center,
+                &[],
+                Some(7),
+                AudioManager::new(),
+            )
+            .unwrap();
+            game.parallel = parallel;
+            game.action_log = Some(ActionLog::new());
+            game.run_headless();
+            game
+        };
+
+        let serial = run(false);
+        let parallel = run(true);
+        assert_eq!(parallel.state_json(), serial.state_json());
+        let serial_log = serial.action_log.unwrap();
+        assert!(!serial_log.commands.is_empty());
+        assert_eq!(parallel.action_log.unwrap().to_text(), serial_log.to_text());
+    }
+
+    #[test]
+    fn test_paused_vm_does_not_advance() {
+        let program = "nop\nnop\nnop\nnop\nnop\nnop";
+        let mut robots = vec![
+            dummy_robot(1, Point { x: 0.2, y: 0.2 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.8, y: 0.8 }, RobotStatus::Active),
+        ];
+        for robot in robots.iter_mut() {
+            robot.load_program(crate::vm::parser::parse_assembly(program, None).unwrap());
+        }
+        let mut game = test_game(robots);
+        assert!(game.toggle_vm_pause(1));
+        assert!(!game.toggle_vm_pause(99));
+
+        for _ in 0..3 {
+            game.update_simulation();
+        }
+        assert_eq!(game.robots[0].vm_state.ip, 0);
+        assert!(game.robots[1].vm_state.ip > 0);
+
+        // Resuming picks up where it left off
+        assert!(!game.toggle_vm_pause(1));
+        game.update_simulation();
+        assert!(game.robots[0].vm_state.ip > 0);
+    }
+
+    #[test]
+    fn test_win_and_draw_logic() {
+        // Test win condition: one robot left
+        let mut game = test_game(vec![dummy_robot(
+            1,
+            Point { x: 0.1, y: 0.1 },
+            RobotStatus::Active,
+        )]);
         game.update_simulation();
         assert!(game.game_over);
         assert_eq!(game.winner, Some(1));
 
         // Test draw condition: no robots left
-        let mut game = Game {
-            arena: Arena::new(),
-            robots: vec![],
-            particle_system: ParticleSystem::new(),
-            audio_manager: AudioManager::new(),
-            current_turn: 1,
-            current_cycle: 0,
-            max_turns: 10,
-            time_accumulator: 0.0,
-            cycle_duration: 1.0,
-            game_over: false,
-            winner: None,
-        };
+        let mut game = test_game(vec![]);
+        game.update_simulation();
+        assert!(game.game_over);
+        assert_eq!(game.winner, None);
+    }
+
+    #[test]
+    fn test_surviving_teammates_win_as_a_team() {
+        let robots = vec![
+            dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.9, y: 0.1 }, RobotStatus::Active),
+            dummy_robot(3, Point { x: 0.1, y: 0.9 }, RobotStatus::Active),
+            dummy_robot(4, Point { x: 0.9, y: 0.9 }, RobotStatus::Active),
+        ];
+        let mut game = test_game(robots);
+        game.set_teams(&[1, 2, 1, 2]);
+
+        // One robot from each team still standing: the match goes on
+        game.robots[1].status = RobotStatus::Destroyed;
+        game.update_simulation();
+        assert!(!game.game_over);
+
+        // Both of team 1's robots outlive team 2
+        game.robots.iter_mut().find(|r| r.id == 4).unwrap().status = RobotStatus::Destroyed;
         game.update_simulation();
         assert!(game.game_over);
         assert_eq!(game.winner, None);
+        let result = game.match_result();
+        assert_eq!(result.winning_team, Some(1));
+        assert!(result.to_json().contains("\"winning_team\": 1,"));
+
+        // Survivors without a team still have to fight it out
+        let robots = vec![
+            dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.9, y: 0.9 }, RobotStatus::Active),
+        ];
+        let mut game = test_game(robots);
+        game.update_simulation();
+        assert!(!game.game_over);
+    }
+
+    #[test]
+    fn test_new_rejects_zero_max_turns() {
+        let result = Game::new(
+            &[],
+            0,
+            &InitialHeading::Center,
+            &[],
+            None,
+            AudioManager::new(),
+        );
+        assert!(result.is_err());
+        assert!(
+            result
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("max_turns must be at least 1")
+        );
+    }
+
+    #[test]
+    fn test_headless_step_and_state_json() {
+        let mut game = test_game(Vec::new());
+        let shooter = "select 2\nstart:\nfire 0.5\njmp start";
+        assert_eq!(game.load_robot("shooter", shooter, None), Ok(1));
+        assert_eq!(
+            game.load_robot("idler", "start:\nnop\njmp start", None),
+            Ok(2)
+        );
+        assert!(game.load_robot("broken", "bogus 1", None).is_err());
+
+        game.step_turn();
+        assert_eq!((game.current_turn, game.current_cycle), (2, 0));
+
+        let state: serde_json::Value = serde_json::from_str(&game.state_json()).unwrap();
+        assert_eq!(state["turn"], 2);
+        assert_eq!(state["winner"], serde_json::Value::Null);
+        assert_eq!(state["robots"][0]["name"], "shooter");
+        assert_eq!(state["robots"][0]["status"], "active");
+        assert_eq!(state["projectiles"][0]["source_robot"], 1);
+    }
+
+    #[test]
+    fn test_load_robot_takes_next_id_and_free_corner() {
+        let mut game = test_game(vec![
+            dummy_robot(1, start_position(&Arena::new(), 0), RobotStatus::Active),
+            dummy_robot(3, start_position(&Arena::new(), 1), RobotStatus::Active),
+        ]);
+        game.seed_coins(7);
+        game.seed_random(7);
+        game.set_teams(&[1, 0]);
+
+        // Ids continue past the highest one in play; the robot takes the first empty corner
+        assert_eq!(game.load_robot("late", "nop", Some(90.0)), Ok(4));
+        let robot = game.robots.last().unwrap();
+        assert_eq!(robot.position, start_position(&game.arena, 2));
+        assert_eq!(robot.drive.direction, 90.0);
+        assert_eq!(robot.coin, crate::robot::coin_flip(7, 4));
+        assert_eq!(robot.team, 0);
+        assert!(game.robots[0].teammates.is_empty());
+
+        assert_eq!(game.load_robot("last", "nop", None), Ok(5));
+        assert!(game.load_robot("extra", "nop", None).is_err());
+
+        // A corner inside an obstacle is rejected like a blocked map spawn
+        let mut game = test_game(Vec::new());
+        let (grid_x, grid_y) = game.arena.world_to_grid(start_position(&game.arena, 0));
+        let mut rows = vec![".".repeat(20); 20];
+        rows[grid_y as usize].replace_range(grid_x as usize..grid_x as usize + 1, "#");
+        game.load_map(&rows.join("\n")).unwrap();
+        let error = game.load_robot("blocked", "nop", None).unwrap_err();
+        assert!(error.contains("inside an obstacle"));
+        assert!(game.robots.is_empty());
+    }
+
+    #[test]
+    fn test_match_result_json() {
+        let mut game = test_game(vec![
+            dummy_robot(1, Point { x: 0.2, y: 0.2 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.8, y: 0.8 }, RobotStatus::Destroyed),
+        ]);
+        game.robots[0].name = "bot \"one\"".to_string();
+        game.robots[0].shots_fired = 3;
+        game.robots[0].damage_dealt = 12.5;
+        game.update_simulation();
+
+        let json: serde_json::Value = serde_json::from_str(&game.match_result().to_json()).unwrap();
+        assert_eq!(json["winner"], 1);
+        assert_eq!(
+            json["robots"][0],
+            json!({
+                "id": 1,
+                "name": "bot \"one\"",
+                "health": 100.0,
+                "status": "Active",
+                "turns_survived": 0,
+                "shots_fired": 3,
+                "damage_dealt": 12.5,
+            })
+        );
+        assert_eq!(json["robots"][1]["name"], "TestRobot_2");
+        assert_eq!(json["robots"][1]["status"], "Destroyed");
+
+        // JSON has no NaN, so a corrupted stat is written as null
+        let mut result = game.match_result();
+        result.robots[1].damage_dealt = f64::NAN;
+        let json: serde_json::Value = serde_json::from_str(&result.to_json()).unwrap();
+        assert_eq!(json["robots"][1]["damage_dealt"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_overtime_boosts_damage() {
+        // Damage two overlapping robots take from running into each other at `turn`
+        let collision_damage = |turn: u32| {
+            let mut game = test_game(vec![
+                dummy_robot(1, Point { x: 0.5, y: 0.5 }, RobotStatus::Active),
+                dummy_robot(2, Point { x: 0.52, y: 0.5 }, RobotStatus::Active),
+            ]);
+            game.max_turns = 200;
+            game.overtime = true;
+            game.current_turn = turn;
+            game.update_simulation();
+            config::DEFAULT_INITIAL_HEALTH - game.robots[0].health
+        };
+
+        let regular = collision_damage(50);
+        let early_overtime = collision_damage(120);
+        let late_overtime = collision_damage(180);
+        assert!((regular - config::COLLISION_DAMAGE).abs() < 1e-9);
+        assert!(early_overtime > regular);
+        assert!(late_overtime > early_overtime);
+        assert!((late_overtime - config::COLLISION_DAMAGE * 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_seeded_matches_are_identical() {
+        let bot = |name: &str| format!("{}/bots/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let robot_files = [bot("chaos.rasm"), bot("jojo.rasm")];
+        let run = |seed: u64| {
+            let mut game = Game::new(
+                &robot_files,
+                3,
+                &InitialHeading::Center,
+                &[],
+                Some(seed),
+                AudioManager::new(),
+            )
+            .unwrap();
+            game.place_obstacles();
+            game.replay = Some(Vec::new());
+            game.run_headless();
+            (game.arena.obstacles, game.replay.unwrap())
+        };
+
+        let (obstacles, trace) = run(7);
+        assert!(!obstacles.is_empty());
+        assert_eq!(run(7), (obstacles.clone(), trace));
+        assert_ne!(run(8).0, obstacles);
+    }
+
+    #[test]
+    fn test_seed_reproduces_robot_randomness() {
+        let path = std::env::temp_dir().join(format!("botarena_seed_{}.rasm", std::process::id()));
+        fs::write(
+            &path,
+            "randrange 0 1000\nmov @d0 @result\nrand_normal 0 10\npop @d1\nmov @d2 @coin\n",
+        )
+        .unwrap();
+        let robot_files = vec![path.to_string_lossy().into_owned(); 2];
+        // Registers of both robots after the program has run, and their headings
+        let run = |seed: u64| {
+            let mut game = Game::new(
+                &robot_files,
+                3,
+                &InitialHeading::Random(None),
+                &[],
+                Some(seed),
+                AudioManager::new(),
+            )
+            .unwrap();
+            for _ in 0..10 {
+                game.update_simulation();
+            }
+            game.robots
+                .iter()
+                .map(|robot| {
+                    let registers = &robot.vm_state.registers;
+                    (
+                        registers.get(Register::D0).unwrap(),
+                        registers.get(Register::D1).unwrap(),
+                        robot.drive.direction,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = run(1);
+        assert_eq!(run(1), first);
+        assert_ne!(run(2), first);
+        // Each robot draws its own sequence
+        assert_ne!(first[0].0, first[1].0);
+        fs::remove_file(&path).unwrap();
+    }
+
+    // Builds a random program that always parses: a mix of stack, math, memory,
+    // control flow and component instructions over random operands, with every
+    // jump aimed at one of a handful of labels placed through the program
+    fn fuzz_program(rng: &mut StdRng) -> String {
+        const LABELS: usize = 4;
+        const READ_REGISTERS: [&str; 8] = [
+            "@health",
+            "@power",
+            "@target_distance",
+            "@target_direction",
+            "@rand",
+            "@turn",
+            "@fault",
+            "@result",
+        ];
+        const UNARY: [&str; 15] = [
+            "sqrt", "log", "sin", "cos", "tan", "asin", "acos", "atan", "abs", "neg", "floor",
+            "ceil", "round", "not", "dbg",
+        ];
+        const BINARY: [&str; 13] = [
+            "add", "sub", "mul", "div", "mod", "min", "max", "pow", "atan2", "and", "or", "xor",
+            "shl",
+        ];
+        const JUMPS: [&str; 10] = [
+            "jmp", "jz", "jnz", "jl", "jle", "jg", "jge", "je", "jne", "loop",
+        ];
+        const NULLARY: [&str; 11] = [
+            "dup", "swap", "pop", "scan", "deselect", "whoami", "ret", "nop", "divmod", "add",
+            "neg",
+        ];
+
+        fn pick<'a>(rng: &mut StdRng, items: &[&'a str]) -> &'a str {
+            items[rng.gen_range(0..items.len())]
+        }
+        fn data_register(rng: &mut StdRng) -> String {
+            format!("@d{}", rng.gen_range(0..19))
+        }
+        fn operand(rng: &mut StdRng) -> String {
+            match rng.gen_range(0..4) {
+                0 => data_register(rng),
+                1 => pick(rng, &READ_REGISTERS).to_string(),
+                2 => format!("{}", rng.gen_range(-3..=3)),
+                _ => format!("{:.3}", rng.gen_range(-1000.0..1000.0)),
+            }
+        }
+
+        let length = rng.gen_range(5..60);
+        let label_at: Vec<usize> = (0..LABELS).map(|_| rng.gen_range(0..length)).collect();
+        let mut lines = Vec::new();
+        for line in 0..length {
+            for (label, _) in label_at.iter().enumerate().filter(|(_, at)| **at == line) {
+                lines.push(format!("l{}:", label));
+            }
+            let label = format!("l{}", rng.gen_range(0..LABELS));
+            lines.push(match rng.gen_range(0..14) {
+                0 => format!("push {}", operand(rng)),
+                1 => format!("pop {}", data_register(rng)),
+                2 => format!("mov {} {}", data_register(rng), operand(rng)),
+                3 => format!("{} {}", pick(rng, &UNARY), operand(rng)),
+                4 => format!("{} {} {}", pick(rng, &BINARY), operand(rng), operand(rng)),
+                5 => format!("cmp {} {}", operand(rng), operand(rng)),
+                6 => format!("{} {}", pick(rng, &JUMPS), label),
+                7 => format!("call {}", label),
+                8 => format!("select {}", rng.gen_range(-1..=3)),
+                9 => format!("{} {}", pick(rng, &["rotate", "drive"]), operand(rng)),
+                10 => format!(
+                    "{} {}",
+                    pick(rng, &["fire", "mortar", "beam", "detonate"]),
+                    operand(rng)
+                ),
+                11 => match rng.gen_range(0..4) {
+                    0 => format!("sto {}", operand(rng)),
+                    1 => format!("lod {}", data_register(rng)),
+                    2 => format!("movidx {} {}", operand(rng), operand(rng)),
+                    _ => format!("lodidx {} {}", data_register(rng), operand(rng)),
+                },
+                12 => format!("clamp {} {} {}", operand(rng), operand(rng), operand(rng)),
+                _ => pick(rng, &NULLARY).to_string(),
+            });
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_fuzz_matches_never_panic_and_always_end() {
+        const MATCHES: u64 = 300;
+        for seed in 0..MATCHES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let programs: Vec<String> = (0..rng.gen_range(2..=4))
+                .map(|_| fuzz_program(&mut rng))
+                .collect();
+
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let mut game = test_game(Vec::new());
+                game.max_turns = 3;
+                game.rng = StdRng::seed_from_u64(seed);
+                for (index, program) in programs.iter().enumerate() {
+                    if let Err(e) = game.load_robot(&format!("fuzz{}", index), program, None) {
+                        panic!("generated program failed to parse: {}", e);
+                    }
+                }
+                // Every cycle advances the clock, so a match that outlives this
+                // many updates is stuck
+                let cycle_limit = game.max_turns * config::CYCLES_PER_TURN + 1;
+                let mut updates = 0;
+                while game.current_turn <= game.max_turns && !game.game_over {
+                    assert!(updates < cycle_limit, "match did not end within max_turns");
+                    game.update_simulation();
+                    updates += 1;
+                }
+            }));
+            if outcome.is_err() {
+                panic!(
+                    "fuzz match {} failed; programs:\n{}",
+                    seed,
+                    programs.join("\n----\n")
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_register_presets_applied_at_load() {
+        let preset: RegisterPreset = "1:@d0=5,@d1=-2.5".parse().unwrap();
+        let robot_file = concat!(env!("CARGO_MANIFEST_DIR"), "/bots/square.rasm").to_string();
+        let game = Game::new(
+            &[robot_file],
+            10,
+            &InitialHeading::Center,
+            &[preset],
+            None,
+            AudioManager::new(),
+        )
+        .unwrap();
+
+        let registers = &game.robots[0].vm_state.registers;
+        assert_eq!(registers.get(Register::D0).unwrap(), 5.0);
+        assert_eq!(registers.get(Register::D1).unwrap(), -2.5);
+    }
+
+    #[test]
+    fn test_register_preset_parsing_rejects_bad_specs() {
+        assert!("1:@health=5".parse::<RegisterPreset>().is_err()); // Read-only
+        assert!("1:@bogus=5".parse::<RegisterPreset>().is_err());
+        assert!("1:@d0=abc".parse::<RegisterPreset>().is_err());
+        assert!("@d0=5".parse::<RegisterPreset>().is_err());
+    }
+
+    #[test]
+    fn test_alive_cycles_tracking() {
+        let mut game = test_game(vec![
+            dummy_robot(1, Point { x: 0.1, y: 0.1 }, RobotStatus::Active),
+            dummy_robot(2, Point { x: 0.5, y: 0.5 }, RobotStatus::Active),
+            dummy_robot(3, Point { x: 0.9, y: 0.9 }, RobotStatus::Active),
+        ]);
+        for _ in 0..5 {
+            game.update_simulation();
+        }
+        // Robot 2 is destroyed partway through the match
+        game.robots[1].status = RobotStatus::Destroyed;
+        for _ in 0..5 {
+            game.update_simulation();
+        }
+
+        let result = game.match_result();
+        assert_eq!(result.total_cycles, 10);
+        let alive = |id: u32| {
+            result
+                .robots
+                .iter()
+                .find(|r| r.id == id)
+                .map(|r| r.alive_cycles)
+                .unwrap()
+        };
+        assert_eq!(alive(1), 10);
+        assert_eq!(alive(3), 10);
+        assert_eq!(alive(2), 5);
+        assert!(alive(2) < result.total_cycles);
+    }
+
+    #[test]
+    fn test_debug_focus_cycles_through_robots() {
+        let center = Point { x: 0.5, y: 0.5 };
+        let mut game = test_game(vec![
+            dummy_robot(1, center, RobotStatus::Active),
+            dummy_robot(2, center, RobotStatus::Active),
+        ]);
+        assert_eq!(game.debug_focus, 0);
+        game.cycle_debug_focus();
+        assert_eq!(game.debug_focus, 1);
+        game.cycle_debug_focus();
+        assert_eq!(game.debug_focus, 0);
+    }
+
+    #[test]
+    fn test_event_stream_records_scripted_match() {
+        let path =
+            std::env::temp_dir().join(format!("botarena_events_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut shooter = dummy_robot(1, Point { x: 0.3, y: 0.5 }, RobotStatus::Active);
+        shooter.load_program(
+            crate::vm::parser::parse_assembly("select 2\nstart:\nscan\nfire 1.0\njmp start", None)
+                .unwrap(),
+        );
+        let mut target = dummy_robot(2, Point { x: 0.6, y: 0.5 }, RobotStatus::Active);
+        target.health = 5.0;
+        let mut game = test_game(vec![shooter, target]);
+        game.event_stream = Some(EventStream::open(path.to_str().unwrap()).unwrap());
+
+        for _ in 0..200 {
+            game.update_simulation();
+            if game.game_over {
+                break;
+            }
+        }
+        assert_eq!(game.winner, Some(1));
+        game.event_stream.as_mut().unwrap().flush().unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(
+            lines
+                .iter()
+                .all(|line| line.starts_with("{\"turn\":") && line.ends_with('}'))
+        );
+        let has = |needle: &str| lines.iter().any(|line| line.contains(needle));
+        assert!(has(
+            "\"event\":\"scan_performed\",\"robot_id\":1,\"target_id\":2"
+        ));
+        assert!(has("\"event\":\"projectile_spawned\",\"robot_id\":1"));
+        assert!(has(
+            "\"event\":\"robot_hit\",\"robot_id\":2,\"source_robot\":1"
+        ));
+        // The kill is the last thing that happens
+        assert!(
+            lines
+                .last()
+                .unwrap()
+                .ends_with("\"event\":\"robot_destroyed\",\"robot_id\":2,\"source_robot\":1}")
+        );
     }
 }
@@ -0,0 +1,103 @@
+// Bot Arena as a library: the simulation and rendering modules the desktop
+// binary (main.rs) is built from, plus `WasmGame`, a headless match for
+// embedding the arena in a web page
+
+pub mod action_log;
+pub mod arena;
+pub mod assets;
+pub mod audio;
+pub mod config;
+pub mod debug_console;
+pub mod events;
+pub mod game;
+pub mod logging;
+pub mod particles;
+pub mod render;
+pub mod render_profile;
+pub mod replay;
+pub mod robot;
+pub mod standings;
+pub mod training;
+pub mod types;
+pub mod utils;
+pub mod vm;
+
+use crate::audio::AudioManager;
+use crate::game::{Game, InitialHeading};
+
+/// A match driven from JavaScript: robots are loaded from source text, the match
+/// advances one turn per `step` and `get_state` returns the JSON snapshot a
+/// renderer draws each frame from (see `Game::state_json`). Nothing here touches
+/// the window; drawing is left to the page.
+pub struct WasmGame {
+    game: Game,
+}
+
+impl WasmGame {
+    /// An empty match lasting up to `max_turns` turns, with every random stream
+    /// derived from `seed`
+    pub fn new(max_turns: u32, seed: u64) -> Result<Self, String> {
+        let game = Game::new(
+            &[],
+            max_turns,
+            &InitialHeading::Center,
+            &[],
+            Some(seed),
+            AudioManager::new(),
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(WasmGame { game })
+    }
+
+    /// Parses `source` and adds the robot in the next free corner, facing the
+    /// arena center. Returns the robot's id, or the parse error.
+    pub fn load_robot(&mut self, name: &str, source: &str) -> Result<u32, String> {
+        self.game.load_robot(name, source, None)
+    }
+
+    /// Advances the match by one turn; does nothing once it is over
+    pub fn step(&mut self) {
+        self.game.step_turn();
+    }
+
+    /// JSON snapshot of the turn, robots and projectiles in flight
+    pub fn get_state(&self) -> String {
+        self.game.state_json()
+    }
+
+    /// True once the match has a winner, ended in a draw or ran out of turns
+    pub fn is_over(&self) -> bool {
+        self.game.is_game_over() || self.game.current_turn > self.game.max_turns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_game_runs_robots_from_source() {
+        let mut game = WasmGame::new(2, 7).unwrap();
+        assert_eq!(
+            game.load_robot("spinner", "start:\nrotate 10\njmp start"),
+            Ok(1)
+        );
+        assert_eq!(game.load_robot("idler", "start:\nnop\njmp start"), Ok(2));
+        assert!(game.load_robot("broken", "bogus 1").is_err());
+
+        game.step();
+        let state = game.get_state();
+        assert!(state.contains("\"turn\": 2,"));
+        assert!(state.contains("\"name\": \"spinner\""));
+        assert!(!game.is_over());
+
+        game.step();
+        assert!(game.is_over());
+        // Stepping a finished match leaves it where it ended
+        game.step();
+        assert_eq!(
+            game.get_state(),
+            state.replace("\"turn\": 2,", "\"turn\": 3,")
+        );
+    }
+}
@@ -1,14 +1,17 @@
 use chrono::Local;
 use log::{LevelFilter, Metadata, Record, SetLoggerError};
-use std::collections::HashSet;
-use std::io::{self, Write};
-use std::sync::OnceLock;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 // Custom logger structure
 #[derive(Debug)]
 struct BotArenaLogger {
     level: LevelFilter,
     debug_filters: Option<HashSet<String>>,
+    robot_logs: Mutex<HashMap<u32, BufWriter<File>>>, // Per-robot files from --robot-logs
 }
 
 // Implement the log::Log trait for our custom logger
@@ -17,11 +20,10 @@ impl log::Log for BotArenaLogger {
         // Check if the record's level is enabled
         if metadata.level() <= self.level {
             // If we have debug filters, check if the target matches any filter
-            if let Some(filters) = &self.debug_filters {
-                if metadata.level() == log::Level::Debug || metadata.level() == log::Level::Trace {
-                    return filters.contains(metadata.target())
-                        || filters.iter().any(|f| metadata.target().starts_with(f));
-                }
+            if let Some(filters) = &self.debug_filters
+                && (metadata.level() == log::Level::Debug || metadata.level() == log::Level::Trace)
+            {
+                return filters_match(filters, metadata.target());
             }
             return true;
         }
@@ -29,6 +31,23 @@ impl log::Log for BotArenaLogger {
     }
 
     fn log(&self, record: &Record) {
+        // Each robot's debug output also goes to its own file, whatever the stdout filters
+        if record.level() <= log::Level::Debug
+            && let (Some(robot_id), _) = split_robot_target(record.target())
+            && let Some(file) = self.robot_logs.lock().unwrap().get_mut(&robot_id)
+        {
+            let timestamp = Local::now().format("%H:%M:%S%.3f");
+            // A failed write must not take the match down with it
+            let _ = writeln!(
+                file,
+                "{} {:5} {}: {}",
+                timestamp,
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+
         if self.enabled(record.metadata()) {
             let level_color = match record.level() {
                 log::Level::Error => "\x1B[31m", // Red
@@ -48,38 +67,30 @@ impl log::Log for BotArenaLogger {
             let mut cycle: Option<u32> = None;
 
             // Check if target has robot_id format (robot_N)
-            if let Some(id_str) = record.target().strip_prefix("robot_") {
-                if let Ok(id) = id_str.parse::<u32>() {
-                    robot_id = Some(id);
-                }
+            if let Some(id_str) = record.target().strip_prefix("robot_")
+                && let Ok(id) = id_str.parse::<u32>()
+            {
+                robot_id = Some(id);
             }
 
             // Look for robot ID, turn, and cycle patterns in the message
             let message = record.args().to_string();
 
             // Look for "Robot N" pattern
-            if robot_id.is_none() {
-                if let Some(robot_idx) = message.find("Robot ") {
-                    if let Some(end_idx) =
-                        message[robot_idx + 6..].find(|c: char| !c.is_ascii_digit())
-                    {
-                        if let Ok(id) =
-                            message[robot_idx + 6..robot_idx + 6 + end_idx].parse::<u32>()
-                        {
-                            robot_id = Some(id);
-                        }
-                    }
-                }
+            if robot_id.is_none()
+                && let Some(robot_idx) = message.find("Robot ")
+                && let Some(end_idx) = message[robot_idx + 6..].find(|c: char| !c.is_ascii_digit())
+                && let Ok(id) = message[robot_idx + 6..robot_idx + 6 + end_idx].parse::<u32>()
+            {
+                robot_id = Some(id);
             }
 
             // Look for Cycle N pattern
-            if let Some(cycle_idx) = message.find("Cycle ") {
-                if let Some(end_idx) = message[cycle_idx + 6..].find(|c: char| !c.is_ascii_digit())
-                {
-                    if let Ok(c) = message[cycle_idx + 6..cycle_idx + 6 + end_idx].parse::<u32>() {
-                        cycle = Some(c);
-                    }
-                }
+            if let Some(cycle_idx) = message.find("Cycle ")
+                && let Some(end_idx) = message[cycle_idx + 6..].find(|c: char| !c.is_ascii_digit())
+                && let Ok(c) = message[cycle_idx + 6..cycle_idx + 6 + end_idx].parse::<u32>()
+            {
+                cycle = Some(c);
             }
 
             // Create context prefix with available information
@@ -111,10 +122,10 @@ impl log::Log for BotArenaLogger {
             );
 
             // Add module path if available and different from target
-            if let Some(module_path) = record.module_path() {
-                if module_path != record.target() {
-                    output.push_str(&format!(" [{}]", module_path));
-                }
+            if let Some(module_path) = record.module_path()
+                && module_path != record.target()
+            {
+                output.push_str(&format!(" [{}]", module_path));
             }
 
             let mut stdout = io::stdout();
@@ -125,6 +136,9 @@ impl log::Log for BotArenaLogger {
 
     fn flush(&self) {
         io::stdout().flush().expect("Failed to flush stdout");
+        for file in self.robot_logs.lock().unwrap().values_mut() {
+            let _ = file.flush();
+        }
     }
 }
 
@@ -145,6 +159,7 @@ pub fn init_logger(level: LevelFilter, debug_filter: Option<String>) -> Result<(
         let logger = BotArenaLogger {
             level,
             debug_filters,
+            robot_logs: Mutex::new(HashMap::new()),
         };
 
         // Try to set the logger
@@ -155,14 +170,101 @@ pub fn init_logger(level: LevelFilter, debug_filter: Option<String>) -> Result<(
     log::set_logger(LOGGER.get().unwrap()).map(|()| log::set_max_level(level))
 }
 
+// Log file for one robot under --robot-logs, e.g. "robot02_chaos.log"
+fn robot_log_path(dir: &Path, robot_id: u32, name: &str) -> PathBuf {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("robot{:02}_{}.log", robot_id, name))
+}
+
+/// Creates one log file per robot in `dir` and routes every record carrying that
+/// robot's id (the `debug_*!` macros) to it, on top of the normal stdout output.
+/// Robot debug output is captured even when the stdout level is below debug.
+pub fn open_robot_logs<'a>(
+    dir: &Path,
+    robots: impl IntoIterator<Item = (u32, &'a str)>,
+) -> io::Result<()> {
+    let Some(logger) = LOGGER.get() else {
+        return Err(io::Error::other("logger is not initialized"));
+    };
+    std::fs::create_dir_all(dir)?;
+    let mut files = HashMap::new();
+    for (robot_id, name) in robots {
+        let file = File::create(robot_log_path(dir, robot_id, name))?;
+        files.insert(robot_id, BufWriter::new(file));
+    }
+    *logger.robot_logs.lock().unwrap() = files;
+    log::set_max_level(logger.level.max(LevelFilter::Debug));
+    Ok(())
+}
+
+// Builds the per-robot log target used by the debug macros, e.g. "robot2::drive"
+pub fn robot_target(robot_id: u32, topic: &str) -> String {
+    format!("robot{}::{}", robot_id, topic)
+}
+
+// Splits a per-robot target ("robot2::drive") into its robot id and topic
+fn split_robot_target(target: &str) -> (Option<u32>, &str) {
+    if let Some((id, topic)) = target
+        .strip_prefix("robot")
+        .and_then(|rest| rest.split_once("::"))
+        && let Ok(id) = id.parse::<u32>()
+    {
+        return (Some(id), topic);
+    }
+    (None, target)
+}
+
+// Whether a log target passes the debug filters. Filters are either topics/target
+// prefixes (e.g. "drive") or robot selectors ("robot=2"). When both kinds are given
+// a target must match a robot selector AND a topic, so "robot=2,drive" shows only
+// robot 2's drive output.
+fn filters_match(filters: &HashSet<String>, target: &str) -> bool {
+    let (robot_id, topic) = split_robot_target(target);
+
+    let robot_filters: Vec<&str> = filters
+        .iter()
+        .filter_map(|f| f.strip_prefix("robot="))
+        .collect();
+    let robot_ok = robot_filters.is_empty()
+        || robot_id.is_some_and(|id| {
+            robot_filters
+                .iter()
+                .any(|f| f.parse::<u32>().is_ok_and(|f| f == id))
+        });
+
+    let mut topic_filters = filters
+        .iter()
+        .filter(|f| !f.starts_with("robot="))
+        .peekable();
+    let topic_ok = topic_filters.peek().is_none()
+        || topic_filters.any(|f| topic.starts_with(f.as_str()) || target.starts_with(f.as_str()));
+
+    robot_ok && topic_ok
+}
+
+// Whether instruction tracing is enabled for a robot. With no trace target every
+// robot is traced; otherwise only the selected robot id is.
+pub fn should_trace(trace_robot: Option<u32>, robot_id: u32) -> bool {
+    trace_robot.is_none_or(|id| id == robot_id)
+}
+
 // Helper macros for specific debug topics
 #[macro_export]
 macro_rules! debug_vm {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "vm", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "vm"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "vm", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "vm"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "vm", "{}", format_args!($($arg)*))
@@ -172,10 +274,10 @@ macro_rules! debug_vm {
 #[macro_export]
 macro_rules! debug_robot {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "robot", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "robot"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "robot", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "robot"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "robot", "{}", format_args!($($arg)*))
@@ -185,10 +287,10 @@ macro_rules! debug_robot {
 #[macro_export]
 macro_rules! debug_drive {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "drive", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "drive"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "drive", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "drive"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "drive", "{}", format_args!($($arg)*))
@@ -198,10 +300,10 @@ macro_rules! debug_drive {
 #[macro_export]
 macro_rules! debug_weapon {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "weapon", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "weapon"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "weapon", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "weapon"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "weapon", "{}", format_args!($($arg)*))
@@ -211,10 +313,10 @@ macro_rules! debug_weapon {
 #[macro_export]
 macro_rules! debug_scan {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "scan", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "scan"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "scan", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "scan"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "scan", "{}", format_args!($($arg)*))
@@ -224,10 +326,10 @@ macro_rules! debug_scan {
 #[macro_export]
 macro_rules! debug_instructions {
     ($robot_id:expr, $turn:expr, $cycle:expr, $($arg:tt)*) => {
-        log::debug!(target: "instructions", "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "instructions"), "[R{:02}][T{:03}][C{:02}] {}", $robot_id, $turn, $cycle, format_args!($($arg)*))
     };
     ($robot_id:expr, $($arg:tt)*) => {
-        log::debug!(target: "instructions", "[R{:02}] {}", $robot_id, format_args!($($arg)*))
+        log::debug!(target: &$crate::logging::robot_target($robot_id, "instructions"), "[R{:02}] {}", $robot_id, format_args!($($arg)*))
     };
     ($($arg:tt)*) => {
         log::debug!(target: "instructions", "{}", format_args!($($arg)*))
@@ -235,3 +337,102 @@ macro_rules! debug_instructions {
 }
 
 // Robot ID-specific logging functions have been removed as they are not used in the codebase
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Keeps the target and message of the records this module's test emits
+    struct CaptureLogger(Mutex<Vec<(String, String)>>);
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &Record) {
+            let message = record.args().to_string();
+            // Other tests run alongside and log too
+            if message.contains("tagged ") {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((record.target().to_string(), message));
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    // Reads the "[R02]" robot tag the debug macros put at the start of their messages
+    fn parse_robot_tag(message: &str) -> Option<u32> {
+        message.strip_prefix("[R")?.split_once(']')?.0.parse().ok()
+    }
+
+    fn filters(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_robot_filter_matches_only_that_robot() {
+        let robot2 = filters(&["robot=2"]);
+        assert!(filters_match(&robot2, &robot_target(2, "drive")));
+        assert!(filters_match(&robot2, &robot_target(2, "vm")));
+        assert!(!filters_match(&robot2, &robot_target(1, "drive")));
+        assert!(!filters_match(&robot2, &robot_target(12, "drive")));
+        assert!(!filters_match(&robot2, "botarena::arena"));
+    }
+
+    #[test]
+    fn test_robot_and_topic_filters_combine() {
+        let robot2_drive = filters(&["robot=2", "drive"]);
+        assert!(filters_match(&robot2_drive, &robot_target(2, "drive")));
+        assert!(!filters_match(&robot2_drive, &robot_target(2, "vm")));
+        assert!(!filters_match(&robot2_drive, &robot_target(3, "drive")));
+
+        // Topic-only filters still match every robot
+        let drive = filters(&["drive", "botarena::arena"]);
+        assert!(filters_match(&drive, &robot_target(1, "drive")));
+        assert!(filters_match(&drive, &robot_target(4, "drive")));
+        assert!(filters_match(&drive, "botarena::arena"));
+        assert!(!filters_match(&drive, &robot_target(1, "scan")));
+    }
+
+    #[test]
+    fn test_should_trace_without_target_traces_all() {
+        assert!(should_trace(None, 1));
+        assert!(should_trace(None, 4));
+    }
+
+    #[test]
+    fn test_should_trace_only_matching_robot() {
+        assert!(should_trace(Some(2), 2));
+        assert!(!should_trace(Some(2), 1));
+        assert!(!should_trace(Some(2), 3));
+    }
+
+    #[test]
+    fn test_debug_macros_tag_records_with_robot_id() {
+        static CAPTURE: OnceLock<CaptureLogger> = OnceLock::new();
+        let capture = CAPTURE.get_or_init(|| CaptureLogger(Mutex::new(Vec::new())));
+        log::set_logger(capture).expect("no other test installs a logger");
+        log::set_max_level(LevelFilter::Debug);
+
+        crate::debug_drive!(3, 12, 40, "tagged drive");
+        crate::debug_scan!(11, "tagged scan");
+        crate::debug_vm!(7, 1, 2, "tagged vm");
+        log::set_max_level(LevelFilter::Off);
+
+        let tagged = capture.0.lock().unwrap();
+        assert_eq!(tagged.len(), 3);
+        for ((target, message), id) in tagged.iter().zip([3, 11, 7]) {
+            assert_eq!(split_robot_target(target).0, Some(id));
+            assert_eq!(parse_robot_tag(message), Some(id));
+        }
+        assert!(parse_robot_tag("no tag here").is_none());
+    }
+
+    #[test]
+    fn test_robot_log_path_is_safe_file_name() {
+        let path = robot_log_path(Path::new("logs"), 2, "my bot/v2");
+        assert_eq!(path, Path::new("logs").join("robot02_my_bot_v2.log"));
+    }
+}
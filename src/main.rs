@@ -1,37 +1,41 @@
-mod arena;
-mod assets;
-mod audio;
-mod config;
-mod game;
-mod logging;
-mod particles;
-mod render;
-mod robot;
-mod types;
-mod utils;
-mod vm;
-
-use crate::config::{ARENA_WIDTH, UI_PANEL_WIDTH, WINDOW_HEIGHT};
+use botarena::action_log::ActionLog;
+use botarena::arena::Arena;
+use botarena::config::WINDOW_HEIGHT;
 use clap::Parser;
 use log::{LevelFilter, error, info};
 use macroquad::prelude::*;
 use std::process;
 
-use crate::audio::AudioManager;
-use crate::game::Game;
-use crate::logging::init_logger;
-use crate::render::Renderer;
+use botarena::audio::AudioManager;
+use botarena::debug_console::DebugConsole;
+use botarena::events::EventStream;
+use botarena::game::{Game, InitialHeading, RegisterPreset, TeamSpec};
+use botarena::logging::{init_logger, open_robot_logs};
+use botarena::particles::ParticleSystem;
+use botarena::render::{ArenaView, Renderer, UiPlacement};
+use botarena::replay::ReplayPlayer;
+use botarena::standings::DrawScoring;
+use botarena::{config, game, replay, standings, vm};
 
 // Command line arguments structure
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Paths to the robot program files (up to 4).
-    #[arg(required = true, num_args = 1..=4)]
+    #[arg(required_unless_present_any = ["compare_replays", "play_replay", "xref", "team"], num_args = 1..=4)]
     robot_files: Vec<String>,
 
+    /// Robot files that play as a team, e.g. 1:a.rasm,b.rasm (repeat for more teams).
+    /// Teammates' shots pass through each other.
+    #[arg(long, value_name = "TEAM:FILE,...")]
+    team: Vec<TeamSpec>,
+
+    /// Make scans look past teammates, so only enemies are found
+    #[arg(long)]
+    scan_skips_teammates: bool,
+
     /// Maximum number of turns for the simulation.
-    #[arg(short, long, default_value_t = 1000)]
+    #[arg(short, long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
     max_turns: u32,
 
     /// Log level (off, error, warn, info, debug, trace).
@@ -46,15 +50,173 @@ struct Args {
     #[arg(long)]
     no_obstacles: bool,
 
+    /// Load the arena layout from a map file (an ASCII grid of '#' and '.', or exported JSON)
+    /// instead of placing random obstacles
+    #[arg(long, value_name = "PATH")]
+    map: Option<String>,
+
+    /// Write the arena layout (dimensions and obstacles) to a JSON map file
+    #[arg(long, value_name = "PATH")]
+    export_map: Option<String>,
+
     /// Disable sound effects
     #[arg(long)]
     no_audio: bool,
+
+    /// Sound effect volume, from 0.0 (silent) to 1.0 (full); press M in game to mute
+    #[arg(long, value_name = "0.0-1.0", default_value_t = 1.0, value_parser = parse_volume)]
+    volume: f32,
+
+    /// Comma-separated initial headings in degrees, one per robot (default: face arena center)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        allow_negative_numbers = true,
+        conflicts_with = "random_headings"
+    )]
+    headings: Option<Vec<f64>>,
+
+    /// Start robots at random headings, generated from the given seed or else from --seed
+    #[arg(long, value_name = "SEED", num_args = 0..=1)]
+    random_headings: Option<Option<u64>>,
+
+    /// Initial register values for a robot, e.g. 1:@d0=5,@d1=10 (repeat for more robots)
+    #[arg(long, value_name = "ID:@REG=VALUE,...")]
+    preset_registers: Vec<RegisterPreset>,
+
+    /// Only emit instruction trace logging for the robot with this id
+    #[arg(long, value_name = "ID")]
+    trace_robot: Option<u32>,
+
+    /// Seed for every random choice in the match (obstacles, @rand, @coin, rand_normal,
+    /// randrange, sensor noise, random headings), making the whole match reproducible
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+
+    /// Override the seed for the per-robot @coin tiebreaker register
+    #[arg(long, value_name = "SEED")]
+    coin_seed: Option<u64>,
+
+    /// Override the seed for the per-robot RNG behind rand_normal, randrange and sensor noise
+    #[arg(long, value_name = "SEED")]
+    rand_seed: Option<u64>,
+
+    /// Obstacles take damage from projectiles and crumble once their health runs out
+    #[arg(long)]
+    destructible_obstacles: bool,
+
+    /// Destroyed obstacles drop rubble into neighbouring cells (with --destructible-obstacles)
+    #[arg(long)]
+    rubble: bool,
+
+    /// Destroyed robots shove nearby robots away
+    #[arg(long)]
+    explosions_push: bool,
+
+    /// Remove a destroyed robot's in-flight projectiles
+    #[arg(long)]
+    despawn_dead_shots: bool,
+
+    /// Drop health and energy pickups around the arena for robots to collect
+    #[arg(long)]
+    pickups: bool,
+
+    /// Boost all damage through the final turns of the match, growing every turn
+    #[arg(long)]
+    overtime: bool,
+
+    /// Destroyed robots respawn after a delay with full health; the match runs to max turns
+    #[arg(long)]
+    respawn: bool,
+
+    /// Reduce the drive turn rate as speed increases, so fast robots turn wider
+    #[arg(long)]
+    speed_turn_coupling: bool,
+
+    /// How drawn matches count in the standings: win0.5, loss or nopoints
+    #[arg(long, value_name = "POLICY", default_value = "win0.5")]
+    draw_scoring: DrawScoring,
+
+    /// Fault (@fault = 16) on bitwise operands beyond 2^53 instead of saturating them
+    #[arg(long)]
+    strict_bitwise: bool,
+
+    /// Move robots one grid cell per turn and turn them in 90 degree steps
+    #[arg(long)]
+    grid_movement: bool,
+
+    /// Clear @target_distance/@target_direction except right after a successful scan
+    #[arg(long)]
+    blind_targeting: bool,
+
+    /// Arm robots with a hit-scan laser: `fire` hits the first robot in line instantly
+    #[arg(long)]
+    laser_weapons: bool,
+
+    /// Run the robots' VM cycles in parallel on a thread pool (results match a serial run)
+    #[arg(long)]
+    parallel: bool,
+
+    /// Report exact scan readings instead of adding noise that grows with distance
+    #[arg(long)]
+    perfect_sensors: bool,
+
+    /// Where to put the UI panel: left, right or hidden (the arena gets the whole window)
+    #[arg(long, value_name = "PLACEMENT", default_value = "right")]
+    ui: UiPlacement,
+
+    /// Append match events (shots, hits, kills, scans) to this file as JSON lines
+    #[arg(long, value_name = "PATH")]
+    event_stream: Option<String>,
+
+    /// Also write each robot's debug output to its own log file in this directory
+    #[arg(long, value_name = "DIR")]
+    robot_logs: Option<String>,
+
+    /// Show a per-phase render timing overlay (scene, bright, blur, composite, UI)
+    #[arg(long)]
+    profile_render: bool,
+
+    /// Start with the glow post-processing off (toggle it in game with G)
+    #[arg(long)]
+    no_glow: bool,
+
+    /// Record a replay of the match to the given file
+    #[arg(long, value_name = "FILE")]
+    record_replay: Option<String>,
+
+    /// Write a per-turn hashed log of every robot's commands to the given file
+    #[arg(long, value_name = "FILE")]
+    action_log: Option<String>,
+
+    /// Write the match result (winner and per-robot stats) as JSON to the given file
+    #[arg(long, value_name = "FILE")]
+    results_json: Option<String>,
+
+    /// Replay an action log's commands without a window and check the final state against it
+    #[arg(long, value_name = "FILE", conflicts_with = "action_log")]
+    verify_action_log: Option<String>,
+
+    /// Compare two replay files and report the first cycle where they diverge
+    #[arg(long, num_args = 2, value_names = ["REPLAY_A", "REPLAY_B"])]
+    compare_replays: Option<Vec<String>>,
+
+    /// Play back a recorded replay; click or drag the timeline to jump to any cycle
+    #[arg(long, value_name = "FILE", conflicts_with = "compare_replays")]
+    play_replay: Option<String>,
+
+    /// Print a cross-reference of a program's labels and constants (where each is used)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["compare_replays", "play_replay"])]
+    xref: Option<String>,
 }
 
 fn window_conf() -> Conf {
+    // Runs before main, so the UI placement is read from the arguments here too;
+    // invalid arguments are reported once main parses them
+    let ui = Args::try_parse().map(|args| args.ui).unwrap_or_default();
     Conf {
         window_title: "Bot Arena".to_owned(),
-        window_width: (ARENA_WIDTH + UI_PANEL_WIDTH),
+        window_width: ui.layout().window_width,
         window_height: WINDOW_HEIGHT,
         high_dpi: true,
         ..Default::default()
@@ -88,6 +250,16 @@ async fn main() {
         process::exit(1);
     }
 
+    // Replay comparison is a standalone tool mode; no match is run
+    if let Some(paths) = &args.compare_replays {
+        process::exit(compare_replay_files(&paths[0], &paths[1]));
+    }
+
+    // So is the cross-reference report
+    if let Some(path) = &args.xref {
+        process::exit(print_xref(path));
+    }
+
     info!("Bot Arena starting...");
 
     // Create Renderer and load fonts
@@ -96,6 +268,16 @@ async fn main() {
     renderer.load_ui_font().await; // Load UI font
     renderer.init_glow_resources();
     renderer.init_scanner_material();
+    if args.profile_render {
+        renderer.enable_profiling();
+    }
+    renderer.set_ui_placement(args.ui);
+    renderer.set_glow_enabled(!args.no_glow);
+
+    // Replay playback draws recorded frames instead of running a match
+    if let Some(path) = &args.play_replay {
+        process::exit(play_replay_file(path, &mut renderer).await);
+    }
 
     // Create AudioManager
     let mut audio_manager = AudioManager::new();
@@ -103,18 +285,110 @@ async fn main() {
     if !args.no_audio {
         audio_manager.load_assets().await;
     }
+    audio_manager.set_volume(args.volume);
+
+    // Determine how robots are oriented at the start of the match
+    let initial_heading = match (args.headings, args.random_headings) {
+        (Some(headings), _) => InitialHeading::Fixed(headings),
+        (None, Some(seed)) => InitialHeading::Random(seed),
+        (None, None) => InitialHeading::Center,
+    };
+
+    // Team robots join the match after the free-for-all ones (team 0)
+    let mut robot_files = args.robot_files.clone();
+    let mut teams = vec![0; robot_files.len()];
+    for spec in &args.team {
+        robot_files.extend(spec.files.iter().cloned());
+        teams.extend(std::iter::repeat_n(spec.team, spec.files.len()));
+    }
 
     // Create Game instance (passing potentially empty audio_manager)
-    let mut game = match Game::new(&args.robot_files, args.max_turns, audio_manager) {
+    let mut game = match Game::new(
+        &robot_files,
+        args.max_turns,
+        &initial_heading,
+        &args.preset_registers,
+        args.seed,
+        audio_manager,
+    ) {
         Ok(g) => g,
         Err(e) => {
             error!("Failed to initialize game: {}", e);
             process::exit(1);
         }
     };
+    game.set_teams(&teams);
+    game.set_skip_teammates(args.scan_skips_teammates);
 
-    if !args.no_obstacles {
-        game.arena.place_obstacles();
+    if let Some(dir) = &args.robot_logs {
+        let robots = game.robots.iter().map(|r| (r.id, r.name.as_str()));
+        if let Err(e) = open_robot_logs(std::path::Path::new(dir), robots) {
+            error!("Failed to open robot logs in {}: {}", dir, e);
+            process::exit(1);
+        }
+    }
+
+    if let Some(path) = &args.map {
+        let loaded = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| game.load_map(&contents));
+        if let Err(e) = loaded {
+            error!("Failed to load map {}: {}", path, e);
+            process::exit(1);
+        }
+    } else if !args.no_obstacles {
+        game.place_obstacles();
+    }
+    if args.destructible_obstacles {
+        game.arena.make_obstacles_destructible();
+    }
+
+    if let Some(path) = &args.export_map {
+        match std::fs::write(path, game.arena.to_map_json()) {
+            Ok(()) => info!("Arena map exported to {}", path),
+            Err(e) => error!("Failed to export map to {}: {}", path, e),
+        }
+    }
+
+    game.trace_robot = args.trace_robot;
+    if let Some(seed) = args.coin_seed {
+        game.seed_coins(seed);
+    }
+    if let Some(seed) = args.rand_seed {
+        game.seed_random(seed);
+    }
+    game.explosions_push = args.explosions_push;
+    game.despawn_dead_shots = args.despawn_dead_shots;
+    game.pickups = args.pickups;
+    game.rubble = args.rubble;
+    game.parallel = args.parallel;
+    game.overtime = args.overtime;
+    game.set_speed_turn_coupling(args.speed_turn_coupling);
+    game.set_strict_bitwise(args.strict_bitwise);
+    game.set_blind_targeting(args.blind_targeting);
+    game.set_laser_weapons(args.laser_weapons);
+    game.set_sensor_noise(!args.perfect_sensors);
+    game.set_grid_movement(args.grid_movement);
+    game.set_respawn(args.respawn);
+    if args.record_replay.is_some() {
+        game.replay = Some(Vec::new());
+    }
+    if args.action_log.is_some() {
+        game.action_log = Some(ActionLog::new());
+    }
+    if let Some(path) = &args.event_stream {
+        match EventStream::open(path) {
+            Ok(stream) => game.event_stream = Some(stream),
+            Err(e) => {
+                error!("Failed to open event stream {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    // Verification replays the logged commands headless and compares the outcome
+    if let Some(path) = &args.verify_action_log {
+        process::exit(verify_action_log(path, &mut game));
     }
 
     // Run the game loop
@@ -123,5 +397,224 @@ async fn main() {
         process::exit(1);
     }
 
+    if let Some(stream) = game.event_stream.as_mut()
+        && let Err(e) = stream.flush()
+    {
+        error!("Failed to flush event stream: {}", e);
+    }
+
+    game.finish_action_log();
+    if let (Some(path), Some(log)) = (&args.action_log, &game.action_log) {
+        match std::fs::write(path, log.to_text()) {
+            Ok(()) => info!("Action log saved to {}", path),
+            Err(e) => error!("Failed to save action log to {}: {}", path, e),
+        }
+    }
+
+    if let (Some(path), Some(frames)) = (&args.record_replay, &game.replay) {
+        match replay::save_replay(path, frames) {
+            Ok(()) => info!("Replay saved to {}", path),
+            Err(e) => error!("Failed to save replay to {}: {}", path, e),
+        }
+    }
+
+    let result = game.match_result();
+    if let Some(path) = &args.results_json {
+        match std::fs::write(path, result.to_json()) {
+            Ok(()) => info!("Match results saved to {}", path),
+            Err(e) => error!("Failed to save match results to {}: {}", path, e),
+        }
+    }
+    info!("Match lasted {} cycles", result.total_cycles);
+    for robot in &result.robots {
+        info!(
+            "Robot {} ({}): alive for {} cycles{}",
+            robot.id,
+            robot.name,
+            robot.alive_cycles,
+            if robot.destroyed { ", destroyed" } else { "" }
+        );
+    }
+    for standing in standings::tally(&[result], args.draw_scoring) {
+        info!(
+            "Standings: {} ({}): {} points ({}W/{}D/{}L)",
+            standing.name,
+            standing.entrant,
+            standing.points,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        );
+    }
+
     info!("Bot Arena finished.");
 }
+
+// Replays a recorded action log headless and checks the match ends as logged.
+// Returns the process exit code: 0 when it matches, 1 when it does not.
+fn verify_action_log(path: &str, game: &mut Game) -> i32 {
+    let recorded = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| ActionLog::from_text(&text))
+    {
+        Ok(log) => log,
+        Err(e) => {
+            error!("Failed to load action log {}: {}", path, e);
+            return 2;
+        }
+    };
+
+    match game.verify_action_log(&recorded) {
+        Ok(()) => {
+            println!(
+                "Action log verified: {} commands replayed",
+                recorded.commands.len()
+            );
+            0
+        }
+        Err(e) => {
+            println!("Action log mismatch: {}", e);
+            1
+        }
+    }
+}
+
+// Plays a replay in the window until it is closed. Returns the process exit code.
+async fn play_replay_file(path: &str, renderer: &mut Renderer) -> i32 {
+    let frames = match replay::load_replay(path) {
+        Ok(frames) if !frames.is_empty() => frames,
+        Ok(_) => {
+            error!("Replay {} has no frames", path);
+            return 2;
+        }
+        Err(e) => {
+            error!("Failed to load replay {}: {}", path, e);
+            return 2;
+        }
+    };
+    let max_turns = frames.last().map_or(0, |frame| frame.turn);
+    let mut player = ReplayPlayer::new(frames);
+    let destruction_frames = player.destruction_frames();
+
+    // Replays record robots only, so the arena is drawn empty
+    let arena = Arena::new();
+    let particle_system = ParticleSystem::new();
+    let debug_console = DebugConsole::new(0);
+    let cycle_duration = 1.0 / config::CYCLES_PER_TURN as f32;
+    let mut time_accumulator = 0.0;
+    let mut scrubbing = false;
+
+    while !Renderer::window_should_close() {
+        let bar = renderer.replay_timeline_bar();
+        let (mouse_x, mouse_y) = mouse_position();
+        if is_mouse_button_pressed(MouseButton::Left) && bar.contains(vec2(mouse_x, mouse_y)) {
+            scrubbing = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            scrubbing = false;
+        }
+
+        if scrubbing {
+            player.seek(replay::timeline_frame_index(
+                mouse_x,
+                bar.x,
+                bar.w,
+                player.len(),
+            ));
+            time_accumulator = 0.0;
+        } else {
+            time_accumulator += get_frame_time();
+            while time_accumulator >= cycle_duration {
+                time_accumulator -= cycle_duration;
+                player.advance();
+            }
+        }
+
+        let Some(frame) = player.current() else {
+            break;
+        };
+        let robots = frame.to_robots(player.previous());
+        renderer.draw_frame(
+            &arena,
+            &robots,
+            &particle_system,
+            frame.turn,
+            max_turns,
+            frame.cycle,
+            config::CYCLES_PER_TURN,
+            time_accumulator,
+            cycle_duration,
+            None,
+            None,
+            &debug_console,
+            &ArenaView::default(),
+        );
+        renderer.draw_replay_timeline(bar, player.position(), player.len(), &destruction_frames);
+        next_frame().await;
+    }
+    0
+}
+
+// Accepts a volume between 0.0 and 1.0 for --volume
+fn parse_volume(s: &str) -> Result<f32, String> {
+    let volume: f32 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if (0.0..=1.0).contains(&volume) {
+        Ok(volume)
+    } else {
+        Err(format!(
+            "volume must be between 0.0 and 1.0, got {}",
+            volume
+        ))
+    }
+}
+
+// Parses a program and prints its label/constant cross-reference. Returns the process exit code.
+fn print_xref(path: &str) -> i32 {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            error!("Failed to read {}: {}", path, e);
+            return 2;
+        }
+    };
+    let constants = game::predefined_constants(&Arena::new());
+    match vm::parser::parse_assembly(&source, Some(&constants)) {
+        Ok(program) => {
+            for line in program.references.report_lines() {
+                println!("{}", line);
+            }
+            0
+        }
+        Err(e) => {
+            error!("Failed to parse {} ({}): {}", path, e.location(), e.message);
+            1
+        }
+    }
+}
+
+// Loads and compares two replays, printing the first divergence. Returns the process exit code.
+fn compare_replay_files(path_a: &str, path_b: &str) -> i32 {
+    let load = |path: &str| {
+        replay::load_replay(path).map_err(|e| error!("Failed to load replay {}: {}", path, e))
+    };
+    let (Ok(a), Ok(b)) = (load(path_a), load(path_b)) else {
+        return 2;
+    };
+
+    match replay::compare_replays(&a, &b) {
+        None => {
+            println!("Replays are identical ({} frames)", a.len());
+            0
+        }
+        Some(divergence) => {
+            println!(
+                "Replays diverge at frame {} (turn {}, cycle {}):",
+                divergence.frame, divergence.turn, divergence.cycle
+            );
+            for difference in &divergence.differences {
+                println!("  {}", difference);
+            }
+            1
+        }
+    }
+}
@@ -51,6 +51,12 @@ pub struct ParticleSystem {
 }
 
 // Implementation for ParticleSystem
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParticleSystem {
     pub fn new() -> Self {
         ParticleSystem {
@@ -1,9 +1,14 @@
 use crate::arena::*;
 use crate::assets::get_asset_bytes;
 use crate::config::{
-    ARENA_HEIGHT, ARENA_WIDTH, UI_PANEL_WIDTH, UNIT_SIZE, WINDOW_HEIGHT, WINDOW_WIDTH,
+    ARENA_HEIGHT, ARENA_MAX_ZOOM, ARENA_MIN_ZOOM, ARENA_PAN_SPEED, ARENA_WIDTH, ARENA_ZOOM_STEP,
+    DEBUG_CONSOLE_LINES, DEFAULT_PROJECTILE_SPEED, PROJECTILE_TRAIL_SEGMENTS, UI_PANEL_WIDTH,
+    UNIT_SIZE, WINDOW_HEIGHT,
 };
+use crate::debug_console::DebugConsole;
 use crate::particles::ParticleSystem;
+use crate::render_profile::{RenderPhase, RenderProfiler};
+use crate::replay;
 use crate::robot::Robot;
 use crate::types::*;
 use crate::utils;
@@ -13,10 +18,13 @@ use macroquad::miniquad::{
     TextureParams,
 };
 use macroquad::prelude::*;
+use std::str::FromStr;
 
 const BRIGHTNESS_THRESHOLD: f32 = 0.05;
 const BLUR_PASSES: usize = 2; // Keep blur passes low for now
 const GLOW_INTENSITY: f32 = 1.5; // Factor to multiply glow brightness
+const TIMELINE_MARGIN: f32 = 20.0; // Gap between the replay timeline and the arena edges
+const TIMELINE_HEIGHT: f32 = 12.0; // Height of the replay timeline bar
 
 // Conversion helpers
 fn point_to_vec2(p: Point, arena_screen_width: i32, arena_screen_height: i32) -> Vec2 {
@@ -42,7 +50,154 @@ fn brighten_color(color: Color, amount: f32) -> Color {
     )
 }
 
+/// Glow color of a pickup, also used for its collection sparkle
+pub fn pickup_color(kind: PickupKind) -> Color {
+    match kind {
+        PickupKind::Health => GREEN,
+        PickupKind::Energy => SKYBLUE,
+    }
+}
+
+// Accent ring color shared by a team's robots
+fn team_color(team: u8) -> Color {
+    match team % 4 {
+        1 => GOLD,
+        2 => VIOLET,
+        3 => LIME,
+        _ => PINK,
+    }
+}
+
+// Base body color for a robot id
+fn robot_color(robot_id: u32) -> Color {
+    match robot_id {
+        1 => Color::from_rgba(40, 80, 140, 255),
+        2 => Color::from_rgba(140, 40, 40, 255),
+        3 => Color::from_rgba(40, 100, 40, 255),
+        4 => Color::from_rgba(140, 120, 20, 255),
+        _ => Color::from_rgba(100, 50, 100, 255),
+    }
+}
+
+// Projectile color for the robot that fired it (a brightened body color so it
+// stands out against the arena); falls back to white for unknown sources
+fn projectile_color(robots: &[Robot], source_robot: u32) -> Color {
+    robots
+        .iter()
+        .find(|r| r.id == source_robot)
+        .map(|r| brighten_color(robot_color(r.id), 0.35))
+        .unwrap_or(WHITE)
+}
+
+/// Which side of the window the UI panel sits on, or whether it is shown at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiPlacement {
+    Left,
+    #[default]
+    Right,
+    Hidden, // The window shrinks to just the arena
+}
+
+impl FromStr for UiPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" => Ok(UiPlacement::Left),
+            "right" => Ok(UiPlacement::Right),
+            "hidden" => Ok(UiPlacement::Hidden),
+            _ => Err(format!(
+                "unknown UI placement '{}' (expected left, right or hidden)",
+                s
+            )),
+        }
+    }
+}
+
+/// Screen positions of the arena viewport and the UI panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub arena_x: f32,         // Left edge of the arena viewport
+    pub panel_x: Option<f32>, // Left edge of the UI panel (None when hidden)
+    pub window_width: i32,
+}
+
+impl UiPlacement {
+    pub fn layout(self) -> Layout {
+        let (arena_x, panel_x, window_width) = match self {
+            UiPlacement::Left => (UI_PANEL_WIDTH, Some(0), ARENA_WIDTH + UI_PANEL_WIDTH),
+            UiPlacement::Right => (0, Some(ARENA_WIDTH), ARENA_WIDTH + UI_PANEL_WIDTH),
+            UiPlacement::Hidden => (0, None, ARENA_WIDTH),
+        };
+        Layout {
+            arena_x: arena_x as f32,
+            panel_x: panel_x.map(|x| x as f32),
+            window_width,
+        }
+    }
+}
+
+// What a piece of text is for, which decides the font it uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FontRole {
+    Title,
+    Ui,
+}
+
+// The font text with the given role is both measured and drawn with. A title
+// without its font falls back to the UI font; None is macroquad's built-in font.
+fn resolve_font<T>(role: FontRole, title: Option<T>, ui: Option<T>) -> Option<T> {
+    match role {
+        FontRole::Title => title.or(ui),
+        FontRole::Ui => ui,
+    }
+}
+
 // Helper function to calculate health bar gradient color
+// Text lines of the pause overlay: where the robot's program is and what its registers hold
+fn debug_overlay_lines(robot: &Robot) -> Vec<String> {
+    let vm = &robot.vm_state;
+    let reg = |register: Register| vm.registers.get(register).unwrap_or(0.0);
+    let top = vm
+        .stack
+        .top()
+        .map_or("empty".to_string(), |value| format!("{:.3}", value));
+    let mut lines = vec![
+        format!("R{:02} {}", robot.id, robot.name),
+        format!(
+            "ip {:04}  {}",
+            vm.ip,
+            robot.get_current_instruction_string()
+        ),
+        format!("stack top: {}", top),
+        format!(
+            "@c {:.3}  @result {:.3}  @fault {:.0}  @index {:.0}",
+            reg(Register::C),
+            reg(Register::Result),
+            reg(Register::Fault),
+            reg(Register::Index)
+        ),
+    ];
+    // Data registers, four to a line
+    let data: Vec<String> = (0..19)
+        .filter_map(Register::data)
+        .enumerate()
+        .map(|(index, register)| format!("@d{:<2} {:>9.3}", index, reg(register)))
+        .collect();
+    for row in data.chunks(4) {
+        lines.push(row.join("  "));
+    }
+    lines
+}
+
+// Opacity of a projectile trail segment, `segment` cycles behind the head:
+// faster shots leave brighter trails, and each older segment is fainter
+fn trail_segment_alpha(speed: f64, segment: usize) -> f32 {
+    let brightness = (speed / DEFAULT_PROJECTILE_SPEED).clamp(0.2, 1.0) as f32;
+    let age = segment as f32 / PROJECTILE_TRAIL_SEGMENTS as f32;
+    0.6 * brightness * (1.0 - age)
+}
+
 fn get_health_gradient_color(ratio: f32) -> Color {
     if ratio > 0.5 {
         // Green to Yellow (1.0 -> 0.5)
@@ -55,6 +210,122 @@ fn get_health_gradient_color(ratio: f32) -> Color {
     }
 }
 
+/// Zoom and pan of the arena viewport. Positions are in arena pixels (as from
+/// `point_to_vec2`); the UI panel is never transformed.
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaView {
+    pub zoom: f32,           // 1.0 shows the whole arena
+    pub center: Vec2,        // Arena pixel shown at the middle of the viewport
+    drag_from: Option<Vec2>, // Mouse position while middle-dragging
+}
+
+impl Default for ArenaView {
+    fn default() -> Self {
+        ArenaView {
+            zoom: 1.0,
+            center: vec2(ARENA_WIDTH as f32 / 2.0, ARENA_HEIGHT as f32 / 2.0),
+            drag_from: None,
+        }
+    }
+}
+
+impl ArenaView {
+    /// Scales the zoom by `factor`, within ARENA_MIN_ZOOM..=ARENA_MAX_ZOOM
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.zoom = (self.zoom * factor).clamp(ARENA_MIN_ZOOM, ARENA_MAX_ZOOM);
+        self.clamp_center();
+    }
+
+    /// Moves the view by `delta` screen pixels
+    pub fn pan(&mut self, delta: Vec2) {
+        self.center += delta / self.zoom;
+        self.clamp_center();
+    }
+
+    // Keeps the viewport inside the arena
+    fn clamp_center(&mut self) {
+        let size = vec2(ARENA_WIDTH as f32, ARENA_HEIGHT as f32);
+        let half_view = size / (2.0 * self.zoom);
+        self.center = self.center.clamp(half_view, size - half_view);
+    }
+
+    /// Mouse wheel zooms, arrow keys or a middle-button drag pan, `R` resets the view
+    pub fn handle_input(&mut self, frame_time: f32) {
+        let (_, wheel) = mouse_wheel();
+        if wheel > 0.0 {
+            self.zoom_by(ARENA_ZOOM_STEP);
+        } else if wheel < 0.0 {
+            self.zoom_by(1.0 / ARENA_ZOOM_STEP);
+        }
+
+        let mut direction = Vec2::ZERO;
+        for (key, step) in [
+            (KeyCode::Left, vec2(-1.0, 0.0)),
+            (KeyCode::Right, vec2(1.0, 0.0)),
+            (KeyCode::Up, vec2(0.0, -1.0)),
+            (KeyCode::Down, vec2(0.0, 1.0)),
+        ] {
+            if is_key_down(key) {
+                direction += step;
+            }
+        }
+        if direction != Vec2::ZERO {
+            self.pan(direction * ARENA_PAN_SPEED * frame_time);
+        }
+
+        if is_mouse_button_down(MouseButton::Middle) {
+            let mouse = Vec2::from(mouse_position());
+            if let Some(from) = self.drag_from {
+                // The arena follows the mouse
+                self.pan(from - mouse);
+            }
+            self.drag_from = Some(mouse);
+        } else {
+            self.drag_from = None;
+        }
+
+        if is_key_pressed(KeyCode::R) {
+            *self = ArenaView::default();
+        }
+    }
+
+    /// Window position of an arena pixel, for overlays drawn straight to the screen
+    pub fn screen_position(&self, point: Vec2, arena_x: f32) -> Vec2 {
+        let viewport_center = vec2(
+            arena_x + ARENA_WIDTH as f32 / 2.0,
+            ARENA_HEIGHT as f32 / 2.0,
+        );
+        (point - self.center) * self.zoom + viewport_center
+    }
+
+    // Camera drawing arena pixels into an arena-sized render target
+    fn render_target_camera(&self, render_target: &RenderTarget) -> Camera2D {
+        Camera2D {
+            render_target: Some(render_target.clone()),
+            zoom: vec2(
+                1.0 / ARENA_WIDTH as f32 * 2.0 * self.zoom,
+                1.0 / ARENA_HEIGHT as f32 * 2.0 * self.zoom,
+            ),
+            target: self.center,
+            ..Default::default()
+        }
+    }
+
+    // Camera drawing arena pixels straight to the window, matching `screen_position`
+    fn screen_camera(&self, arena_x: f32) -> Camera2D {
+        let screen = vec2(screen_width(), screen_height());
+        let viewport_center = vec2(
+            arena_x + ARENA_WIDTH as f32 / 2.0,
+            ARENA_HEIGHT as f32 / 2.0,
+        );
+        Camera2D {
+            zoom: vec2(2.0 * self.zoom / screen.x, -2.0 * self.zoom / screen.y),
+            target: self.center + (screen / 2.0 - viewport_center) / self.zoom,
+            ..Default::default()
+        }
+    }
+}
+
 // Handles rendering the simulation state using macroquad
 pub struct Renderer {
     scene_rt: Option<RenderTarget>,
@@ -68,6 +339,15 @@ pub struct Renderer {
     scanner_material: Option<Material>,
     title_font: Option<Font>,
     ui_font: Option<Font>,
+    profiler: Option<RenderProfiler>, // Per-phase frame timings, shown with --profile-render
+    layout: Layout,                   // Where the arena and UI panel are drawn
+    glow_enabled: bool,               // Run the bright/blur/composite passes (toggled with G)
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Renderer {
@@ -84,9 +364,43 @@ impl Renderer {
             scanner_material: None,
             title_font: None,
             ui_font: None,
+            profiler: None,
+            layout: UiPlacement::default().layout(),
+            glow_enabled: true,
         }
     }
 
+    /// Moves the UI panel to the given side of the window, or hides it. The window
+    /// itself is sized from the same layout in `window_conf`.
+    pub fn set_ui_placement(&mut self, placement: UiPlacement) {
+        self.layout = placement.layout();
+    }
+
+    /// Turns the glow post-processing on or off. Without it the scene is drawn
+    /// straight to the screen, which is much cheaper on weak GPUs.
+    pub fn set_glow_enabled(&mut self, enabled: bool) {
+        self.glow_enabled = enabled;
+    }
+
+    pub fn glow_enabled(&self) -> bool {
+        self.glow_enabled
+    }
+
+    /// Turns on the frame-time breakdown overlay
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(RenderProfiler::new());
+    }
+
+    // Records the time since `phase_start` for a phase and restarts the clock.
+    // Takes only the profiler so it can run while render targets are borrowed.
+    fn end_phase(profiler: &mut Option<RenderProfiler>, phase: RenderPhase, phase_start: &mut f64) {
+        let now = get_time();
+        if let Some(profiler) = profiler.as_mut() {
+            profiler.record(phase, now - *phase_start);
+        }
+        *phase_start = now;
+    }
+
     pub fn init_scanner_material(&mut self) {
         let vertex_shader = "#version 100
             attribute vec3 position;
@@ -135,20 +449,32 @@ impl Renderer {
         match get_asset_bytes("title.ttf") {
             Some(bytes) => match load_ttf_font_from_bytes(bytes.as_ref()) {
                 Ok(font) => self.title_font = Some(font),
-                Err(e) => log::error!("Failed to parse embedded font title.ttf: {}", e),
+                Err(e) => log::error!(
+                    "Failed to parse embedded font title.ttf: {}; titles use the UI font",
+                    e
+                ),
             },
-            None => log::error!("Embedded font title.ttf not found"),
+            None => log::error!("Embedded font title.ttf not found; titles use the UI font"),
         }
     }
 
+    // Font for text with the given role; pass the same value to measure_text and
+    // TextParams so layout matches what is drawn, with or without custom fonts
+    fn font(&self, role: FontRole) -> Option<&Font> {
+        resolve_font(role, self.title_font.as_ref(), self.ui_font.as_ref())
+    }
+
     // Load the custom UI font
     pub async fn load_ui_font(&mut self) {
         match get_asset_bytes("default.ttf") {
             Some(bytes) => match load_ttf_font_from_bytes(bytes.as_ref()) {
                 Ok(font) => self.ui_font = Some(font),
-                Err(e) => log::error!("Failed to parse embedded font default.ttf: {}", e),
+                Err(e) => log::error!(
+                    "Failed to parse embedded font default.ttf: {}; using the built-in font",
+                    e
+                ),
             },
-            None => log::error!("Embedded font default.ttf not found"),
+            None => log::error!("Embedded font default.ttf not found; using the built-in font"),
         }
     }
 
@@ -332,63 +658,20 @@ void main() {
         );
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn draw_frame(
-        &mut self,
+    // Draws the arena, robots, shots and particles in arena coordinates, through
+    // whichever camera is current
+    fn draw_scene(
+        &self,
         arena: &Arena,
         robots: &[Robot],
         particle_system: &ParticleSystem,
-        current_turn: u32,
-        max_turns: u32,
-        current_cycle: u32,
-        cycles_per_turn: u32,
-        time_accumulator: f32,
-        cycle_duration: f32,
-        announcement: Option<&str>,
+        alpha: f32,
     ) {
-        // --- Bypass Glow Effect - Draw directly to screen ---
-        /*
-        set_default_camera(); // Ensure we are drawing to the screen
-        clear_background(BLACK); // Clear the main screen
-
-        let alpha = (time_accumulator / cycle_duration).clamp(0.0, 1.0);
-        // Draw arena elements directly
-        Self::draw_arena_boundaries(arena, ARENA_WIDTH, ARENA_HEIGHT);
-        Self::draw_obstacles(arena, ARENA_WIDTH, ARENA_HEIGHT);
-        for robot in robots {
-            // Note: draw_robot now needs &mut self if we were to use materials internally
-            // Since we are calling it on Self, we pass self implicitly.
-            // If draw_robot was not part of Renderer impl, we would need &mut renderer.
-            self.draw_robot(robot, ARENA_WIDTH, ARENA_HEIGHT, alpha as f64);
-        }
-        Self::draw_projectiles(arena, ARENA_WIDTH, ARENA_HEIGHT, alpha as f64);
-        Self::draw_particles(particle_system, ARENA_WIDTH, ARENA_HEIGHT, alpha);
-        // --- End Direct Draw ---
-        */
-
-        // --- Glow Effect Code ---
-        // Ensure all RTs and materials are initialized (should be done in main, but double-check)
-        if self.scene_rt.is_none() {
-            self.init_glow_resources();
-        }
-
-        // --- Pass 1: Draw Scene to Render Target ---
-        let scene_rt = self.scene_rt.as_ref().unwrap();
-        set_camera(&Camera2D {
-            render_target: Some(scene_rt.clone()),
-            zoom: vec2(
-                1.0 / ARENA_WIDTH as f32 * 2.0,
-                1.0 / ARENA_HEIGHT as f32 * 2.0,
-            ),
-            target: vec2(ARENA_WIDTH as f32 / 2.0, ARENA_HEIGHT as f32 / 2.0),
-            ..Default::default()
-        });
-        clear_background(BLACK); // Clear the scene RT
-
-        let alpha = (time_accumulator / cycle_duration).clamp(0.0, 1.0);
         // Draw arena elements normally (no special material here)
         Self::draw_arena_boundaries(arena, ARENA_WIDTH, ARENA_HEIGHT);
         Self::draw_obstacles(arena, ARENA_WIDTH, ARENA_HEIGHT);
+        Self::draw_pickups(arena, ARENA_WIDTH, ARENA_HEIGHT);
+        Self::draw_mines(arena, ARENA_WIDTH, ARENA_HEIGHT);
 
         // --- Draw Gridlines ---
         if !robots.is_empty() {
@@ -402,13 +685,7 @@ void main() {
 
             if total_health > 0.0 {
                 for robot in robots {
-                    let base_color = match robot.id {
-                        1 => Color::from_rgba(40, 80, 140, 255),
-                        2 => Color::from_rgba(140, 40, 40, 255),
-                        3 => Color::from_rgba(40, 100, 40, 255),
-                        4 => Color::from_rgba(140, 120, 20, 255),
-                        _ => Color::from_rgba(100, 50, 100, 255),
-                    };
+                    let base_color = robot_color(robot.id);
                     let weight = (robot.health.max(0.0) / total_health) as f32;
                     final_r += base_color.r * weight;
                     final_g += base_color.g * weight;
@@ -446,10 +723,37 @@ void main() {
         for robot in robots {
             self.draw_robot(robot, ARENA_WIDTH, ARENA_HEIGHT, alpha as f64);
         }
-        Self::draw_projectiles(arena, ARENA_WIDTH, ARENA_HEIGHT, alpha as f64);
+        Self::draw_projectiles(arena, robots, ARENA_WIDTH, ARENA_HEIGHT, alpha as f64);
+        Self::draw_beams(arena, robots, ARENA_WIDTH, ARENA_HEIGHT);
         Self::draw_particles(particle_system, ARENA_WIDTH, ARENA_HEIGHT, alpha);
+    }
+
+    // Draws the scene into the scene render target, then composites it to the
+    // screen with a blurred copy of its bright pixels added on top
+    fn draw_scene_with_glow(
+        &mut self,
+        arena: &Arena,
+        robots: &[Robot],
+        particle_system: &ParticleSystem,
+        alpha: f32,
+        view: &ArenaView,
+        phase_start: &mut f64,
+    ) {
+        let arena_x = self.layout.arena_x;
+        // Ensure all RTs and materials are initialized (should be done in main, but double-check)
+        if self.scene_rt.is_none() {
+            self.init_glow_resources();
+        }
+
+        // --- Pass 1: Draw Scene to Render Target ---
+        let scene_rt = self.scene_rt.as_ref().unwrap();
+        set_camera(&view.render_target_camera(scene_rt));
+        clear_background(BLACK); // Clear the scene RT
+
+        self.draw_scene(arena, robots, particle_system, alpha);
 
         set_default_camera(); // Reset camera after drawing to RT
+        Self::end_phase(&mut self.profiler, RenderPhase::Scene, phase_start);
 
         // --- Pass 2: Extract Bright Pixels ---
         let bright_rt = self.bright_rt.as_ref().unwrap();
@@ -480,6 +784,7 @@ void main() {
         );
         gl_use_default_material();
         set_default_camera();
+        Self::end_phase(&mut self.profiler, RenderPhase::Bright, phase_start);
 
         // --- Pass 3: Blur Bright Pixels (Ping-Pong) ---
         let h_blur_material = self.h_blur_material.as_ref().unwrap();
@@ -541,6 +846,7 @@ void main() {
         }
         // After the loop, current_source_rt holds the final blurred texture
         let final_glow_rt = current_source_rt;
+        Self::end_phase(&mut self.profiler, RenderPhase::Blur, phase_start);
 
         // --- Final Composite: Draw Scene + Additive Glow to Screen ---
         clear_background(BLACK); // Clear the main screen
@@ -548,7 +854,7 @@ void main() {
         // 1. Draw the original scene - NO flip needed now
         draw_texture_ex(
             &scene_rt.texture,
-            0.0,
+            arena_x,
             0.0,
             WHITE,
             DrawTextureParams {
@@ -563,8 +869,51 @@ void main() {
         additive_material.set_uniform("GlowIntensity", GLOW_INTENSITY); // Set intensity
         gl_use_material(additive_material); // This applies the additive blend pipeline
         // Draw rectangle, the material's passthrough shader will sample the glow texture
-        draw_rectangle(0.0, 0.0, ARENA_WIDTH as f32, ARENA_HEIGHT as f32, WHITE);
+        draw_rectangle(arena_x, 0.0, ARENA_WIDTH as f32, ARENA_HEIGHT as f32, WHITE);
         gl_use_default_material(); // Reset to default material/pipeline
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_frame(
+        &mut self,
+        arena: &Arena,
+        robots: &[Robot],
+        particle_system: &ParticleSystem,
+        current_turn: u32,
+        max_turns: u32,
+        current_cycle: u32,
+        cycles_per_turn: u32,
+        time_accumulator: f32,
+        cycle_duration: f32,
+        announcement: Option<&str>,
+        overtime: Option<f64>,
+        debug_console: &DebugConsole,
+        view: &ArenaView,
+    ) {
+        let alpha = (time_accumulator / cycle_duration).clamp(0.0, 1.0);
+        let arena_x = self.layout.arena_x;
+        let mut phase_start = get_time();
+        if self.glow_enabled {
+            self.draw_scene_with_glow(
+                arena,
+                robots,
+                particle_system,
+                alpha,
+                view,
+                &mut phase_start,
+            );
+        } else {
+            // Draw the scene straight to the screen, shifted to the arena's place in the window
+            set_default_camera();
+            clear_background(BLACK);
+            set_camera(&view.screen_camera(arena_x));
+            self.draw_scene(arena, robots, particle_system, alpha);
+            set_default_camera();
+            Self::end_phase(&mut self.profiler, RenderPhase::Scene, &mut phase_start);
+            // No bright or blur passes without glow
+            Self::end_phase(&mut self.profiler, RenderPhase::Bright, &mut phase_start);
+            Self::end_phase(&mut self.profiler, RenderPhase::Blur, &mut phase_start);
+        }
 
         // --- Draw Scanners (After Glow, unaffected by it) ---
         if let Some(scanner_material) = &self.scanner_material {
@@ -579,18 +928,16 @@ void main() {
                     robot.turret.direction,
                     alpha as f64,
                 );
-                let center_pos = point_to_vec2(interp_pos, ARENA_WIDTH, ARENA_HEIGHT);
-                let body_color = match robot.id {
-                    1 => Color::from_rgba(40, 80, 140, 255),
-                    2 => Color::from_rgba(140, 40, 40, 255),
-                    3 => Color::from_rgba(40, 100, 40, 255),
-                    4 => Color::from_rgba(140, 120, 20, 255),
-                    _ => Color::from_rgba(100, 50, 100, 255),
-                };
+                let center_pos = view.screen_position(
+                    point_to_vec2(interp_pos, ARENA_WIDTH, ARENA_HEIGHT),
+                    arena_x,
+                );
+                let body_color = robot_color(robot.id);
 
                 // Reuse the mesh generation logic
                 let scanner_range =
-                    (robot.turret.scanner.range * ARENA_WIDTH.min(ARENA_HEIGHT) as f64) as f32;
+                    (robot.turret.scanner.range * ARENA_WIDTH.min(ARENA_HEIGHT) as f64) as f32
+                        * view.zoom;
                 let scanner_fov_deg = robot.turret.scanner.fov as f32;
                 let start_angle_deg = interp_turret_deg as f32 - scanner_fov_deg / 2.0;
                 let base_scanner_color = faded_color(body_color, 0.15);
@@ -643,56 +990,51 @@ void main() {
         set_default_camera(); // Ensure drawing to screen
         for robot in robots {
             // Check if the robot has a target lock
-            if let Ok(target_distance) = robot.vm_state.registers.get(Register::TargetDistance) {
-                if target_distance > 0.0 {
-                    if let Ok(target_direction_deg) =
-                        robot.vm_state.registers.get(Register::TargetDirection)
-                    {
-                        // Get scanner's interpolated position and color
-                        let interp_pos =
-                            utils::lerp_point(robot.prev_position, robot.position, alpha as f64);
-                        let body_color = match robot.id {
-                            1 => Color::from_rgba(40, 80, 140, 255),
-                            2 => Color::from_rgba(140, 40, 40, 255),
-                            3 => Color::from_rgba(40, 100, 40, 255),
-                            4 => Color::from_rgba(140, 120, 20, 255),
-                            _ => Color::from_rgba(100, 50, 100, 255),
-                        };
-
-                        // Calculate target world position
-                        let target_direction_rad = target_direction_deg.to_radians();
-                        let target_world_pos = Point {
-                            x: interp_pos.x + target_distance * target_direction_rad.cos(),
-                            y: interp_pos.y + target_distance * target_direction_rad.sin(),
-                        };
-
-                        // Convert to screen coordinates
-                        let target_screen_pos =
-                            point_to_vec2(target_world_pos, ARENA_WIDTH, ARENA_HEIGHT);
-
-                        // Draw indicator circle
-                        let indicator_radius = 6.0; // Adjust size as needed
-                        let indicator_color = faded_color(body_color, 0.7); // Use scanner's color, slightly faded
-                        let outline_color = brighten_color(body_color, 0.3);
-
-                        draw_circle(
-                            target_screen_pos.x,
-                            target_screen_pos.y,
-                            indicator_radius,
-                            indicator_color,
-                        );
-                        draw_circle_lines(
-                            target_screen_pos.x,
-                            target_screen_pos.y,
-                            indicator_radius,
-                            1.5,
-                            outline_color,
-                        );
-                    }
-                }
+            if let Ok(target_distance) = robot.vm_state.registers.get(Register::TargetDistance)
+                && target_distance > 0.0
+                && let Ok(target_direction_deg) =
+                    robot.vm_state.registers.get(Register::TargetDirection)
+            {
+                // Get scanner's interpolated position and color
+                let interp_pos =
+                    utils::lerp_point(robot.prev_position, robot.position, alpha as f64);
+                let body_color = robot_color(robot.id);
+
+                // Calculate target world position
+                let target_direction_rad = target_direction_deg.to_radians();
+                let target_world_pos = Point {
+                    x: interp_pos.x + target_distance * target_direction_rad.cos(),
+                    y: interp_pos.y + target_distance * target_direction_rad.sin(),
+                };
+
+                // Convert to screen coordinates
+                let target_screen_pos = view.screen_position(
+                    point_to_vec2(target_world_pos, ARENA_WIDTH, ARENA_HEIGHT),
+                    arena_x,
+                );
+
+                // Draw indicator circle
+                let indicator_radius = 6.0; // Adjust size as needed
+                let indicator_color = faded_color(body_color, 0.7); // Use scanner's color, slightly faded
+                let outline_color = brighten_color(body_color, 0.3);
+
+                draw_circle(
+                    target_screen_pos.x,
+                    target_screen_pos.y,
+                    indicator_radius,
+                    indicator_color,
+                );
+                draw_circle_lines(
+                    target_screen_pos.x,
+                    target_screen_pos.y,
+                    indicator_radius,
+                    1.5,
+                    outline_color,
+                );
             }
         }
         // --- End Target Indicators ---
+        Self::end_phase(&mut self.profiler, RenderPhase::Composite, &mut phase_start);
 
         // --- Draw UI (unaffected by glow) ---
         self.draw_ui_panel(
@@ -705,16 +1047,29 @@ void main() {
         // Draw FPS counter using UI font
         let fps_text = format!("FPS: {}", get_fps());
         let fps_params = TextParams {
-            font: self.ui_font.as_ref(),
+            font: self.font(FontRole::Ui),
             font_size: 18,
             color: WHITE,
             ..Default::default()
         };
-        draw_text_ex(&fps_text, 10.0, 20.0, fps_params.clone()); // Use clone if needed elsewhere
+        draw_text_ex(&fps_text, arena_x + 10.0, 20.0, fps_params.clone()); // Use clone if needed elsewhere
+
+        if let Some(multiplier) = overtime {
+            self.draw_overtime_banner(multiplier);
+        }
+
+        if debug_console.visible {
+            self.draw_debug_console(debug_console, robots);
+        }
 
         if let Some(msg) = announcement {
             self.draw_announcement(msg);
         }
+        Self::end_phase(&mut self.profiler, RenderPhase::Ui, &mut phase_start);
+
+        if let Some(profiler) = &self.profiler {
+            self.draw_render_profile(profiler);
+        }
     }
 
     fn draw_arena_boundaries(_arena: &Arena, arena_screen_width: i32, arena_screen_height: i32) {
@@ -745,6 +1100,53 @@ void main() {
         }
     }
 
+    // Pickups are small bright icons (a cross for health, a diamond for
+    // energy) over a faint halo, so the glow pass makes them shine
+    fn draw_pickups(arena: &Arena, arena_screen_width: i32, arena_screen_height: i32) {
+        let size = (UNIT_SIZE * arena_screen_width.min(arena_screen_height) as f64) as f32 * 0.3;
+        for pickup in &arena.pickups {
+            let center = point_to_vec2(pickup.position, arena_screen_width, arena_screen_height);
+            let color = pickup_color(pickup.kind);
+            draw_circle(center.x, center.y, size * 1.6, faded_color(color, 0.25));
+            match pickup.kind {
+                PickupKind::Health => {
+                    let arm = size * 0.35;
+                    draw_rectangle(
+                        center.x - size,
+                        center.y - arm,
+                        size * 2.0,
+                        arm * 2.0,
+                        color,
+                    );
+                    draw_rectangle(
+                        center.x - arm,
+                        center.y - size,
+                        arm * 2.0,
+                        size * 2.0,
+                        color,
+                    );
+                }
+                PickupKind::Energy => draw_poly(center.x, center.y, 4, size, 0.0, color),
+            }
+        }
+    }
+
+    // Mines are small red dots whose halo pulses, so they read as armed
+    fn draw_mines(arena: &Arena, arena_screen_width: i32, arena_screen_height: i32) {
+        let size = (UNIT_SIZE * arena_screen_width.min(arena_screen_height) as f64) as f32 * 0.12;
+        let pulse = ((get_time() * 6.0).sin() as f32 + 1.0) / 2.0;
+        for mine in &arena.mines {
+            let center = point_to_vec2(mine.position, arena_screen_width, arena_screen_height);
+            draw_circle(
+                center.x,
+                center.y,
+                size * (1.5 + pulse),
+                faded_color(RED, 0.15 + 0.25 * pulse),
+            );
+            draw_circle(center.x, center.y, size, RED);
+        }
+    }
+
     fn draw_robot(
         &self,
         robot: &Robot,
@@ -752,9 +1154,9 @@ void main() {
         arena_screen_height: i32,
         alpha: f64,
     ) {
-        let robot_screen_size =
-            (UNIT_SIZE * arena_screen_width.min(arena_screen_height) as f64) as f32;
-        let radius = robot_screen_size / 2.0;
+        // Draw at the body's size, not its (shape-dependent) collision footprint
+        let size = robot.radius / robot.shape.footprint_radius(1.0);
+        let radius = (size * arena_screen_width.min(arena_screen_height) as f64) as f32;
         // Interpolate state
         let interp_pos = utils::lerp_point(robot.prev_position, robot.position, alpha);
         let interp_drive_deg =
@@ -763,13 +1165,7 @@ void main() {
             utils::angle_lerp(robot.prev_turret_direction, robot.turret.direction, alpha);
         let center_pos = point_to_vec2(interp_pos, arena_screen_width, arena_screen_height);
         // Use the same color logic as the UI card
-        let body_color = match robot.id {
-            1 => Color::from_rgba(40, 80, 140, 255),
-            2 => Color::from_rgba(140, 40, 40, 255),
-            3 => Color::from_rgba(40, 100, 40, 255),
-            4 => Color::from_rgba(140, 120, 20, 255),
-            _ => Color::from_rgba(100, 50, 100, 255),
-        };
+        let body_color = robot_color(robot.id);
         let body_outline_color = brighten_color(body_color, 0.5);
         // Compute target directions
         let target_drive_deg =
@@ -804,18 +1200,56 @@ void main() {
             2.0,
             ghost_fill_color,
         ); // Use brighter background color
-        // Draw robot body as triangle (interpolated)
-        Self::draw_triangle_at_angle(
-            center_pos,
-            radius,
-            interp_drive_deg as f32,
-            faded_color(body_color, 1.0),
-            body_outline_color,
-            true,
-            1.0,
-            true,
-            WHITE,
-        );
+        // Teammates share an accent ring around the body
+        if robot.team != 0 {
+            draw_circle_lines(
+                center_pos.x,
+                center_pos.y,
+                radius * 1.4,
+                2.0,
+                team_color(robot.team),
+            );
+        }
+        // Draw robot body in its shape (interpolated)
+        match robot.shape.body_sides() {
+            Some(3) => Self::draw_triangle_at_angle(
+                center_pos,
+                radius,
+                interp_drive_deg as f32,
+                faded_color(body_color, 1.0),
+                body_outline_color,
+                true,
+                1.0,
+                true,
+                WHITE,
+            ),
+            Some(sides) => {
+                // Polygon radius is to the corners; keep the flat sides at `radius`
+                let corner_radius = radius / (std::f32::consts::PI / sides as f32).cos();
+                let rotation = interp_drive_deg as f32 + 180.0 / sides as f32;
+                draw_poly(
+                    center_pos.x,
+                    center_pos.y,
+                    sides,
+                    corner_radius,
+                    rotation,
+                    body_color,
+                );
+                draw_poly_lines(
+                    center_pos.x,
+                    center_pos.y,
+                    sides,
+                    corner_radius,
+                    rotation,
+                    1.0,
+                    body_outline_color,
+                );
+            }
+            None => {
+                draw_circle(center_pos.x, center_pos.y, radius, body_color);
+                draw_circle_lines(center_pos.x, center_pos.y, radius, 1.0, body_outline_color);
+            }
+        }
         // Draw turret as a line (interpolated)
         let turret_rad = interp_turret_deg.to_radians() as f32;
         let turret_end = center_pos + Vec2::new(turret_rad.cos(), turret_rad.sin()) * radius * 0.8;
@@ -874,6 +1308,7 @@ void main() {
 
     fn draw_projectiles(
         arena: &Arena,
+        robots: &[Robot],
         arena_screen_width: i32,
         arena_screen_height: i32,
         alpha: f64,
@@ -892,19 +1327,63 @@ void main() {
                 arena_screen_height,
             );
 
-            // Draw the vapor trail line (fading gray)
-            let trail_color = faded_color(LIGHTGRAY, 0.5); // Use helper for faded color
-            draw_line(
-                prev_tick_screen_pos.x,
-                prev_tick_screen_pos.y,
+            // Color by the robot that fired it
+            let color = projectile_color(robots, projectile.source_robot);
+
+            // Draw the vapor trail (faded source color): the move made this cycle, then
+            // the same step repeated back for older cycles, each segment fainter
+            let step = point_to_vec2(projectile.position, arena_screen_width, arena_screen_height)
+                - prev_tick_screen_pos;
+            let mut segment_end = current_screen_pos;
+            let mut segment_start = prev_tick_screen_pos;
+            for segment in 0..PROJECTILE_TRAIL_SEGMENTS {
+                let trail_color =
+                    faded_color(color, trail_segment_alpha(projectile.speed, segment));
+                draw_line(
+                    segment_start.x,
+                    segment_start.y,
+                    segment_end.x,
+                    segment_end.y,
+                    1.5, // Line thickness
+                    trail_color,
+                );
+                segment_end = segment_start;
+                segment_start -= step;
+            }
+
+            // Draw the projectile head (full source color); mortar shells grow with height
+            let head_radius = match projectile.ballistic {
+                Some(arc) => 2.0 * (1.0 + (arc.height / UNIT_SIZE) as f32),
+                None => 2.0,
+            };
+            draw_circle(
                 current_screen_pos.x,
                 current_screen_pos.y,
-                1.5, // Line thickness
-                trail_color,
+                head_radius,
+                color,
             );
+        }
+    }
 
-            // Draw the projectile head (slightly brighter)
-            draw_circle(current_screen_pos.x, current_screen_pos.y, 2.0, WHITE);
+    fn draw_beams(
+        arena: &Arena,
+        robots: &[Robot],
+        arena_screen_width: i32,
+        arena_screen_height: i32,
+    ) {
+        for beam in &arena.beams {
+            let start = point_to_vec2(beam.start, arena_screen_width, arena_screen_height);
+            let end = point_to_vec2(beam.end, arena_screen_width, arena_screen_height);
+            // Extra bright so the glow pass picks it up; thicker at higher power
+            let color = brighten_color(projectile_color(robots, beam.source_robot), 0.5);
+            draw_line(
+                start.x,
+                start.y,
+                end.x,
+                end.y,
+                1.0 + 2.0 * beam.power as f32,
+                color,
+            );
         }
     }
 
@@ -943,7 +1422,9 @@ void main() {
         current_cycle: u32,
         cycles_per_turn: u32,
     ) {
-        let panel_x = ARENA_WIDTH as f32;
+        let Some(panel_x) = self.layout.panel_x else {
+            return;
+        };
         let panel_width = UI_PANEL_WIDTH as f32;
         let padding = 10.0; // General padding for horizontal spacing and between elements
         let top_margin = 16.0; // Specific margin for the top
@@ -959,17 +1440,17 @@ void main() {
         };
         let small_params = TextParams {
             font_size: small_font_size as u16,
-            font: self.ui_font.as_ref(), // Use UI font
+            font: self.font(FontRole::Ui), // Use UI font
             ..default_params
         };
         let small_white_params = TextParams {
-            font: self.ui_font.as_ref(), // Use UI font
+            font: self.font(FontRole::Ui), // Use UI font
             color: WHITE,
             ..small_params
         };
         let small_value_params = TextParams {
             font_size: (small_font_size - 2.0) as u16,
-            font: self.ui_font.as_ref(), // Use UI font
+            font: self.font(FontRole::Ui), // Use UI font
             color: WHITE,
             ..small_params
         };
@@ -1021,7 +1502,7 @@ void main() {
 
         // Title - Use custom font here only
         let title_params = TextParams {
-            font: self.title_font.as_ref(), // Use custom font
+            font: self.font(FontRole::Title), // Use custom font
             font_size: font_size as u16,
             color: GOLD,
             ..Default::default()
@@ -1041,7 +1522,7 @@ void main() {
         let turn_text = format!("{}/{}", current_turn, max_turns);
         let turn_text_dims = measure_text(
             &turn_text,
-            self.ui_font.as_ref(),
+            self.font(FontRole::Ui),
             small_value_params.font_size,
             1.0,
         );
@@ -1055,7 +1536,7 @@ void main() {
 
         // Position bar relative to text baseline + spacing
         let turn_bar_y = meter_label_y + label_bar_spacing;
-        let turn_ratio = current_turn as f32 / max_turns as f32;
+        let turn_ratio = current_turn as f32 / max_turns.max(1) as f32;
         draw_rectangle(
             bar_x,
             turn_bar_y,
@@ -1079,7 +1560,7 @@ void main() {
         let cycle_text = format!("{}/{}", current_cycle, cycles_per_turn);
         let cycle_text_dims = measure_text(
             &cycle_text,
-            self.ui_font.as_ref(),
+            self.font(FontRole::Ui),
             small_value_params.font_size,
             1.0,
         );
@@ -1093,7 +1574,7 @@ void main() {
 
         // Position bar relative to text baseline + spacing
         let cycle_bar_y = cycle_meter_y + label_bar_spacing;
-        let cycle_ratio = current_cycle as f32 / cycles_per_turn as f32;
+        let cycle_ratio = current_cycle as f32 / cycles_per_turn.max(1) as f32;
         draw_rectangle(
             bar_x,
             cycle_bar_y,
@@ -1117,13 +1598,7 @@ void main() {
         let card_spacing = padding; // Use general padding for card spacing
         for robot in robots {
             let card_y = y;
-            let robot_color = match robot.id {
-                1 => faded_color(Color::from_rgba(40, 80, 140, 255), 1.0),
-                2 => faded_color(Color::from_rgba(140, 40, 40, 255), 1.0),
-                3 => faded_color(Color::from_rgba(40, 100, 40, 255), 1.0),
-                4 => faded_color(Color::from_rgba(140, 120, 20, 255), 1.0),
-                _ => faded_color(Color::from_rgba(100, 50, 100, 255), 1.0),
-            };
+            let robot_color = robot_color(robot.id);
             // Card drop shadow (keep solid for contrast)
             draw_rectangle(
                 panel_x + padding + 3.0,
@@ -1166,7 +1641,7 @@ void main() {
             // Define parameters for the robot name
             let robot_name_font_size = 20.0;
             let robot_name_params = TextParams {
-                font: self.title_font.as_ref(), // Use title font
+                font: self.font(FontRole::Title), // Use title font
                 font_size: robot_name_font_size as u16,
                 color: WHITE, // Keep white for now
                 ..Default::default()
@@ -1293,10 +1768,128 @@ void main() {
         }
     }
 
+    fn draw_debug_console(&self, console: &DebugConsole, robots: &[Robot]) {
+        let font_size = 16.0;
+        let line_height = font_size + 2.0;
+        let x = self.layout.arena_x + 10.0;
+        let y = 30.0;
+        let width = ARENA_WIDTH as f32 - 20.0;
+        let height = line_height * (DEBUG_CONSOLE_LINES + 1) as f32 + 10.0;
+        draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 180));
+
+        let mut text_y = y + line_height;
+        for line in console.lines() {
+            let text = format!(
+                "[R{:02}][T{:03}][C{:02}] {}",
+                line.robot_id, line.turn, line.cycle, line.value
+            );
+            let params = TextParams {
+                font: self.font(FontRole::Ui),
+                font_size: font_size as u16,
+                color: robot_color(line.robot_id),
+                ..Default::default()
+            };
+            draw_text_ex(&text, x + 6.0, text_y, params);
+            text_y += line_height;
+        }
+
+        // Footer: which VMs are paused, and how to toggle them
+        let paused: Vec<String> = robots
+            .iter()
+            .filter(|r| r.vm_paused)
+            .map(|r| format!("R{:02}", r.id))
+            .collect();
+        let footer = if paused.is_empty() {
+            "1-4: pause/resume robot VM".to_string()
+        } else {
+            format!("1-4: pause/resume robot VM | paused: {}", paused.join(" "))
+        };
+        let params = TextParams {
+            font: self.font(FontRole::Ui),
+            font_size: font_size as u16,
+            color: LIGHTGRAY,
+            ..Default::default()
+        };
+        draw_text_ex(
+            &footer,
+            x + 6.0,
+            y + line_height * (DEBUG_CONSOLE_LINES + 1) as f32,
+            params,
+        );
+    }
+
+    /// Pause overlay for the focused robot: IP, current instruction, top of stack and registers
+    pub fn draw_debug_overlay(&self, robot: &Robot) {
+        let font_size = 14.0;
+        let line_height = font_size + 2.0;
+        let mut lines = debug_overlay_lines(robot);
+        lines.push("PAUSED  space: resume | .: step cycle | tab: next robot".to_string());
+        let x = self.layout.arena_x + 10.0;
+        let width = ARENA_WIDTH as f32 - 20.0;
+        let height = line_height * lines.len() as f32 + 8.0;
+        let y = WINDOW_HEIGHT as f32 - height - 10.0;
+        draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 200));
+
+        let mut text_y = y + line_height;
+        let last = lines.len() - 1;
+        for (index, line) in lines.iter().enumerate() {
+            let color = match index {
+                0 => robot_color(robot.id),
+                _ if index == last => LIGHTGRAY,
+                _ => WHITE,
+            };
+            let params = TextParams {
+                font: self.font(FontRole::Ui),
+                font_size: font_size as u16,
+                color,
+                ..Default::default()
+            };
+            draw_text_ex(line, x + 6.0, text_y, params);
+            text_y += line_height;
+        }
+    }
+
+    fn draw_render_profile(&self, profiler: &RenderProfiler) {
+        let font_size = 14.0;
+        let line_height = font_size + 2.0;
+        let lines = profiler.summary_lines();
+        let width = 150.0;
+        let height = line_height * lines.len() as f32 + 8.0;
+        let x = self.layout.arena_x + ARENA_WIDTH as f32 - width - 10.0;
+        let y = 10.0;
+        draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 180));
+
+        let mut text_y = y + line_height;
+        for line in &lines {
+            let params = TextParams {
+                font: self.font(FontRole::Ui),
+                font_size: font_size as u16,
+                color: LIGHTGRAY,
+                ..Default::default()
+            };
+            draw_text_ex(line, x + 6.0, text_y, params);
+            text_y += line_height;
+        }
+    }
+
+    // Banner across the top of the arena while overtime damage is in effect
+    fn draw_overtime_banner(&self, multiplier: f64) {
+        let text = format!("OVERTIME  x{:.2} DAMAGE", multiplier);
+        let params = TextParams {
+            font: self.font(FontRole::Ui),
+            font_size: 28,
+            color: RED,
+            ..Default::default()
+        };
+        let dims = measure_text(&text, self.font(FontRole::Ui), params.font_size, 1.0);
+        let x = self.layout.arena_x + (ARENA_WIDTH as f32 - dims.width) / 2.0;
+        draw_text_ex(&text, x, 40.0, params);
+    }
+
     fn draw_announcement(&self, msg: &str) {
         let rect_width = 500.0;
         let rect_height = 120.0;
-        let x = (WINDOW_WIDTH as f32 / 2.0) - (rect_width / 2.0);
+        let x = (self.layout.window_width as f32 / 2.0) - (rect_width / 2.0);
         let y = (WINDOW_HEIGHT as f32 / 2.0) - (rect_height / 2.0);
         draw_rectangle(
             x,
@@ -1309,14 +1902,14 @@ void main() {
         // Use ui_font for announcement text
         let font_size_announcement = 32.0;
         let announcement_params = TextParams {
-            font: self.ui_font.as_ref(),
+            font: self.font(FontRole::Ui),
             font_size: font_size_announcement as u16,
             color: WHITE,
             ..Default::default()
         };
         let text_dims = measure_text(
             msg,
-            self.ui_font.as_ref(),
+            self.font(FontRole::Ui),
             announcement_params.font_size,
             1.0,
         );
@@ -1329,12 +1922,12 @@ void main() {
         let hint = "Press ESC to exit";
         let hint_size = 18.0;
         let hint_params = TextParams {
-            font: self.ui_font.as_ref(),
+            font: self.font(FontRole::Ui),
             font_size: hint_size as u16,
             color: LIGHTGRAY,
             ..Default::default()
         };
-        let hint_dims = measure_text(hint, self.ui_font.as_ref(), hint_params.font_size, 1.0);
+        let hint_dims = measure_text(hint, self.font(FontRole::Ui), hint_params.font_size, 1.0);
         let hint_x = x + (rect_width - hint_dims.width) / 2.0;
         draw_text_ex(
             hint,
@@ -1344,6 +1937,53 @@ void main() {
         );
     }
 
+    /// Screen rectangle of the replay timeline bar, along the bottom of the arena
+    pub fn replay_timeline_bar(&self) -> Rect {
+        Rect::new(
+            self.layout.arena_x + TIMELINE_MARGIN,
+            ARENA_HEIGHT as f32 - TIMELINE_MARGIN - TIMELINE_HEIGHT,
+            ARENA_WIDTH as f32 - 2.0 * TIMELINE_MARGIN,
+            TIMELINE_HEIGHT,
+        )
+    }
+
+    /// Draws the replay timeline over the arena: progress through the replay,
+    /// a marker for each robot destruction and a handle at the current frame
+    pub fn draw_replay_timeline(
+        &self,
+        bar: Rect,
+        position: usize,
+        frame_count: usize,
+        destruction_frames: &[usize],
+    ) {
+        draw_rectangle(bar.x, bar.y, bar.w, bar.h, Color::from_rgba(0, 0, 0, 180));
+        let handle_x = replay::timeline_x(position, bar.x, bar.w, frame_count);
+        draw_rectangle(
+            bar.x,
+            bar.y,
+            handle_x - bar.x,
+            bar.h,
+            Color::from_rgba(80, 80, 80, 220),
+        );
+        draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 1.0, GRAY);
+
+        for &frame in destruction_frames {
+            let x = replay::timeline_x(frame, bar.x, bar.w, frame_count);
+            draw_line(x, bar.y - 4.0, x, bar.y + bar.h + 4.0, 2.0, RED);
+        }
+
+        draw_rectangle(handle_x - 2.0, bar.y - 3.0, 4.0, bar.h + 6.0, WHITE);
+
+        let label = format!("{}/{}", position + 1, frame_count);
+        let params = TextParams {
+            font: self.font(FontRole::Ui),
+            font_size: 14,
+            color: LIGHTGRAY,
+            ..Default::default()
+        };
+        draw_text_ex(&label, bar.x, bar.y - 8.0, params);
+    }
+
     pub fn window_should_close() -> bool {
         is_key_down(KeyCode::Escape) || is_quit_requested()
     }
@@ -1352,3 +1992,126 @@ void main() {
         is_key_down(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_layout_for_each_placement() {
+        let arena_width = ARENA_WIDTH as f32;
+        let panel_width = UI_PANEL_WIDTH as f32;
+
+        let right = UiPlacement::Right.layout();
+        assert_eq!(right.arena_x, 0.0);
+        assert_eq!(right.panel_x, Some(arena_width));
+        assert_eq!(right.window_width, ARENA_WIDTH + UI_PANEL_WIDTH);
+
+        let left = UiPlacement::Left.layout();
+        assert_eq!(left.arena_x, panel_width);
+        assert_eq!(left.panel_x, Some(0.0));
+        assert_eq!(left.window_width, ARENA_WIDTH + UI_PANEL_WIDTH);
+
+        // Hidden gives the arena the whole (narrower) window
+        let hidden = UiPlacement::Hidden.layout();
+        assert_eq!(hidden.arena_x, 0.0);
+        assert_eq!(hidden.panel_x, None);
+        assert_eq!(hidden.window_width, ARENA_WIDTH);
+
+        assert_eq!("LEFT".parse(), Ok(UiPlacement::Left));
+        assert!("top".parse::<UiPlacement>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_font_falls_back_consistently() {
+        // Stand-ins for loaded fonts
+        let (title, ui) = (Some("title"), Some("ui"));
+
+        assert_eq!(resolve_font(FontRole::Title, title, ui), title);
+        assert_eq!(resolve_font(FontRole::Ui, title, ui), ui);
+        // A missing title font falls back to the UI font
+        assert_eq!(resolve_font(FontRole::Title, None, ui), ui);
+        // Without custom fonts everything uses the built-in font
+        assert_eq!(resolve_font(FontRole::Title, None::<&str>, None), None);
+        assert_eq!(resolve_font(FontRole::Ui, title, None), None);
+    }
+
+    #[test]
+    fn test_projectile_color_by_source_robot() {
+        let center = Point { x: 0.5, y: 0.5 };
+        let robots = vec![
+            Robot::new(1, "one".to_string(), Point { x: 0.1, y: 0.1 }, center),
+            Robot::new(2, "two".to_string(), Point { x: 0.9, y: 0.9 }, center),
+        ];
+
+        let blue = projectile_color(&robots, 1);
+        let red = projectile_color(&robots, 2);
+        assert_eq!(blue, brighten_color(robot_color(1), 0.35));
+        assert_eq!(red, brighten_color(robot_color(2), 0.35));
+        assert_ne!(blue, red);
+
+        // Unknown source falls back to white
+        assert_eq!(projectile_color(&robots, 7), WHITE);
+    }
+
+    #[test]
+    fn test_debug_overlay_lines_show_vm_state() {
+        let center = Point { x: 0.5, y: 0.5 };
+        let mut robot = Robot::new(3, "Stepper".to_string(), center, center);
+        robot.vm_state.stack.push(7.5).unwrap();
+        robot.vm_state.registers.set(Register::D5, 2.0).unwrap();
+
+        let lines = debug_overlay_lines(&robot);
+        assert_eq!(lines[0], "R03 Stepper");
+        assert!(lines[1].starts_with("ip 0000"));
+        assert_eq!(lines[2], "stack top: 7.500");
+        assert!(lines.iter().any(|line| line.contains("@d5      2.000")));
+        assert_eq!(lines.len(), 4 + 5); // 19 data registers, four per line
+    }
+
+    #[test]
+    fn test_trail_fades_with_age_and_brightens_with_speed() {
+        let fast = DEFAULT_PROJECTILE_SPEED;
+        assert!(trail_segment_alpha(fast, 0) > trail_segment_alpha(fast, 1));
+        assert!(trail_segment_alpha(fast, PROJECTILE_TRAIL_SEGMENTS - 1) > 0.0);
+        assert!(trail_segment_alpha(fast / 2.0, 0) < trail_segment_alpha(fast, 0));
+        // Faster than the default speed doesn't get any brighter
+        assert_eq!(
+            trail_segment_alpha(fast * 3.0, 0),
+            trail_segment_alpha(fast, 0)
+        );
+    }
+
+    #[test]
+    fn test_arena_view_zoom_and_pan_stay_in_bounds() {
+        let arena_size = vec2(ARENA_WIDTH as f32, ARENA_HEIGHT as f32);
+        let mut view = ArenaView::default();
+        assert_eq!(
+            view.screen_position(vec2(10.0, 20.0), 200.0),
+            vec2(210.0, 20.0)
+        );
+
+        // Fully zoomed out the whole arena is on screen, so there is nothing to pan
+        view.pan(vec2(100.0, 0.0));
+        assert_eq!(view.center, arena_size / 2.0);
+
+        view.zoom_by(2.0);
+        view.pan(vec2(100.0, 0.0));
+        assert_eq!(view.center.x, arena_size.x / 2.0 + 50.0);
+        // Doubled zoom spreads arena pixels twice as far from the viewport center
+        let center = view.screen_position(view.center, 0.0);
+        assert_eq!(
+            view.screen_position(view.center + vec2(10.0, 0.0), 0.0),
+            center + vec2(20.0, 0.0)
+        );
+
+        // The viewport can't leave the arena, and zoom stays within its limits
+        view.pan(vec2(1.0e6, 1.0e6));
+        assert_eq!(view.center, arena_size - arena_size / 4.0);
+        view.zoom_by(1000.0);
+        assert_eq!(view.zoom, ARENA_MAX_ZOOM);
+        view.zoom_by(0.0001);
+        assert_eq!(view.zoom, ARENA_MIN_ZOOM);
+        assert_eq!(view.center, arena_size / 2.0);
+    }
+}
@@ -0,0 +1,142 @@
+// Render profiling: per-phase frame timings for the glow pipeline, smoothed
+// with a moving average and shown as a small overlay with --profile-render
+
+use std::collections::VecDeque;
+
+// Number of frames averaged for each phase readout
+const PROFILE_WINDOW: usize = 60;
+
+// The major phases of Renderer::draw_frame, in draw order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPhase {
+    Scene,
+    Bright,
+    Blur,
+    Composite,
+    Ui,
+}
+
+impl RenderPhase {
+    pub const ALL: [RenderPhase; 5] = [
+        RenderPhase::Scene,
+        RenderPhase::Bright,
+        RenderPhase::Blur,
+        RenderPhase::Composite,
+        RenderPhase::Ui,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RenderPhase::Scene => "scene",
+            RenderPhase::Bright => "bright",
+            RenderPhase::Blur => "blur",
+            RenderPhase::Composite => "composite",
+            RenderPhase::Ui => "ui",
+        }
+    }
+}
+
+/// Average of the most recent `window` samples
+#[derive(Debug)]
+pub struct MovingAverage {
+    samples: VecDeque<f64>,
+    window: usize,
+    sum: f64,
+}
+
+impl MovingAverage {
+    pub fn new(window: usize) -> Self {
+        MovingAverage {
+            samples: VecDeque::with_capacity(window),
+            window: window.max(1),
+            sum: 0.0,
+        }
+    }
+
+    /// Adds a sample, dropping the oldest once the window is full
+    pub fn push(&mut self, sample: f64) {
+        if self.samples.len() == self.window
+            && let Some(oldest) = self.samples.pop_front()
+        {
+            self.sum -= oldest;
+        }
+        self.samples.push_back(sample);
+        self.sum += sample;
+    }
+
+    /// Mean of the samples in the window (0.0 before any sample)
+    pub fn average(&self) -> f64 {
+        if self.samples.is_empty() {
+            0.0
+        } else {
+            self.sum / self.samples.len() as f64
+        }
+    }
+}
+
+/// Smoothed timings (in seconds) for each render phase
+#[derive(Debug)]
+pub struct RenderProfiler {
+    phases: Vec<MovingAverage>, // Indexed like RenderPhase::ALL
+}
+
+impl Default for RenderProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderProfiler {
+    pub fn new() -> Self {
+        RenderProfiler {
+            phases: RenderPhase::ALL
+                .iter()
+                .map(|_| MovingAverage::new(PROFILE_WINDOW))
+                .collect(),
+        }
+    }
+
+    pub fn record(&mut self, phase: RenderPhase, seconds: f64) {
+        self.phases[phase as usize].push(seconds);
+    }
+
+    /// Overlay lines, one per phase plus a total, in milliseconds
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut total = 0.0;
+        let mut lines: Vec<String> = RenderPhase::ALL
+            .iter()
+            .map(|&phase| {
+                let ms = self.phases[phase as usize].average() * 1000.0;
+                total += ms;
+                format!("{:<9} {:6.2} ms", phase.label(), ms)
+            })
+            .collect();
+        lines.push(format!("{:<9} {:6.2} ms", "total", total));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moving_average_window() {
+        let mut avg = MovingAverage::new(3);
+        assert_eq!(avg.average(), 0.0);
+
+        avg.push(1.0);
+        avg.push(2.0);
+        assert_eq!(avg.average(), 1.5);
+
+        avg.push(3.0);
+        avg.push(7.0); // Evicts 1.0
+        assert_eq!(avg.average(), 4.0);
+
+        // A zero window still tracks the latest sample
+        let mut single = MovingAverage::new(0);
+        single.push(5.0);
+        single.push(9.0);
+        assert_eq!(single.average(), 9.0);
+    }
+}
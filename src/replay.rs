@@ -0,0 +1,442 @@
+// Replay recording, playback and comparison: per-cycle snapshots of robot
+// state, a plain text file format, a random-access player for scrubbing, and a
+// diff that finds the first cycle two replays diverge
+
+use crate::robot::{Robot, RobotStatus};
+use crate::types::Point;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Snapshot of a single robot within a replay frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotFrame {
+    pub id: u32,
+    pub position: Point,
+    pub health: f64,
+    pub status: RobotStatus,
+}
+
+// Snapshot of all robots at the end of one simulation cycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    pub turn: u32,
+    pub cycle: u32,
+    pub robots: Vec<RobotFrame>,
+}
+
+// The first point at which two replays differ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub frame: usize, // Index of the first differing frame
+    pub turn: u32,
+    pub cycle: u32,
+    pub differences: Vec<String>, // Human readable description of each differing field
+}
+
+impl ReplayFrame {
+    /// Captures the current state of all robots
+    pub fn capture(turn: u32, cycle: u32, robots: &[Robot]) -> Self {
+        ReplayFrame {
+            turn,
+            cycle,
+            robots: robots
+                .iter()
+                .map(|r| RobotFrame {
+                    id: r.id,
+                    position: r.position,
+                    health: r.health,
+                    status: r.status,
+                })
+                .collect(),
+        }
+    }
+
+    /// Rebuilds displayable robots for this frame. Position changes since
+    /// `previous` become the robots' previous positions, so rendering can
+    /// interpolate between the two frames. Headings are not recorded; robots
+    /// face the arena center.
+    pub fn to_robots(&self, previous: Option<&ReplayFrame>) -> Vec<Robot> {
+        let center = Point { x: 0.5, y: 0.5 };
+        self.robots
+            .iter()
+            .map(|r| {
+                let mut robot = Robot::new(r.id, format!("Robot {}", r.id), r.position, center);
+                robot.health = r.health;
+                robot.status = r.status;
+                if let Some(before) =
+                    previous.and_then(|frame| frame.robots.iter().find(|b| b.id == r.id))
+                {
+                    robot.prev_position = before.position;
+                }
+                robot
+            })
+            .collect()
+    }
+
+    // Serializes the frame as a single line: `turn cycle|id x y health status|...`
+    fn to_line(&self) -> String {
+        let mut line = format!("{} {}", self.turn, self.cycle);
+        for r in &self.robots {
+            line.push_str(&format!(
+                "|{} {} {} {} {}",
+                r.id,
+                r.position.x,
+                r.position.y,
+                r.health,
+                status_name(r.status)
+            ));
+        }
+        line
+    }
+
+    // Parses a line written by `to_line`
+    fn from_line(line: &str) -> Option<Self> {
+        let mut sections = line.split('|');
+        let mut header = sections.next()?.split_whitespace();
+        let turn = header.next()?.parse().ok()?;
+        let cycle = header.next()?.parse().ok()?;
+
+        let mut robots = Vec::new();
+        for section in sections {
+            let fields: Vec<&str> = section.split_whitespace().collect();
+            if fields.len() != 5 {
+                return None;
+            }
+            robots.push(RobotFrame {
+                id: fields[0].parse().ok()?,
+                position: Point {
+                    x: fields[1].parse().ok()?,
+                    y: fields[2].parse().ok()?,
+                },
+                health: fields[3].parse().ok()?,
+                status: parse_status(fields[4])?,
+            });
+        }
+
+        Some(ReplayFrame {
+            turn,
+            cycle,
+            robots,
+        })
+    }
+}
+
+pub(crate) fn status_name(status: RobotStatus) -> &'static str {
+    match status {
+        RobotStatus::Idle => "idle",
+        RobotStatus::Active => "active",
+        RobotStatus::Stunned(_) => "stunned",
+        RobotStatus::Destroyed => "destroyed",
+    }
+}
+
+fn parse_status(name: &str) -> Option<RobotStatus> {
+    match name {
+        "idle" => Some(RobotStatus::Idle),
+        "active" => Some(RobotStatus::Active),
+        "stunned" => Some(RobotStatus::Stunned(0)), // Replays don't keep the stun length
+        "destroyed" => Some(RobotStatus::Destroyed),
+        _ => None,
+    }
+}
+
+/// Writes a replay to disk, one frame per line
+pub fn save_replay(path: impl AsRef<Path>, frames: &[ReplayFrame]) -> io::Result<()> {
+    let contents: Vec<String> = frames.iter().map(ReplayFrame::to_line).collect();
+    fs::write(path, contents.join("\n"))
+}
+
+/// Loads a replay written by `save_replay`
+pub fn load_replay(path: impl AsRef<Path>) -> io::Result<Vec<ReplayFrame>> {
+    let contents = fs::read_to_string(path)?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            ReplayFrame::from_line(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid replay frame on line {}", i + 1),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Plays back a loaded replay. Frames are held in memory, so seeking to any
+/// frame is O(1).
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    frames: Vec<ReplayFrame>,
+    position: usize, // Index of the frame being shown
+}
+
+impl ReplayPlayer {
+    pub fn new(frames: Vec<ReplayFrame>) -> Self {
+        ReplayPlayer {
+            frames,
+            position: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> Option<&ReplayFrame> {
+        self.frames.get(self.position)
+    }
+
+    /// The frame before the current one, if any
+    pub fn previous(&self) -> Option<&ReplayFrame> {
+        self.position
+            .checked_sub(1)
+            .and_then(|index| self.frames.get(index))
+    }
+
+    /// Jumps to a frame, clamped to the last frame
+    pub fn seek(&mut self, index: usize) {
+        self.position = index.min(self.frames.len().saturating_sub(1));
+    }
+
+    /// Moves to the next frame. Returns false (staying put) at the end of the replay.
+    pub fn advance(&mut self) -> bool {
+        if self.position + 1 < self.frames.len() {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Indices of the frames in which a robot was destroyed
+    pub fn destruction_frames(&self) -> Vec<usize> {
+        let mut markers = Vec::new();
+        for (index, pair) in self.frames.windows(2).enumerate() {
+            let destroyed = pair[1].robots.iter().any(|r| {
+                r.status == RobotStatus::Destroyed
+                    && pair[0]
+                        .robots
+                        .iter()
+                        .any(|b| b.id == r.id && b.status != RobotStatus::Destroyed)
+            });
+            if destroyed {
+                markers.push(index + 1);
+            }
+        }
+        markers
+    }
+}
+
+/// Maps an x position on a timeline bar to the frame it points at. Positions
+/// outside the bar clamp to the first or last frame.
+pub fn timeline_frame_index(x: f32, bar_x: f32, bar_width: f32, frame_count: usize) -> usize {
+    if frame_count <= 1 || bar_width <= 0.0 {
+        return 0;
+    }
+    let fraction = ((x - bar_x) / bar_width).clamp(0.0, 1.0);
+    (fraction * (frame_count - 1) as f32).round() as usize
+}
+
+/// The x position of a frame on a timeline bar (the inverse of `timeline_frame_index`)
+pub fn timeline_x(frame: usize, bar_x: f32, bar_width: f32, frame_count: usize) -> f32 {
+    if frame_count <= 1 {
+        return bar_x;
+    }
+    bar_x + bar_width * frame.min(frame_count - 1) as f32 / (frame_count - 1) as f32
+}
+
+/// Finds the first frame at which two replays differ, or None if they are identical
+pub fn compare_replays(a: &[ReplayFrame], b: &[ReplayFrame]) -> Option<Divergence> {
+    for (index, (frame_a, frame_b)) in a.iter().zip(b.iter()).enumerate() {
+        let differences = diff_frames(frame_a, frame_b);
+        if !differences.is_empty() {
+            return Some(Divergence {
+                frame: index,
+                turn: frame_a.turn,
+                cycle: frame_a.cycle,
+                differences,
+            });
+        }
+    }
+
+    // One replay ended before the other
+    if a.len() != b.len() {
+        let index = a.len().min(b.len());
+        let (ended, longer, frame) = if a.len() < b.len() {
+            ("A", "B", &b[index])
+        } else {
+            ("B", "A", &a[index])
+        };
+        return Some(Divergence {
+            frame: index,
+            turn: frame.turn,
+            cycle: frame.cycle,
+            differences: vec![format!("replay {} ended while {} continues", ended, longer)],
+        });
+    }
+
+    None
+}
+
+// Lists the differing fields between two frames
+fn diff_frames(a: &ReplayFrame, b: &ReplayFrame) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    if a.turn != b.turn || a.cycle != b.cycle {
+        differences.push(format!(
+            "time: T{}/C{} vs T{}/C{}",
+            a.turn, a.cycle, b.turn, b.cycle
+        ));
+    }
+
+    for robot_a in &a.robots {
+        let Some(robot_b) = b.robots.iter().find(|r| r.id == robot_a.id) else {
+            differences.push(format!("robot {}: missing from B", robot_a.id));
+            continue;
+        };
+        if robot_a.position != robot_b.position {
+            differences.push(format!(
+                "robot {}: position ({}, {}) vs ({}, {})",
+                robot_a.id,
+                robot_a.position.x,
+                robot_a.position.y,
+                robot_b.position.x,
+                robot_b.position.y
+            ));
+        }
+        if robot_a.health != robot_b.health {
+            differences.push(format!(
+                "robot {}: health {} vs {}",
+                robot_a.id, robot_a.health, robot_b.health
+            ));
+        }
+        if robot_a.status != robot_b.status {
+            differences.push(format!(
+                "robot {}: status {:?} vs {:?}",
+                robot_a.id, robot_a.status, robot_b.status
+            ));
+        }
+    }
+    for robot_b in &b.robots {
+        if !a.robots.iter().any(|r| r.id == robot_b.id) {
+            differences.push(format!("robot {}: missing from A", robot_b.id));
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(turn: u32, cycle: u32, x: f64, health: f64) -> ReplayFrame {
+        ReplayFrame {
+            turn,
+            cycle,
+            robots: vec![
+                RobotFrame {
+                    id: 1,
+                    position: Point { x, y: 0.25 },
+                    health,
+                    status: RobotStatus::Active,
+                },
+                RobotFrame {
+                    id: 2,
+                    position: Point { x: 0.75, y: 0.75 },
+                    health: 100.0,
+                    status: RobotStatus::Active,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_compare_replays_reports_first_divergence() {
+        let a: Vec<ReplayFrame> = (0..6)
+            .map(|c| frame(0, c, 0.1 + c as f64 * 0.01, 100.0))
+            .collect();
+        let mut b = a.clone();
+        b[4].robots[0].health = 90.0;
+        b[5].robots[0].position.x = 0.5;
+
+        let divergence = compare_replays(&a, &b).expect("replays should diverge");
+        assert_eq!(divergence.frame, 4);
+        assert_eq!(divergence.cycle, 4);
+        assert_eq!(divergence.differences.len(), 1);
+        assert!(divergence.differences[0].contains("health"));
+
+        assert_eq!(compare_replays(&a, &a), None);
+    }
+
+    #[test]
+    fn test_compare_replays_different_lengths() {
+        let a: Vec<ReplayFrame> = (0..3).map(|c| frame(0, c, 0.1, 100.0)).collect();
+        let b = a[..2].to_vec();
+        let divergence = compare_replays(&a, &b).expect("replays should diverge");
+        assert_eq!(divergence.frame, 2);
+    }
+
+    #[test]
+    fn test_timeline_click_maps_to_frame() {
+        // 11 frames over a 100px bar starting at x = 50: one frame every 10px
+        assert_eq!(timeline_frame_index(50.0, 50.0, 100.0, 11), 0);
+        assert_eq!(timeline_frame_index(150.0, 50.0, 100.0, 11), 10);
+        assert_eq!(timeline_frame_index(100.0, 50.0, 100.0, 11), 5);
+        assert_eq!(timeline_frame_index(84.0, 50.0, 100.0, 11), 3);
+        assert_eq!(timeline_frame_index(86.0, 50.0, 100.0, 11), 4);
+
+        // Clicks past either end clamp
+        assert_eq!(timeline_frame_index(0.0, 50.0, 100.0, 11), 0);
+        assert_eq!(timeline_frame_index(500.0, 50.0, 100.0, 11), 10);
+
+        // Degenerate replays
+        assert_eq!(timeline_frame_index(100.0, 50.0, 100.0, 1), 0);
+        assert_eq!(timeline_frame_index(100.0, 50.0, 100.0, 0), 0);
+
+        for frame in 0..11 {
+            let x = timeline_x(frame, 50.0, 100.0, 11);
+            assert_eq!(timeline_frame_index(x, 50.0, 100.0, 11), frame);
+        }
+    }
+
+    #[test]
+    fn test_player_seeks_and_marks_destructions() {
+        let mut frames: Vec<ReplayFrame> = (0..5).map(|c| frame(0, c, 0.1, 100.0)).collect();
+        for f in &mut frames[3..] {
+            f.robots[1].status = RobotStatus::Destroyed;
+        }
+        let mut player = ReplayPlayer::new(frames);
+        assert_eq!(player.destruction_frames(), vec![3]);
+
+        player.seek(2);
+        assert_eq!(player.current().unwrap().cycle, 2);
+        assert_eq!(player.previous().unwrap().cycle, 1);
+        player.seek(99);
+        assert_eq!(player.position(), 4);
+        assert!(!player.advance());
+        player.seek(0);
+        assert!(player.previous().is_none());
+        assert!(player.advance());
+        assert_eq!(player.position(), 1);
+    }
+
+    #[test]
+    fn test_frame_line_round_trip() {
+        let original = frame(3, 42, 0.123456789, 87.5);
+        let parsed = ReplayFrame::from_line(&original.to_line()).unwrap();
+        assert_eq!(parsed, original);
+        assert!(ReplayFrame::from_line("1 2|1 0.5").is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::arena::Arena;
 use crate::config;
+use crate::events::Event;
 use crate::types::Scanner;
 use crate::types::*;
 use crate::vm;
@@ -7,14 +8,17 @@ use crate::vm::instruction::Instruction;
 use crate::vm::parser;
 use crate::vm::state::VMState;
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use std::collections::VecDeque;
 use std::f64::consts::PI;
+use std::sync::Arc;
 
 // Represents the possible states of a robot
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RobotStatus {
     Idle, // Just loaded, hasn't run yet
     Active,
+    Stunned(u32), // VM skips this many more cycles, then the robot is Active again
     Destroyed,
 }
 
@@ -56,43 +60,138 @@ impl Default for TurretComponent {
     }
 }
 
+// Represents the Shield component of a robot
+#[derive(Debug, Clone, Copy)]
+pub struct ShieldComponent {
+    pub strength: f64, // Damage the shield can still absorb (does not recharge)
+    pub active: bool,  // Absorbing hits and draining power
+}
+
+// Forward/backward wall and obstacle distances from the last raycast, reused
+// while the robot stays put and the arena layout is unchanged
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionDistanceCache {
+    position: Point,
+    direction: f64,
+    revision: u32, // Arena::obstacle_revision the distances were measured against
+    forward: f64,
+    backward: f64,
+}
+
+impl Default for ShieldComponent {
+    fn default() -> Self {
+        ShieldComponent {
+            strength: config::SHIELD_CAPACITY,
+            active: false,
+        }
+    }
+}
+
+/// Deterministic RNG for a robot, derived from a match seed and the robot id
+pub fn robot_rng(seed: u64, robot_id: u32) -> StdRng {
+    StdRng::seed_from_u64(seed ^ (robot_id as u64).rotate_left(32))
+}
+
+/// Standard normal sample (mean 0, standard deviation 1) from `rng`
+pub fn standard_normal(rng: &mut StdRng) -> f64 {
+    // Box-Muller transform; 1 - u keeps the log argument in (0, 1]
+    let u1 = 1.0 - rng.r#gen::<f64>();
+    let u2 = rng.r#gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// Deterministic 0.0/1.0 coin flip for a robot, derived from a match seed and the robot id
+pub fn coin_flip(seed: u64, robot_id: u32) -> f64 {
+    robot_rng(seed, robot_id).gen_range(0..2) as f64
+}
+
 // Represents a robot in the arena
 #[derive(Debug, Clone)]
 pub struct Robot {
-    pub id: u32,      // Unique identifier
-    pub name: String, // Name derived from filename
+    pub id: u32,        // Unique identifier
+    pub name: String,   // Name derived from filename
+    pub source: String, // Program file the robot was loaded from (its name if loaded from text)
     pub position: Point,
     pub prev_position: Point, // <-- Add previous position
     pub health: f64,
+    pub armor: f64, // Flat reduction of kinetic hits, exposed as @armor
     pub power: f64,
+    pub radius: f64, // Collision radius in arena coordinates
+    pub shape: RobotShape,
     pub status: RobotStatus,
+    pub alive_cycles: u32,              // Simulation cycles survived so far
+    pub damage_dealt: f64,              // Total projectile damage inflicted on other robots
+    pub kills: u32,                     // Robots destroyed by this robot's projectiles
+    pub shots_fired: u32,               // Ranged shots fired (shots, mortar shells and laser shots)
+    pub last_combat_cycle: Option<u32>, // Match cycle this robot last dealt or took damage
+    pub coin: f64,                      // Symmetry-breaking 0/1 value exposed as @coin
+    pub debug_output: Vec<f64>,         // `dbg` values this cycle, drained by the game loop
+    pub events: Vec<Event>,             // Hits and scans this cycle, drained by the game loop
+    pub speed_turn_coupling: bool,      // Drive turn rate shrinks as speed increases
+    pub blind_targeting: bool,          // @target_* only hold fresh scan results
+    pub sensor_noise: bool,             // Scan readings get distance-scaled noise
+    pub grid_movement: bool,            // Move cell by cell and turn in 90 degree steps
+    pub grid_step_cycles: u32,          // Cycles spent driving toward the next cell
+    pub scanned_last_cycle: bool,       // A successful scan ran in the previous VM cycle
+    pub team: u8,                       // Team number (0 = no team, fights alone)
+    pub teammates: Vec<u32>,            // Ids of the other robots on this robot's team
+    pub skip_teammates: bool,           // Scans look past teammates
+    pub vm_paused: bool,                // VM frozen for debugging; physics still applies
     pub drive: DriveComponent,
     pub prev_drive_direction: f64, // <-- Add previous drive direction
     pub turret: TurretComponent,
     pub prev_turret_direction: f64, // <-- Add previous turret direction
-    pub vm_state: VMState,          // Made public for executor access
-    pub program: Vec<Instruction>,
-    pub rng: ThreadRng,
-    pub aoi: Vec<u32>, // Area of interest - IDs of nearby robots
+    pub shield: ShieldComponent,
+    pub distance_cache: Option<CollisionDistanceCache>, // Last @forward/@backward raycast
+    pub vm_state: VMState,                              // Made public for executor access
+    pub program: Arc<[Instruction]>, // Shared so execution borrows instructions instead of cloning them
+    pub seeded_rng: StdRng,          // Reproducible randomness (rand_normal), see Game::seed_random
+    pub aoi: Vec<u32>,               // Area of interest - IDs of nearby robots
 }
 
 impl Robot {
-    // Creates a new robot with default values at a given position
+    // Creates a new robot with default values at a given position, facing the center
     pub fn new(id: u32, name: String, position: Point, center: Point) -> Self {
         // Calculate angle towards the center
         let dx = center.x - position.x;
         let dy = center.y - position.y;
         let angle_rad = dy.atan2(dx);
-        let initial_direction_deg = angle_rad.to_degrees().rem_euclid(360.0);
+        Self::with_heading(id, name, position, angle_rad.to_degrees())
+    }
+
+    // Creates a new robot with default values at a given position, facing an explicit heading
+    pub fn with_heading(id: u32, name: String, position: Point, heading: f64) -> Self {
+        let initial_direction_deg = heading.rem_euclid(360.0);
 
         Robot {
             id,
+            source: name.clone(),
             name, // Store the provided name
             position,
             prev_position: position,
             health: config::DEFAULT_INITIAL_HEALTH,
+            armor: config::DEFAULT_ROBOT_ARMOR,
             power: config::DEFAULT_INITIAL_POWER,
+            radius: config::DEFAULT_ROBOT_RADIUS,
+            shape: RobotShape::Triangle,
             status: RobotStatus::Idle,
+            alive_cycles: 0,
+            damage_dealt: 0.0,
+            kills: 0,
+            shots_fired: 0,
+            last_combat_cycle: None,
+            coin: coin_flip(0, id),
+            debug_output: Vec::new(),
+            events: Vec::new(),
+            speed_turn_coupling: false,
+            blind_targeting: false,
+            sensor_noise: false,
+            grid_movement: false,
+            grid_step_cycles: 0,
+            scanned_last_cycle: false,
+            team: 0,
+            teammates: Vec::new(),
+            skip_teammates: false,
             drive: DriveComponent {
                 direction: initial_direction_deg, // Set initial direction
                 velocity: 0.0,
@@ -106,13 +205,26 @@ impl Robot {
                 ranged: RangedWeapon::default(),
             },
             prev_turret_direction: initial_direction_deg, // Initialize prev state
+            shield: ShieldComponent::default(),
+            distance_cache: None,
             vm_state: VMState::new(),
-            program: Vec::new(), // Initialize empty program
-            rng: thread_rng(),
+            program: Arc::from([]), // Initialize empty program
+            vm_paused: false,
+            seeded_rng: robot_rng(0, id),
             aoi: Vec::new(), // Initialize empty area of interest
         }
     }
 
+    /// Points the drive and turret at `heading` degrees, as if the robot had
+    /// started the match that way
+    pub fn set_heading(&mut self, heading: f64) {
+        let heading = heading.rem_euclid(360.0);
+        self.drive.direction = heading;
+        self.prev_drive_direction = heading;
+        self.turret.direction = heading;
+        self.prev_turret_direction = heading;
+    }
+
     /// Updates the previous state fields with the current state.
     /// Should be called AFTER all simulation updates for the cycle are done.
     pub fn update_prev_state(&mut self) {
@@ -122,8 +234,20 @@ impl Robot {
     }
 
     /// Fires the ranged weapon with the specified power level, consuming power.
-    /// Returns the projectile if successfully fired, otherwise None.
+    /// Returns the projectile if successfully fired, otherwise None (out of
+    /// power, or the weapon is still cooling down from the last shot).
     pub fn fire_weapon(&mut self, requested_power: f64) -> Option<Projectile> {
+        if self.turret.ranged.cooldown_remaining > 0 {
+            crate::debug_weapon!(
+                self.id,
+                self.vm_state.turn,
+                self.vm_state.cycle,
+                "Attempted to fire during cooldown ({} cycles left)",
+                self.turret.ranged.cooldown_remaining
+            );
+            return None;
+        }
+
         // Clamp requested power to valid range [0, 1]
         let clamped_power = requested_power.clamp(0.0, 1.0);
         // Determine actual power used based on available power
@@ -141,8 +265,10 @@ impl Robot {
             return None;
         }
 
-        // Consume power
+        // Consume power and start the cooldown
         self.power -= actual_power;
+        self.shots_fired += 1;
+        self.turret.ranged.cooldown_remaining = config::WEAPON_COOLDOWN_CYCLES;
 
         // Calculate starting position from the *tip* of the turret line (80% radius)
         let start_offset_distance = config::UNIT_SIZE * 0.8; // Match visual turret line length
@@ -163,7 +289,11 @@ impl Robot {
             speed: self.turret.ranged.projectile_speed, // Use base speed directly
             power: actual_power, // Store power used for damage calculation later
             base_damage: self.turret.ranged.base_damage, // Get base damage from weapon
+            damage_type: self.turret.ranged.damage_type,
             source_robot: self.id,
+            source_team: self.team,
+            ballistic: None,
+            bounces_remaining: 0,
         };
 
         crate::debug_weapon!(
@@ -180,6 +310,118 @@ impl Robot {
         Some(projectile)
     }
 
+    /// Fires a mortar shell: costs power like `fire_weapon`, but the shell is
+    /// lobbed in an arc whose launch velocity (and so range) scales with power.
+    pub fn fire_mortar(&mut self, requested_power: f64) -> Option<Projectile> {
+        let mut shell = self.fire_weapon(requested_power)?;
+        shell.speed = config::MORTAR_SPEED;
+        shell.ballistic = Some(Ballistic {
+            height: 0.0,
+            vertical_velocity: config::MORTAR_LAUNCH_VELOCITY * shell.power,
+        });
+        Some(shell)
+    }
+
+    /// Lays a proximity mine at the robot's position. Like `fire_weapon`, the
+    /// requested power (clamped to [0, 1]) is taken from the robot's power,
+    /// scaled down to what it has left. Returns None when out of power.
+    pub fn lay_mine(&mut self, requested_power: f64) -> Option<Mine> {
+        let actual_power = requested_power.clamp(0.0, 1.0).min(self.power);
+        if actual_power <= 0.0 {
+            crate::debug_weapon!(
+                self.id,
+                self.vm_state.turn,
+                self.vm_state.cycle,
+                "Attempted to lay a mine with insufficient power ({:.4})",
+                self.power
+            );
+            return None;
+        }
+        self.power -= actual_power;
+        crate::debug_weapon!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "Laid mine (Power: {:.2}, Remaining: {:.2})",
+            actual_power,
+            self.power
+        );
+        Some(Mine {
+            position: self.position,
+            power: actual_power,
+            owner: self.id,
+            team: self.team,
+        })
+    }
+
+    /// Fires the ranged weapon as a laser: charged like `fire_beam`, but it counts as a
+    /// shot and shares the weapon cooldown with `fire_weapon`. Returns the beam power
+    /// fired (0.0 while cooling down or out of power).
+    pub fn fire_laser(&mut self, requested_power: f64) -> f64 {
+        if self.turret.ranged.cooldown_remaining > 0 {
+            crate::debug_weapon!(
+                self.id,
+                self.vm_state.turn,
+                self.vm_state.cycle,
+                "Attempted to fire laser during cooldown ({} cycles left)",
+                self.turret.ranged.cooldown_remaining
+            );
+            return 0.0;
+        }
+        let beam_power = self.fire_beam(requested_power);
+        if beam_power > 0.0 {
+            self.shots_fired += 1;
+            self.turret.ranged.cooldown_remaining = config::WEAPON_COOLDOWN_CYCLES;
+        }
+        beam_power
+    }
+
+    /// Charges the beam weapon. The requested power (clamped to [0, 1]) costs
+    /// BEAM_POWER_COST power per unit and is scaled down to what the robot can afford.
+    /// Returns the beam power actually fired (0.0 when out of power).
+    pub fn fire_beam(&mut self, requested_power: f64) -> f64 {
+        let beam_power = requested_power
+            .clamp(0.0, 1.0)
+            .min(self.power / config::BEAM_POWER_COST);
+        if beam_power <= 0.0 {
+            crate::debug_weapon!(
+                self.id,
+                self.vm_state.turn,
+                self.vm_state.cycle,
+                "Attempted to fire beam with insufficient power ({:.4})",
+                self.power
+            );
+            return 0.0;
+        }
+        self.power -= beam_power * config::BEAM_POWER_COST;
+        crate::debug_weapon!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "Fired beam (Power: {:.2}, Remaining: {:.2})",
+            beam_power,
+            self.power
+        );
+        beam_power
+    }
+
+    /// Self-destructs the robot. The blast power is the requested power (clamped to
+    /// [0, 1]) scaled by the robot's remaining power, all of which is consumed.
+    pub fn detonate(&mut self, requested_power: f64) -> f64 {
+        let blast_power = requested_power.clamp(0.0, 1.0) * self.power;
+        self.power = 0.0;
+        self.health = 0.0;
+        self.status = RobotStatus::Destroyed;
+        crate::debug_weapon!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "Detonated (Blast power: {:.2})",
+            blast_power
+        );
+        blast_power
+    }
+
     /// New method to scan for targets using a function to get robot information by ID.
     /// This avoids the need to clone the entire robots array.
     pub fn scan_for_targets_by_id<F>(
@@ -187,24 +429,57 @@ impl Robot {
         get_robot_info: &mut F,
         robot_ids: &[u32],
         arena: &Arena,
-    ) -> (f64, f64)
+    ) -> (f64, f64, Option<u32>)
+    where
+        F: FnMut(u32) -> Option<(Point, RobotStatus)>,
+    {
+        self.find_target(get_robot_info, robot_ids, arena, self.turret.scanner.fov)
+    }
+
+    /// Like `scan_for_targets_by_id`, but looks in every direction instead of
+    /// only within the scanner's field of view. Range and line of sight still apply.
+    pub fn sweep_for_targets_by_id<F>(
+        &self,
+        get_robot_info: &mut F,
+        robot_ids: &[u32],
+        arena: &Arena,
+    ) -> (f64, f64, Option<u32>)
+    where
+        F: FnMut(u32) -> Option<(Point, RobotStatus)>,
+    {
+        self.find_target(get_robot_info, robot_ids, arena, 360.0)
+    }
+
+    // Nearest visible target within `fov` degrees centered on the turret:
+    // (distance, absolute angle, id), or (0.0, 0.0, None) when nothing is found
+    fn find_target<F>(
+        &self,
+        get_robot_info: &mut F,
+        robot_ids: &[u32],
+        arena: &Arena,
+        fov: f64,
+    ) -> (f64, f64, Option<u32>)
     where
         F: FnMut(u32) -> Option<(Point, RobotStatus)>,
     {
         // Setup scanning variables
         let scanner_pos = self.position;
         let scanner_dir_rad = self.turret.direction.to_radians();
-        let scan_fov_half_rad = (self.turret.scanner.fov / 2.0).to_radians();
+        let scan_fov_half_rad = (fov / 2.0).to_radians();
         let mut closest_target_dist_sq = f64::INFINITY;
         let mut target_found = false;
         let mut best_target_angle_deg = 0.0;
         let mut best_target_dist = 0.0;
+        let mut best_target_id = None;
 
         // Scan through robot IDs
         for &other_id in robot_ids {
             if other_id == self.id {
                 continue; // Don't scan self
             }
+            if self.skip_teammates && self.teammates.contains(&other_id) {
+                continue; // Only enemies show up
+            }
 
             // Get position and status information using the provided closure
             if let Some((target_pos, status)) = get_robot_info(other_id) {
@@ -217,7 +492,9 @@ impl Robot {
                 let dist_sq = dx * dx + dy * dy;
 
                 // 1. Check if within range (using squared distances)
-                if dist_sq <= closest_target_dist_sq {
+                if dist_sq <= closest_target_dist_sq
+                    && dist_sq <= self.turret.scanner.range * self.turret.scanner.range
+                {
                     // 2. Calculate angle to target
                     let angle_to_target_rad = dy.atan2(dx);
                     let angle_to_target_deg_normalized =
@@ -241,6 +518,7 @@ impl Robot {
                             target_found = true;
                             best_target_angle_deg = angle_to_target_deg_normalized;
                             best_target_dist = target_dist;
+                            best_target_id = Some(other_id);
                         }
                     }
                 }
@@ -249,36 +527,202 @@ impl Robot {
 
         // Return results directly
         if target_found {
-            (best_target_dist, best_target_angle_deg)
+            (best_target_dist, best_target_angle_deg, best_target_id)
         } else {
-            (0.0, 0.0) // Return 0.0 distance and 0.0 angle if no target found
+            (0.0, 0.0, None) // Return 0.0 distance and 0.0 angle if no target found
         }
     }
 
     /// Loads a pre-parsed robot assembly program
     pub fn load_program(&mut self, program: parser::ParsedProgram) {
         // Store the instructions
-        self.program = program.instructions;
+        self.program = program.instructions.into();
         // Labels are handled by the parser and resolved to indices,
         // so we don't need to store program.labels here unless needed for debugging.
 
         // Reset VM state for the new program
         self.vm_state = VMState::new();
 
+        // Pre-populate memory from the program's .data section
+        let data_len = program.data.len().min(self.vm_state.memory.len());
+        self.vm_state.memory[..data_len].copy_from_slice(&program.data[..data_len]);
+
+        self.set_shape(program.shape);
+        self.turret.scanner = Scanner::with_settings(program.scanner);
+
         // Program loaded, robot is ready (or Idle until first update)
         self.status = RobotStatus::Idle;
     }
 
-    /// Updates the read-only registers in the VM state before each VM cycle execution
-    pub fn update_vm_state_registers(&mut self, arena: &Arena) {
-        // Update @rand register
-        let random_value = self.rng.r#gen::<f64>(); // <-- Fix gen call
+    /// Changes the body shape, resizing the collision footprint to match
+    pub fn set_shape(&mut self, shape: RobotShape) {
+        self.shape = shape;
+        self.radius = shape.footprint_radius(config::DEFAULT_ROBOT_RADIUS);
+    }
+
+    /// Turns the shield on or off. A depleted shield stays off.
+    pub fn set_shield(&mut self, on: bool) {
+        self.shield.active = on && self.shield.strength > 0.0;
+    }
+
+    /// Lets an active shield soak up as much of `damage` as its strength
+    /// allows, returning what gets through to health. The shield switches off
+    /// once depleted.
+    pub fn absorb_with_shield(&mut self, damage: f64) -> f64 {
+        if !self.shield.active {
+            return damage;
+        }
+        let absorbed = damage.min(self.shield.strength);
+        self.shield.strength -= absorbed;
+        if self.shield.strength <= 0.0 {
+            self.shield.active = false;
+        }
+        damage - absorbed
+    }
+
+    /// Stuns the robot: its VM runs no instructions (so it can't fire) for
+    /// the next `cycles` cycles, while movement and rotation already under way
+    /// carry on. A longer stun replaces a shorter one; destroyed robots ignore it.
+    #[allow(dead_code)] // Nothing in the arena stuns yet
+    pub fn apply_stun(&mut self, cycles: u32) {
+        let remaining = match self.status {
+            RobotStatus::Destroyed => return,
+            RobotStatus::Stunned(remaining) => remaining,
+            RobotStatus::Idle | RobotStatus::Active => 0,
+        };
+        if cycles > remaining {
+            self.status = RobotStatus::Stunned(cycles);
+        }
+    }
+
+    // Counts down a stun by one VM cycle, reactivating the robot on the last
+    // one. Returns true if the robot was stunned this cycle (skip execution).
+    fn tick_stun(&mut self) -> bool {
+        let RobotStatus::Stunned(remaining) = self.status else {
+            return false;
+        };
+        self.status = if remaining > 1 {
+            RobotStatus::Stunned(remaining - 1)
+        } else {
+            RobotStatus::Active
+        };
+        true
+    }
+
+    /// Weighted fitness score exposed through the @score register
+    pub fn score(&self) -> f64 {
+        self.health * config::SCORE_WEIGHT_HEALTH
+            + self.damage_dealt * config::SCORE_WEIGHT_DAMAGE
+            + self.kills as f64 * config::SCORE_WEIGHT_KILLS
+            + self.alive_cycles as f64 * config::SCORE_WEIGHT_ALIVE
+    }
+
+    // Cycles elapsed since the start of the match, from the VM's turn/cycle counters
+    fn match_cycle(&self) -> u32 {
+        self.vm_state.turn * config::CYCLES_PER_TURN + self.vm_state.cycle
+    }
+
+    /// Records that this robot dealt or took damage this cycle
+    pub fn record_combat(&mut self) {
+        self.last_combat_cycle = Some(self.match_cycle());
+    }
+
+    /// True if the robot dealt or took damage within the last COMBAT_RECENCY_CYCLES
+    pub fn in_combat(&self) -> bool {
+        self.last_combat_cycle.is_some_and(|cycle| {
+            self.match_cycle().saturating_sub(cycle) < config::COMBAT_RECENCY_CYCLES
+        })
+    }
+
+    /// Sets @forward_clear_distance to the nearest wall, obstacle or other robot
+    /// along the drive direction. `robots` holds (id, position, radius) of live
+    /// robots; this robot's own entry is skipped.
+    pub fn update_forward_clear_distance(&mut self, arena: &Arena, robots: &[(u32, Point, f64)]) {
+        let others: Vec<(Point, f64)> = robots
+            .iter()
+            .filter(|(id, _, _)| *id != self.id)
+            .map(|(_, position, radius)| (*position, *radius))
+            .collect();
+        // Same cached raycast as @forward_distance, which was set earlier this cycle
+        let (wall_dist, _) = self.collision_distances(arena);
+        let robot_dist =
+            arena.distance_to_robots(self.position, self.drive.direction, self.radius, &others);
+        self.vm_state
+            .registers
+            .set_internal(
+                vm::registers::Register::ForwardClearDistance,
+                wall_dist.min(robot_dist),
+            )
+            .unwrap();
+    }
+
+    /// Sets @closest_ally_distance/@closest_ally_direction to the nearest living
+    /// teammate. `robots` holds (id, team, position) of live robots; both
+    /// registers are 0 when this robot has no team or no living teammates.
+    pub fn update_closest_ally(&mut self, robots: &[(u32, u8, Point)]) {
+        let closest = robots
+            .iter()
+            .filter(|(id, team, _)| self.team != 0 && *team == self.team && *id != self.id)
+            .map(|(_, _, position)| (self.position.distance(position), *position))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let (distance, direction) = match closest {
+            Some((distance, position)) => {
+                let dx = position.x - self.position.x;
+                let dy = position.y - self.position.y;
+                (distance, dy.atan2(dx).to_degrees().rem_euclid(360.0))
+            }
+            None => (0.0, 0.0),
+        };
+        let registers = &mut self.vm_state.registers;
+        registers
+            .set_internal(vm::registers::Register::ClosestAllyDistance, distance)
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::ClosestAllyDirection, direction)
+            .unwrap();
+    }
+
+    /// Distances to the nearest wall or obstacle straight ahead and straight behind.
+    /// The raycasts are skipped while the robot hasn't moved or turned and the
+    /// arena layout hasn't changed since the last call.
+    fn collision_distances(&mut self, arena: &Arena) -> (f64, f64) {
+        if let Some(cache) = self.distance_cache
+            && cache.revision == arena.obstacle_revision
+            && (cache.position.x - self.position.x).abs() <= config::DISTANCE_CACHE_EPSILON
+            && (cache.position.y - self.position.y).abs() <= config::DISTANCE_CACHE_EPSILON
+            && (cache.direction - self.drive.direction).abs() <= config::DISTANCE_CACHE_EPSILON
+        {
+            return (cache.forward, cache.backward);
+        }
 
-        // Calculate forward/backward distances
         let forward_angle = self.drive.direction;
         let backward_angle = (self.drive.direction + 180.0).rem_euclid(360.0);
-        let forward_dist = arena.distance_to_collision(self.position, forward_angle);
-        let backward_dist = arena.distance_to_collision(self.position, backward_angle);
+        let forward = arena.distance_to_collision(self.position, forward_angle);
+        let backward = arena.distance_to_collision(self.position, backward_angle);
+        self.distance_cache = Some(CollisionDistanceCache {
+            position: self.position,
+            direction: self.drive.direction,
+            revision: arena.obstacle_revision,
+            forward,
+            backward,
+        });
+        (forward, backward)
+    }
+
+    /// Updates the read-only registers in the VM state before each VM cycle execution.
+    /// `random_value` is this cycle's @rand, drawn from the match RNG.
+    pub fn update_vm_state_registers(&mut self, arena: &Arena, random_value: f64) {
+        let (forward_dist, backward_dist) = self.collision_distances(arena);
+        // Refined with other robots by update_forward_clear_distance from the game loop
+        let forward_clear_dist = forward_dist;
+
+        let score = self.score();
+        let in_combat = if self.in_combat() { 1.0 } else { 0.0 };
+        let coin = self.coin;
+
+        // Under blind targeting, scan results are only readable by the instruction right after the scan
+        let clear_targets = self.blind_targeting && !self.scanned_last_cycle;
+        self.scanned_last_cycle = false;
 
         let registers = &mut self.vm_state.registers;
         // Use .set_internal() for read-only registers
@@ -294,6 +738,12 @@ impl Robot {
         registers
             .set_internal(vm::registers::Register::Health, self.health)
             .unwrap();
+        registers
+            .set_internal(vm::registers::Register::Armor, self.armor)
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::Shield, self.shield.strength)
+            .unwrap();
         registers
             .set_internal(vm::registers::Register::Power, self.power)
             .unwrap();
@@ -325,13 +775,45 @@ impl Robot {
         registers
             .set_internal(vm::registers::Register::BackwardDistance, backward_dist)
             .unwrap();
+        registers
+            .set_internal(
+                vm::registers::Register::ForwardClearDistance,
+                forward_clear_dist,
+            )
+            .unwrap();
         // Weapon related registers
         registers
             .set_internal(vm::registers::Register::WeaponPower, self.power)
             .unwrap(); // Example: Use robot power
         registers
-            .set_internal(vm::registers::Register::WeaponCooldown, 0.0)
-            .unwrap(); // Placeholder
+            .set_internal(
+                vm::registers::Register::WeaponCooldown,
+                self.turret.ranged.cooldown_remaining as f64,
+            )
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::AliveTime, self.alive_cycles as f64)
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::Score, score)
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::InCombat, in_combat)
+            .unwrap();
+        registers
+            .set_internal(vm::registers::Register::Coin, coin)
+            .unwrap();
+        if clear_targets {
+            registers
+                .set_internal(vm::registers::Register::TargetDistance, 0.0)
+                .unwrap();
+            registers
+                .set_internal(vm::registers::Register::TargetDirection, 0.0)
+                .unwrap();
+            registers
+                .set_internal(vm::registers::Register::TargetId, -1.0)
+                .unwrap();
+        }
     }
 
     /// Execute one simulation cycle's worth of VM instructions.
@@ -342,15 +824,25 @@ impl Robot {
         all_robots: &[Robot],
         arena: &Arena,
         command_queue: &mut VecDeque<ArenaCommand>,
+        trace_robot: Option<u32>,
     ) {
+        let tracing = crate::logging::should_trace(trace_robot, self.id);
+
         // Transition from Idle to Active *before* the guard check
         if self.status == RobotStatus::Idle {
             self.status = RobotStatus::Active;
         }
 
+        if self.tick_stun() {
+            return;
+        }
+
         // Now check if we should execute based on the potentially updated status
-        if self.status != RobotStatus::Active || self.vm_state.fault.is_some() {
-            return; // Don't execute if not active or already faulted
+        if self.status != RobotStatus::Active
+            || self.vm_state.fault.is_some()
+            || self.vm_state.halted
+        {
+            return; // Don't execute if not active, already faulted or halted
         }
 
         // --- Check if waiting for multi-cycle instruction ---
@@ -362,14 +854,16 @@ impl Robot {
         // Create instruction executor
         let executor = vm::executor::InstructionExecutor::new();
 
+        // Hold our own handle so the instruction can be borrowed while `self` is mutated
+        let program = Arc::clone(&self.program);
         let ip = self.vm_state.ip;
         let mut spent = 0;
 
         while spent < 1 {
             // --- Get and Execute Instruction ---
-            if let Some(instr) = self.program.get(ip).cloned() {
+            if let Some(instr) = program.get(ip) {
                 // Get the current instruction location for debugging
-                if ip < self.program.len() {
+                if tracing && ip < program.len() {
                     let instr_str = format!("{:?}", instr);
                     crate::debug_instructions!(
                         self.id,
@@ -400,12 +894,16 @@ impl Robot {
                 // Calculate cost BEFORE execution (needed for Rotate cost)
                 let cost = instr.cycle_cost(&self.vm_state);
                 spent += cost;
+                self.vm_state
+                    .registers
+                    .set_internal(vm::registers::Register::LastInstructionCost, cost as f64)
+                    .unwrap();
 
                 // Store initial IP in case instruction doesn't modify it (e.g., jumps)
                 let ip_before_exec = self.vm_state.ip;
 
                 // Execute the instruction, passing the necessary context
-                match executor.execute_instruction(self, all_robots, arena, &instr, command_queue) {
+                match executor.execute_instruction(self, all_robots, arena, instr, command_queue) {
                     // Pass all_robots and arena
                     Ok(()) => {
                         // Instruction succeeded
@@ -441,92 +939,15 @@ impl Robot {
                 }
             } else {
                 // End of program reached or invalid IP
-                // Halt execution by setting remaining cycles high?
                 self.vm_state.instruction_cycles_remaining = u32::MAX; // Effectively halts
+                break;
             }
         }
 
         // --- End of instructions for this cycle ---
-
-        // --- Debug Output ---
-        crate::debug_instructions!(
-            self.id,
-            self.vm_state.turn,
-            self.vm_state.cycle,
-            "-- Cycle End --"
-        );
-        // Print registers with names
-        use crate::vm::registers::Register::*; // Import variants for easier access
-        let all_regs = [
-            D1,
-            D2,
-            D3,
-            D4,
-            D5,
-            D6,
-            D7,
-            D8,
-            D9,
-            C,
-            Result,
-            Fault,
-            Turn,
-            Cycle,
-            Rand,
-            Health,
-            Power,
-            Component,
-            TurretDirection,
-            DriveDirection,
-            DriveVelocity,
-            PosX,
-            PosY,
-            ForwardDistance,
-            BackwardDistance,
-            WeaponPower,
-            WeaponCooldown,
-            TargetDistance,
-            TargetDirection,
-        ];
-        for reg in all_regs.iter() {
-            match self.vm_state.registers.get(*reg) {
-                Ok(value) => crate::debug_instructions!(
-                    self.id,
-                    self.vm_state.turn,
-                    self.vm_state.cycle,
-                    "{:?}: {:.4}",
-                    reg,
-                    value
-                ),
-                Err(e) => crate::debug_instructions!(
-                    self.id,
-                    self.vm_state.turn,
-                    self.vm_state.cycle,
-                    "{:?}: Error getting value: {:?}",
-                    reg,
-                    e
-                ),
-            }
+        if tracing {
+            self.trace_cycle_end();
         }
-
-        // Use the new Stack::view() method
-        crate::debug_instructions!(
-            self.id,
-            self.vm_state.turn,
-            self.vm_state.cycle,
-            "Stack (top first): {:?}",
-            self.vm_state.stack.view().iter().rev().collect::<Vec<_>>()
-        );
-        crate::debug_instructions!(
-            self.id,
-            self.vm_state.turn,
-            self.vm_state.cycle,
-            "IP: {}, Cycles Left: {}, Fault: {:?}",
-            self.vm_state.ip,
-            self.vm_state.instruction_cycles_remaining,
-            self.vm_state.fault
-        );
-        // --- End Debug ---
     }
 
     /// A version of execute_vm_cycle that uses a robot info provider function to avoid cloning
@@ -536,6 +957,7 @@ impl Robot {
         get_robot_info: &mut G,
         arena: &Arena,
         command_queue: &mut VecDeque<ArenaCommand>,
+        trace_robot: Option<u32>,
     ) -> Option<vm::error::VMFault>
     where
         F: Fn() -> Vec<u32>,
@@ -543,17 +965,33 @@ impl Robot {
     {
         use log::debug;
 
+        let tracing = crate::logging::should_trace(trace_robot, self.id);
+
         // Transition from Idle to Active *before* the guard check
         if self.status == RobotStatus::Idle {
             debug!("Robot {}: Transitioning from Idle to Active", self.id);
             self.status = RobotStatus::Active;
         }
 
+        if self.tick_stun() {
+            return None;
+        }
+
         // Now check if we should execute based on the potentially updated status
         if self.status != RobotStatus::Active || self.vm_state.fault.is_some() {
             return self.vm_state.fault; // Don't execute if not active or already faulted
         }
 
+        // A halted program has finished; physics still applies
+        if self.vm_state.halted {
+            return None;
+        }
+
+        // A paused VM holds its place, including any multi-cycle instruction in progress
+        if self.vm_paused {
+            return None;
+        }
+
         // --- Check if waiting for multi-cycle instruction ---
         if self.vm_state.instruction_cycles_remaining > 0 {
             self.vm_state.instruction_cycles_remaining -= 1;
@@ -564,23 +1002,33 @@ impl Robot {
         let executor = vm::executor::InstructionExecutor::new();
 
         let robot_ids = get_robot_ids();
+        // Hold our own handle so the instruction can be borrowed while `self` is mutated
+        let program = Arc::clone(&self.program);
         let ip = self.vm_state.ip;
         let mut spent = 0;
 
         while spent < 1 {
-            if let Some(instr) = self.program.get(ip).cloned() {
+            if let Some(instr) = program.get(ip) {
                 // Get the current instruction location for debugging
-                if ip < self.program.len() {
+                if tracing && ip < program.len() {
                     let instr_str = format!("{:?}", instr);
-                    debug!(
-                        "Robot {} executing instruction at IP {}: {}",
-                        self.id, ip, instr_str
+                    crate::debug_instructions!(
+                        self.id,
+                        self.vm_state.turn,
+                        self.vm_state.cycle,
+                        "Executing instruction at IP {}: {}",
+                        ip,
+                        instr_str
                     );
                 }
 
                 // Calculate cost BEFORE execution
                 let cost = instr.cycle_cost(&self.vm_state);
                 spent += cost;
+                self.vm_state
+                    .registers
+                    .set_internal(vm::registers::Register::LastInstructionCost, cost as f64)
+                    .unwrap();
 
                 // Store initial IP in case instruction doesn't modify it
                 let ip_before_exec = self.vm_state.ip;
@@ -591,7 +1039,7 @@ impl Robot {
                     get_robot_info,
                     &robot_ids,
                     arena,
-                    &instr,
+                    instr,
                     command_queue,
                 );
 
@@ -615,6 +1063,9 @@ impl Robot {
                         debug!("Robot {} VM Fault at IP {}: {:?}", self.id, ip, fault);
                         self.vm_state.set_fault(fault);
                         self.vm_state.instruction_cycles_remaining = u32::MAX; // Effectively halts
+                        if tracing {
+                            self.trace_cycle_end();
+                        }
                         return Some(fault);
                     }
                 }
@@ -625,7 +1076,106 @@ impl Robot {
             }
         }
 
-        None // No fault occurred
+        if tracing {
+            self.trace_cycle_end();
+        }
+
+        None // No fault occurred
+    }
+
+    /// Logs the end-of-cycle register, stack and IP dump used by instruction tracing
+    fn trace_cycle_end(&self) {
+        // --- Debug Output ---
+        crate::debug_instructions!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "-- Cycle End --"
+        );
+        // Print registers with names
+        use crate::vm::registers::Register::*; // Import variants for easier access
+        let all_regs = [
+            D1,
+            D2,
+            D3,
+            D4,
+            D5,
+            D6,
+            D7,
+            D8,
+            D9,
+            C,
+            Result,
+            Fault,
+            Turn,
+            Cycle,
+            Rand,
+            Health,
+            Power,
+            Component,
+            TurretDirection,
+            DriveDirection,
+            DriveVelocity,
+            PosX,
+            PosY,
+            ForwardDistance,
+            BackwardDistance,
+            WeaponPower,
+            WeaponCooldown,
+            TargetDistance,
+            TargetDirection,
+            AliveTime,
+            Score,
+            InCombat,
+            Coin,
+            ForwardClearDistance,
+            LastTargetX,
+            LastTargetY,
+            ClosestAllyDistance,
+            ClosestAllyDirection,
+            LastInstructionCost,
+            TargetId,
+            Armor,
+            Shield,
+        ];
+        for reg in all_regs.iter() {
+            match self.vm_state.registers.get(*reg) {
+                Ok(value) => crate::debug_instructions!(
+                    self.id,
+                    self.vm_state.turn,
+                    self.vm_state.cycle,
+                    "{:?}: {:.4}",
+                    reg,
+                    value
+                ),
+                Err(e) => crate::debug_instructions!(
+                    self.id,
+                    self.vm_state.turn,
+                    self.vm_state.cycle,
+                    "{:?}: Error getting value: {:?}",
+                    reg,
+                    e
+                ),
+            }
+        }
+
+        // Use the new Stack::view() method
+        crate::debug_instructions!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "Stack (top first): {:?}",
+            self.vm_state.stack.view().iter().rev().collect::<Vec<_>>()
+        );
+        crate::debug_instructions!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "IP: {}, Cycles Left: {}, Fault: {:?}",
+            self.vm_state.ip,
+            self.vm_state.instruction_cycles_remaining,
+            self.vm_state.fault
+        );
     }
 
     // --- Component Control Methods ---
@@ -642,7 +1192,12 @@ impl Robot {
             velocity * config::CYCLES_PER_TURN as f64 / config::UNIT_SIZE
         );
 
-        self.drive.velocity = velocity;
+        // Snap negligible velocities to a full stop to avoid jitter
+        self.drive.velocity = if velocity.abs() < config::VELOCITY_DEADZONE {
+            0.0
+        } else {
+            velocity
+        };
 
         crate::debug_drive!(
             self.id,
@@ -655,6 +1210,10 @@ impl Robot {
 
     // Requests a relative rotation for the drive component
     pub fn request_drive_rotation(&mut self, angle_delta: f64) {
+        // Ignore negligible rotation requests
+        if angle_delta.abs() < config::ROTATION_DEADZONE {
+            return;
+        }
         // Accumulate requested rotation. Actual rotation happens in `update`.
         let adjusted = self.drive.pending_rotation + angle_delta;
         crate::debug_drive!(
@@ -672,6 +1231,10 @@ impl Robot {
 
     // Requests a relative rotation for the turret component
     pub fn request_turret_rotation(&mut self, angle_delta: f64) {
+        // Ignore negligible rotation requests
+        if angle_delta.abs() < config::ROTATION_DEADZONE {
+            return;
+        }
         // Accumulate requested rotation. Actual rotation happens in `update`.
         let adjusted = self.drive.pending_rotation + angle_delta;
         crate::debug_weapon!(
@@ -695,14 +1258,33 @@ impl Robot {
     pub fn process_cycle_updates(&mut self, arena: &Arena) {
         // --- Power Regeneration ---
         self.power = (self.power + config::POWER_REGEN_RATE).min(1.0);
+        if self.shield.active {
+            // The shield drops when the robot can no longer power it
+            if self.power >= config::SHIELD_POWER_DRAIN {
+                self.power -= config::SHIELD_POWER_DRAIN;
+            } else {
+                self.shield.active = false;
+            }
+        }
+        self.turret.ranged.cooldown_remaining =
+            self.turret.ranged.cooldown_remaining.saturating_sub(1);
+
+        if self.grid_movement {
+            self.process_grid_cycle(arena);
+            return;
+        }
 
         // --- Process Rotations ---
         let max_rot = config::MAX_ROTATION_PER_CYCLE;
+        let max_drive_rot = self.max_drive_rotation_per_cycle();
 
         // Process Drive Rotation
         if self.drive.pending_rotation.abs() > 1e-6 {
             // Use epsilon comparison
-            let drive_rot_this_cycle = self.drive.pending_rotation.clamp(-max_rot, max_rot);
+            let drive_rot_this_cycle = self
+                .drive
+                .pending_rotation
+                .clamp(-max_drive_rot, max_drive_rot);
             let old_dir = self.drive.direction;
             self.drive.direction = (self.drive.direction + drive_rot_this_cycle).rem_euclid(360.0);
             self.drive.pending_rotation -= drive_rot_this_cycle;
@@ -747,6 +1329,81 @@ impl Robot {
         self.process_movement(arena);
     }
 
+    // Grid movement model: the drive snaps to the nearest multiple of 90 degrees and,
+    // while the drive velocity is nonzero, the robot hops one whole cell per turn
+    // (backwards for negative velocity) from cell center to cell center. Hops into
+    // a wall or obstacle stop the drive instead. The turret rotates as usual.
+    fn process_grid_cycle(&mut self, arena: &Arena) {
+        if self.drive.pending_rotation != 0.0 {
+            let target = self.drive.direction + self.drive.pending_rotation;
+            self.drive.direction = ((target / 90.0).round() * 90.0).rem_euclid(360.0);
+            self.drive.pending_rotation = 0.0;
+        }
+
+        let max_rot = config::MAX_ROTATION_PER_CYCLE;
+        if self.turret.pending_rotation.abs() > 1e-6 {
+            let turret_rot_this_cycle = self.turret.pending_rotation.clamp(-max_rot, max_rot);
+            self.turret.direction =
+                (self.turret.direction + turret_rot_this_cycle).rem_euclid(360.0);
+            self.turret.pending_rotation -= turret_rot_this_cycle;
+        } else {
+            self.turret.pending_rotation = 0.0;
+        }
+
+        if self.drive.velocity == 0.0 {
+            self.grid_step_cycles = 0;
+            return;
+        }
+        self.grid_step_cycles += 1;
+        if self.grid_step_cycles < config::CYCLES_PER_TURN {
+            return;
+        }
+        self.grid_step_cycles = 0;
+
+        let angle_rad = self.drive.direction.to_radians();
+        let sign = self.drive.velocity.signum();
+        let (cell_x, cell_y) = arena.world_to_grid(self.position);
+        let next_x = cell_x as i64 + (angle_rad.cos() * sign).round() as i64;
+        let next_y = cell_y as i64 + (angle_rad.sin() * sign).round() as i64;
+        if next_x < 0
+            || next_y < 0
+            || next_x >= arena.grid_width as i64
+            || next_y >= arena.grid_height as i64
+        {
+            self.drive.velocity = 0.0;
+            return;
+        }
+        let next_pos = arena.grid_to_world(next_x as u32, next_y as u32);
+        if arena.check_collision(next_pos) {
+            self.drive.velocity = 0.0;
+            return;
+        }
+        crate::debug_drive!(
+            self.id,
+            self.vm_state.turn,
+            self.vm_state.cycle,
+            "Grid step from ({}, {}) to ({}, {})",
+            cell_x,
+            cell_y,
+            next_x,
+            next_y
+        );
+        self.position = next_pos;
+    }
+
+    // Maximum drive rotation this cycle; with speed/turn coupling enabled the
+    // limit falls from MAX_ROTATION_PER_CYCLE at rest towards
+    // SPEED_TURN_MIN_FACTOR of it at full speed
+    fn max_drive_rotation_per_cycle(&self) -> f64 {
+        if !self.speed_turn_coupling {
+            return config::MAX_ROTATION_PER_CYCLE;
+        }
+        let max_velocity = config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR;
+        let speed_ratio = (self.drive.velocity.abs() / max_velocity).clamp(0.0, 1.0);
+        let falloff = speed_ratio.powf(config::SPEED_TURN_CURVE_EXPONENT);
+        config::MAX_ROTATION_PER_CYCLE * (1.0 - (1.0 - config::SPEED_TURN_MIN_FACTOR) * falloff)
+    }
+
     // Processes movement based on velocity and checks for collisions
     fn process_movement(&mut self, arena: &Arena) {
         // DEBUG: Log velocity at start of movement processing
@@ -905,7 +1562,7 @@ mod tests {
         command_queue: &mut VecDeque<ArenaCommand>,
     ) {
         robot.vm_state.instruction_cycles_remaining = 0; // Reset for test
-        robot.execute_vm_cycle(all_robots, arena, command_queue);
+        robot.execute_vm_cycle(all_robots, arena, command_queue, None);
         robot.process_cycle_updates(arena);
     }
 
@@ -1230,7 +1887,7 @@ mod tests {
 
         // Execute the div instruction which should cause a fault
         robot.vm_state.instruction_cycles_remaining = 0; // Reset for test
-        robot.execute_vm_cycle(&[], &arena, &mut command_queue);
+        robot.execute_vm_cycle(&[], &arena, &mut command_queue, None);
 
         // Now check for the fault
         assert!(
@@ -1267,6 +1924,509 @@ mod tests {
         assert_eq!(val, 123.0);
     }
 
+    #[test]
+    fn test_halt_stops_program_without_fault() {
+        let mut robot = Robot::new(
+            0,
+            String::new(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 1.0, y: 0.5 },
+        );
+        robot.status = RobotStatus::Active;
+        let arena = Arena::default();
+        robot.load_program(parse_program(
+            "select 1\ndrive 1\nhalt\nmov @d0 5\nstart:\njmp start",
+        ));
+
+        for _ in 0..3 {
+            simulate_cycle(&mut robot, &[], &arena, &mut VecDeque::new());
+        }
+        assert!(robot.vm_state.halted);
+        let halted_at = robot.vm_state.ip;
+        let position = robot.position.x;
+
+        for _ in 0..5 {
+            simulate_cycle(&mut robot, &[], &arena, &mut VecDeque::new());
+        }
+        assert!(robot.vm_state.fault.is_none());
+        assert_eq!(robot.vm_state.registers.get(Register::Fault).unwrap(), 0.0);
+        assert_eq!(robot.vm_state.registers.get(Register::D0).unwrap(), 0.0);
+        assert_eq!(robot.vm_state.ip, halted_at);
+        // The drive keeps the velocity it was given before the halt
+        assert!(robot.position.x > position);
+    }
+
+    #[test]
+    fn test_yield_waits_out_the_rest_of_the_turn() {
+        let mut robot = Robot::new(
+            0,
+            String::new(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        robot.status = RobotStatus::Active;
+        let arena = Arena::default();
+        robot.load_program(parse_program("yield\nmov @d0 1\nmov @d1 2"));
+
+        // Yield partway through turn 1, then run the VM cycle by cycle like the game does
+        robot.vm_state.turn = 1;
+        robot.vm_state.cycle = 40;
+        let mut d0_set_at = None;
+        for _ in 0..config::CYCLES_PER_TURN {
+            robot.execute_vm_cycle(&[], &arena, &mut VecDeque::new(), None);
+            if d0_set_at.is_none() && robot.vm_state.registers.get(Register::D0).unwrap() == 1.0 {
+                d0_set_at = Some((robot.vm_state.turn, robot.vm_state.cycle));
+            }
+            robot.vm_state.cycle += 1;
+            if robot.vm_state.cycle == config::CYCLES_PER_TURN {
+                robot.vm_state.turn += 1;
+                robot.vm_state.cycle = 0;
+            }
+        }
+        assert_eq!(d0_set_at, Some((2, 0)));
+    }
+
+    #[test]
+    fn test_data_section_initializes_memory() {
+        let mut robot = Robot::new(
+            0,
+            String::new(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        let arena = Arena::default();
+
+        let program = parse_program(
+            r#"
+            .const BASE 10
+            .data 1.5 BASE BASE*2
+            .data 7
+            lod @d0
+            lod @d1
+            lod @d2
+            lod @d3
+        "#,
+        );
+
+        robot.load_program(program);
+        for _ in 0..4 {
+            simulate_cycle(&mut robot, &[], &arena, &mut VecDeque::new());
+        }
+
+        assert_eq!(robot.vm_state.registers.get(Register::D0).unwrap(), 1.5);
+        assert_eq!(robot.vm_state.registers.get(Register::D1).unwrap(), 10.0);
+        assert_eq!(robot.vm_state.registers.get(Register::D2).unwrap(), 20.0);
+        assert_eq!(robot.vm_state.registers.get(Register::D3).unwrap(), 7.0);
+        assert_eq!(robot.vm_state.registers.get(Register::Index).unwrap(), 4.0);
+    }
+
+    #[test]
+    fn test_drive_and_rotation_deadzones() {
+        let (mut robot, _arena) = setup_test_robot();
+
+        // Sub-deadzone velocity snaps to zero; normal velocities pass through
+        robot.set_drive_velocity(config::VELOCITY_DEADZONE / 2.0);
+        assert_eq!(robot.drive.velocity, 0.0);
+        robot.set_drive_velocity(-config::VELOCITY_DEADZONE / 2.0);
+        assert_eq!(robot.drive.velocity, 0.0);
+        robot.set_drive_velocity(config::DRIVE_VELOCITY_FACTOR);
+        assert_eq!(robot.drive.velocity, config::DRIVE_VELOCITY_FACTOR);
+
+        // Sub-deadzone rotations are ignored
+        robot.request_drive_rotation(config::ROTATION_DEADZONE / 2.0);
+        assert_eq!(robot.drive.pending_rotation, 0.0);
+        robot.request_turret_rotation(-config::ROTATION_DEADZONE / 2.0);
+        assert_eq!(robot.turret.pending_rotation, 0.0);
+        robot.request_drive_rotation(10.0);
+        assert_eq!(robot.drive.pending_rotation, 10.0);
+    }
+
+    #[test]
+    fn test_grid_movement_steps_one_cell_per_turn() {
+        let arena = Arena::new();
+        let start = arena.grid_to_world(5, 5);
+        let mut robot = Robot::with_heading(1, "Grid".to_string(), start, 80.0);
+        robot.grid_movement = true;
+
+        // An 80 degree turn from an 80 degree heading snaps to 180 (west)
+        robot.request_drive_rotation(80.0);
+        robot.set_drive_velocity(config::DRIVE_VELOCITY_FACTOR);
+        for _ in 0..config::CYCLES_PER_TURN - 1 {
+            robot.process_cycle_updates(&arena);
+        }
+        assert_eq!(robot.drive.direction, 180.0);
+        assert_eq!(robot.position, start);
+
+        robot.process_cycle_updates(&arena);
+        assert_eq!(robot.position, arena.grid_to_world(4, 5));
+    }
+
+    #[test]
+    fn test_speed_turn_coupling_widens_turns() {
+        // Counts cycles needed to finish a 90 degree drive rotation at the given velocity
+        fn cycles_to_rotate(velocity: f64) -> u32 {
+            let (mut robot, arena) = setup_test_robot();
+            robot.speed_turn_coupling = true;
+            robot.drive.velocity = velocity;
+            robot.request_drive_rotation(90.0);
+            let mut cycles = 0;
+            while robot.drive.pending_rotation != 0.0 {
+                robot.process_cycle_updates(&arena);
+                robot.drive.velocity = velocity; // Keep speed constant despite collisions
+                cycles += 1;
+                assert!(cycles < 10_000, "rotation never completed");
+            }
+            cycles
+        }
+
+        let max_velocity = config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR;
+        let stationary = cycles_to_rotate(0.0);
+        let moving = cycles_to_rotate(max_velocity);
+        assert!(moving > stationary);
+    }
+
+    #[test]
+    fn test_forward_clear_distance_sees_robots() {
+        let (mut robot, arena) = setup_test_robot();
+        robot.drive.direction = 0.0;
+        let blocker = (
+            2,
+            Point {
+                x: robot.position.x + 0.2,
+                y: robot.position.y,
+            },
+            config::DEFAULT_ROBOT_RADIUS,
+        );
+
+        robot.update_vm_state_registers(&arena, 0.0);
+        robot.update_forward_clear_distance(
+            &arena,
+            &[(robot.id, robot.position, robot.radius), blocker],
+        );
+
+        let registers = &robot.vm_state.registers;
+        let forward = registers.get(Register::ForwardDistance).unwrap();
+        let clear = registers.get(Register::ForwardClearDistance).unwrap();
+        let expected = 0.2 - robot.radius - config::DEFAULT_ROBOT_RADIUS;
+        assert!(clear < forward);
+        assert!((clear - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scanner_directive_limits_scans() {
+        let arena = Arena::new();
+        let mut robot = Robot::new(
+            1,
+            "R".to_string(),
+            Point { x: 0.2, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        // A target straight ahead, 0.5 away
+        let mut get_robot_info = |_: u32| Some((Point { x: 0.7, y: 0.5 }, RobotStatus::Active));
+
+        robot.load_program(parse_program(".scanner range 0.4\nnop"));
+        assert_eq!(robot.turret.scanner.range, 0.4);
+        let (_, _, target) = robot.scan_for_targets_by_id(&mut get_robot_info, &[2], &arena);
+        assert_eq!(target, None, "Target is out of range");
+
+        robot.load_program(parse_program(".scanner range 0.6\nnop"));
+        let (_, _, target) = robot.scan_for_targets_by_id(&mut get_robot_info, &[2], &arena);
+        assert_eq!(target, Some(2));
+
+        // A wide cone can't also reach far: range is cut back to the coverage budget
+        robot.load_program(parse_program(".scanner fov 90 range 1.4\nnop"));
+        assert_eq!(robot.turret.scanner.fov, 90.0);
+        assert!((robot.turret.scanner.range - config::SCANNER_COVERAGE / 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shape_footprints() {
+        let shaped = |shape: RobotShape| {
+            let mut robot = Robot::new(
+                1,
+                "R".to_string(),
+                Point { x: 0.5, y: 0.5 },
+                Point { x: 0.5, y: 0.5 },
+            );
+            robot.set_shape(shape);
+            robot
+        };
+        let circle = shaped(RobotShape::Circle);
+        let square = shaped(RobotShape::Square);
+        let triangle = shaped(RobotShape::Triangle);
+        assert!(square.radius > circle.radius);
+        assert_eq!(triangle.radius, config::DEFAULT_ROBOT_RADIUS);
+        assert_eq!(circle.radius, config::DEFAULT_ROBOT_RADIUS);
+
+        // Each shape is drawn with its own body routine
+        assert_eq!(triangle.shape.body_sides(), Some(3));
+        assert_eq!(square.shape.body_sides(), Some(4));
+        assert_eq!(circle.shape.body_sides(), None);
+
+        // The shape comes from the program
+        let mut robot = shaped(RobotShape::Triangle);
+        robot.load_program(parse_assembly(".shape square\nnop", None).unwrap());
+        assert_eq!(robot.shape, RobotShape::Square);
+        assert_eq!(robot.radius, square.radius);
+    }
+
+    #[test]
+    fn test_closest_ally_registers() {
+        let (mut robot, _) = setup_test_robot();
+        robot.team = 1;
+        let ally = Point {
+            x: robot.position.x,
+            y: robot.position.y + 0.3,
+        };
+        let far_ally = Point {
+            x: robot.position.x - 0.5,
+            y: robot.position.y,
+        };
+        let enemy = Point {
+            x: robot.position.x + 0.1,
+            y: robot.position.y,
+        };
+
+        robot.update_closest_ally(&[
+            (robot.id, 1, robot.position),
+            (2, 1, ally),
+            (3, 1, far_ally),
+            (4, 2, enemy),
+        ]);
+        let registers = &robot.vm_state.registers;
+        let distance = registers.get(Register::ClosestAllyDistance).unwrap();
+        let direction = registers.get(Register::ClosestAllyDirection).unwrap();
+        assert!((distance - 0.3).abs() < 1e-9);
+        assert!((direction - 90.0).abs() < 1e-9);
+
+        // Only enemies left alive
+        robot.update_closest_ally(&[(robot.id, 1, robot.position), (4, 2, enemy)]);
+        let registers = &robot.vm_state.registers;
+        assert_eq!(registers.get(Register::ClosestAllyDistance).unwrap(), 0.0);
+        assert_eq!(registers.get(Register::ClosestAllyDirection).unwrap(), 0.0);
+
+        // Robots without a team have no allies
+        robot.team = 0;
+        robot.update_closest_ally(&[(robot.id, 0, robot.position), (2, 0, ally)]);
+        assert_eq!(
+            robot
+                .vm_state
+                .registers
+                .get(Register::ClosestAllyDistance)
+                .unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_score_register() {
+        let (mut robot, arena) = setup_test_robot();
+        robot.health = 80.0;
+        robot.damage_dealt = 30.0;
+        robot.kills = 2;
+        robot.alive_cycles = 500;
+
+        robot.update_vm_state_registers(&arena, 0.0);
+
+        let expected = 80.0 * config::SCORE_WEIGHT_HEALTH
+            + 30.0 * config::SCORE_WEIGHT_DAMAGE
+            + 2.0 * config::SCORE_WEIGHT_KILLS
+            + 500.0 * config::SCORE_WEIGHT_ALIVE;
+        let score = robot.vm_state.registers.get(Register::Score).unwrap();
+        assert!((score - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_coin_register_is_seeded_per_robot() {
+        let arena = Arena::new();
+        let program = "nop";
+        let mut differed = false;
+        for seed in 0..32 {
+            let robots: Vec<Robot> = (1..=2)
+                .map(|id| {
+                    let mut robot = Robot::new(
+                        id,
+                        "Mirror".to_string(),
+                        Point { x: 0.5, y: 0.5 },
+                        Point { x: 0.5, y: 0.5 },
+                    );
+                    robot.load_program(parse_program(program));
+                    robot.coin = coin_flip(seed, id);
+                    robot.update_vm_state_registers(&arena, 0.0);
+                    robot
+                })
+                .collect();
+
+            let coins: Vec<f64> = robots
+                .iter()
+                .map(|r| r.vm_state.registers.get(Register::Coin).unwrap())
+                .collect();
+            for (robot, coin) in robots.iter().zip(&coins) {
+                assert!(*coin == 0.0 || *coin == 1.0);
+                // Deterministic for the same seed and id
+                assert_eq!(*coin, coin_flip(seed, robot.id));
+            }
+            differed |= coins[0] != coins[1];
+        }
+        assert!(
+            differed,
+            "Identical programs should sometimes get different coins"
+        );
+    }
+
+    #[test]
+    fn test_execution_paths_agree_on_representative_program() {
+        // Loops, calls, stack, memory and operand-form arithmetic, run through both
+        // VM entry points for many cycles; both must land in the same state.
+        let source = r#"
+            mov @d0 0
+            mov @c 10
+            mov @index 0
+        accumulate:
+            add @d0 @c
+            mov @d0 @result
+            sto @c
+            call square
+            loop accumulate
+            jmp done
+        square:
+            push @c
+            dup
+            mul
+            pop @d1
+            ret
+        done:
+            nop
+        "#;
+        let arena = Arena::new();
+        let center = Point { x: 0.5, y: 0.5 };
+        let mut direct = Robot::new(1, "direct".to_string(), Point { x: 0.2, y: 0.2 }, center);
+        direct.load_program(parse_program(source));
+        let mut provided = direct.clone();
+        let mut command_queue = VecDeque::new();
+
+        for _ in 0..1000 {
+            direct.execute_vm_cycle(&[], &arena, &mut command_queue, None);
+            provided.execute_vm_cycle_with_provider(
+                Vec::new,
+                &mut |_| None,
+                &arena,
+                &mut command_queue,
+                None,
+            );
+        }
+
+        for robot in [&direct, &provided] {
+            let registers = &robot.vm_state.registers;
+            assert_eq!(robot.vm_state.fault, None);
+            assert_eq!(registers.get(Register::D0).unwrap(), 55.0);
+            assert_eq!(registers.get(Register::D1).unwrap(), 1.0);
+            assert_eq!(registers.get(Register::Index).unwrap(), 10.0);
+            assert_eq!(robot.vm_state.memory[0], 10.0);
+            assert_eq!(robot.vm_state.memory[9], 1.0);
+        }
+        assert_eq!(direct.vm_state.ip, provided.vm_state.ip);
+        assert_eq!(direct.vm_state.memory, provided.vm_state.memory);
+        assert!(command_queue.is_empty());
+    }
+
+    #[test]
+    fn test_last_instruction_cost_register() {
+        let arena = Arena::new();
+        let mut robot = Robot::new(
+            0,
+            "TestRobot".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        robot.load_program(parse_program("pow @d0 2\nselect 1\nrotate 90"));
+        let mut command_queue = VecDeque::new();
+        let last_cost = |robot: &Robot| {
+            robot
+                .vm_state
+                .registers
+                .get(Register::LastInstructionCost)
+                .unwrap()
+        };
+
+        simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+        assert_eq!(last_cost(&robot), 2.0);
+        simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+        assert_eq!(last_cost(&robot), 1.0);
+        // rotate costs 1 plus 1 per started 45 degrees
+        simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+        assert_eq!(last_cost(&robot), 3.0);
+    }
+
+    #[test]
+    fn test_stun_pauses_vm() {
+        let arena = Arena::new();
+        let mut robot = Robot::new(
+            0,
+            "TestRobot".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        robot.load_program(parse_program("nop\nnop\nnop\nnop\nnop\nnop"));
+        let mut command_queue = VecDeque::new();
+
+        simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+        let ip = robot.vm_state.ip;
+        assert!(ip > 0);
+
+        robot.apply_stun(3);
+        robot.apply_stun(1); // A shorter stun doesn't cut it short
+        for _ in 0..3 {
+            assert!(matches!(robot.status, RobotStatus::Stunned(_)));
+            simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+            assert_eq!(robot.vm_state.ip, ip);
+        }
+        assert_eq!(robot.status, RobotStatus::Active);
+
+        simulate_cycle(&mut robot, &[], &arena, &mut command_queue);
+        assert!(robot.vm_state.ip > ip);
+
+        robot.status = RobotStatus::Destroyed;
+        robot.apply_stun(5);
+        assert_eq!(robot.status, RobotStatus::Destroyed);
+    }
+
+    #[test]
+    fn test_weapon_cooldown() {
+        let arena = Arena::new();
+        let mut robot = Robot::new(
+            0,
+            "TestRobot".to_string(),
+            Point { x: 0.5, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+
+        assert!(robot.fire_weapon(0.1).is_some());
+        // The second shot is rejected without spending power
+        let power = robot.power;
+        assert!(robot.fire_weapon(0.1).is_none());
+        assert_eq!(robot.power, power);
+        assert_eq!(robot.shots_fired, 1);
+
+        robot.update_vm_state_registers(&arena, 0.0);
+        let cooldown = |robot: &Robot| {
+            robot
+                .vm_state
+                .registers
+                .get(Register::WeaponCooldown)
+                .unwrap()
+        };
+        assert_eq!(cooldown(&robot), config::WEAPON_COOLDOWN_CYCLES as f64);
+
+        for _ in 0..config::WEAPON_COOLDOWN_CYCLES {
+            assert!(robot.fire_weapon(0.1).is_none());
+            robot.process_cycle_updates(&arena);
+        }
+        robot.update_vm_state_registers(&arena, 0.0);
+        assert_eq!(cooldown(&robot), 0.0);
+        assert!(robot.fire_weapon(0.1).is_some());
+    }
+
     #[test]
     fn test_fire_weapon() {
         let arena = Arena::new();
@@ -1407,6 +2567,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_explicit_initial_heading() {
+        let robot = Robot::with_heading(1, "Test".to_string(), Point { x: 0.1, y: 0.1 }, 135.0);
+        assert_eq!(robot.drive.direction, 135.0);
+        assert_eq!(robot.turret.direction, 135.0);
+        assert_eq!(robot.prev_drive_direction, 135.0);
+        assert_eq!(robot.prev_turret_direction, 135.0);
+
+        // Headings are normalized into 0-360
+        let robot = Robot::with_heading(2, "Test".to_string(), Point { x: 0.1, y: 0.1 }, -90.0);
+        assert_eq!(robot.drive.direction, 270.0);
+        assert_eq!(robot.prev_turret_direction, 270.0);
+    }
+
     // Added back the missing helper function
     fn setup_test_robot() -> (Robot, Arena) {
         let mut robot = Robot::new(
@@ -1421,8 +2595,59 @@ mod tests {
         // Creating a dummy ParsedProgram for now
         let dummy_program = crate::vm::parser::ParsedProgram {
             instructions: vec![Instruction::Mov(Register::D0, Operand::Value(10.0))],
+            data: Vec::new(),
+            shape: Default::default(),
+            scanner: Default::default(),
+            references: Default::default(),
         };
         robot.load_program(dummy_program);
         (robot, arena)
     }
+
+    #[test]
+    fn test_cached_collision_distances_match_raycast() {
+        let mut arena = Arena::new();
+        arena.obstacles.clear();
+        arena.add_obstacle_at_robot(&Robot::new(
+            9,
+            "Wall".to_string(),
+            Point { x: 0.8, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        ));
+        let mut robot = Robot::new(
+            1,
+            "Cached".to_string(),
+            Point { x: 0.3, y: 0.5 },
+            Point { x: 0.5, y: 0.5 },
+        );
+        let raycast = |robot: &Robot, arena: &Arena| {
+            (
+                arena.distance_to_collision(robot.position, robot.drive.direction),
+                arena.distance_to_collision(
+                    robot.position,
+                    (robot.drive.direction + 180.0).rem_euclid(360.0),
+                ),
+            )
+        };
+
+        // Standing still, moving and turning all agree with a fresh raycast
+        for step in 0..20 {
+            if step % 3 == 1 {
+                robot.position.x += 0.01;
+            } else if step % 3 == 2 {
+                robot.drive.direction = (robot.drive.direction + 15.0).rem_euclid(360.0);
+            }
+            assert_eq!(robot.collision_distances(&arena), raycast(&robot, &arena));
+        }
+
+        // A layout change is picked up even though the robot hasn't moved
+        robot.drive.direction = 0.0;
+        robot.position = Point { x: 0.3, y: 0.5 };
+        let before = robot.collision_distances(&arena);
+        arena.obstacles.clear();
+        arena.invalidate_distance_cache();
+        let after = robot.collision_distances(&arena);
+        assert_eq!(after, raycast(&robot, &arena));
+        assert!(after.0 > before.0);
+    }
 }
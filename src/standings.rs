@@ -0,0 +1,196 @@
+// Tournament standings: tallies match results into per-robot wins, draws,
+// losses and points, with a configurable policy for how draws are scored
+
+use crate::game::MatchResult;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+// How a drawn match (no winner) counts toward the standings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawScoring {
+    HalfWin,  // Every participant gets half a win's points
+    Loss,     // Every participant is charged a loss
+    NoPoints, // Recorded as a draw, worth nothing
+}
+
+impl FromStr for DrawScoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "win0.5" => Ok(DrawScoring::HalfWin),
+            "loss" => Ok(DrawScoring::Loss),
+            "nopoints" => Ok(DrawScoring::NoPoints),
+            _ => Err(format!(
+                "unknown draw scoring '{}' (expected win0.5, loss or nopoints)",
+                s
+            )),
+        }
+    }
+}
+
+// One entrant's row in the standings table. Entrants are identified by program
+// file rather than slot, since the same program can land in a different slot (and
+// id) from match to match; a second copy of a program in one match (a mirror
+// match) is a separate entrant, `<file> #2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub entrant: String,
+    pub name: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub points: f64,
+}
+
+const WIN_POINTS: f64 = 1.0;
+
+/// Tallies match results into standings, sorted by points (highest first, ties by entrant)
+pub fn tally(results: &[MatchResult], draw_scoring: DrawScoring) -> Vec<Standing> {
+    let mut table: BTreeMap<String, Standing> = BTreeMap::new();
+    for result in results {
+        for (index, robot) in result.robots.iter().enumerate() {
+            let copy = 1 + result.robots[..index]
+                .iter()
+                .filter(|r| r.source == robot.source)
+                .count();
+            let entrant = match copy {
+                1 => robot.source.clone(),
+                _ => format!("{} #{}", robot.source, copy),
+            };
+            let row = table.entry(entrant.clone()).or_insert_with(|| Standing {
+                entrant,
+                name: robot.name.clone(),
+                wins: 0,
+                draws: 0,
+                losses: 0,
+                points: 0.0,
+            });
+            // Every robot on a winning team shares the win
+            let won = result.winner == Some(robot.id)
+                || (robot.team != 0 && result.winning_team == Some(robot.team));
+            let decided = result.winner.is_some() || result.winning_team.is_some();
+            match (won, decided) {
+                (true, _) => {
+                    row.wins += 1;
+                    row.points += WIN_POINTS;
+                }
+                (false, true) => row.losses += 1,
+                (false, false) => match draw_scoring {
+                    DrawScoring::HalfWin => {
+                        row.draws += 1;
+                        row.points += WIN_POINTS / 2.0;
+                    }
+                    DrawScoring::Loss => row.losses += 1,
+                    DrawScoring::NoPoints => row.draws += 1,
+                },
+            }
+        }
+    }
+
+    let mut standings: Vec<Standing> = table.into_values().collect();
+    // Stable sort keeps the entrant order from the BTreeMap for equal points
+    standings.sort_by(|a, b| b.points.total_cmp(&a.points));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::RobotResult;
+
+    fn match_between(ids: &[u32], winner: Option<u32>) -> MatchResult {
+        let names: Vec<String> = ids.iter().map(|id| format!("bot{}", id)).collect();
+        match_named(ids, &names, winner)
+    }
+
+    fn match_named(ids: &[u32], names: &[String], winner: Option<u32>) -> MatchResult {
+        MatchResult {
+            winner,
+            winning_team: None,
+            total_cycles: 100,
+            robots: ids
+                .iter()
+                .zip(names)
+                .map(|(&id, name)| RobotResult {
+                    id,
+                    name: name.clone(),
+                    source: format!("robots/{}.rasm", name),
+                    team: 0,
+                    alive_cycles: 100,
+                    destroyed: winner.is_some_and(|w| w != id),
+                    health: 0.0,
+                    shots_fired: 0,
+                    damage_dealt: 0.0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_draw_scoring_policies_change_standings() {
+        let results = vec![
+            match_between(&[1, 2], Some(1)),
+            match_between(&[2, 3], None),
+            match_between(&[2, 3], None),
+            match_between(&[2, 3], None),
+        ];
+
+        let half_win = tally(&results, DrawScoring::HalfWin);
+        assert_eq!(half_win[0].name, "bot2");
+        assert_eq!(half_win[0].points, 1.5);
+        assert_eq!(half_win[0].draws, 3);
+
+        let loss = tally(&results, DrawScoring::Loss);
+        assert_eq!(loss[0].name, "bot1");
+        assert_eq!(loss[0].points, 1.0);
+        let robot_2 = loss.iter().find(|s| s.name == "bot2").unwrap();
+        assert_eq!((robot_2.draws, robot_2.losses, robot_2.points), (0, 4, 0.0));
+
+        let no_points = tally(&results, DrawScoring::NoPoints);
+        let robot_3 = no_points.iter().find(|s| s.name == "bot3").unwrap();
+        assert_eq!((robot_3.draws, robot_3.losses, robot_3.points), (3, 0, 0.0));
+    }
+
+    #[test]
+    fn test_standings_follow_robots_across_slots() {
+        let names = ["sniper".to_string(), "rammer".to_string()];
+        let swapped = [names[1].clone(), names[0].clone()];
+        // The sniper wins from slot 1, then again from slot 2
+        let results = vec![
+            match_named(&[1, 2], &names, Some(1)),
+            match_named(&[1, 2], &swapped, Some(2)),
+        ];
+
+        let standings = tally(&results, DrawScoring::HalfWin);
+        assert_eq!(standings.len(), 2);
+        assert_eq!(standings[0].name, "sniper");
+        assert_eq!((standings[0].wins, standings[0].losses), (2, 0));
+        assert_eq!(standings[1].name, "rammer");
+        assert_eq!((standings[1].wins, standings[1].losses), (0, 2));
+    }
+
+    #[test]
+    fn test_mirror_match_keeps_both_copies_apart() {
+        let twins = ["twin".to_string(), "twin".to_string()];
+        let results = vec![
+            match_named(&[1, 2], &twins, Some(1)),
+            match_named(&[1, 2], &twins, Some(1)),
+        ];
+
+        let standings = tally(&results, DrawScoring::HalfWin);
+        assert_eq!(standings.len(), 2);
+        assert_eq!(standings[0].entrant, "robots/twin.rasm");
+        assert_eq!((standings[0].wins, standings[0].losses), (2, 0));
+        assert_eq!(standings[1].entrant, "robots/twin.rasm #2");
+        assert_eq!((standings[1].wins, standings[1].losses), (0, 2));
+    }
+
+    #[test]
+    fn test_parse_draw_scoring() {
+        assert_eq!("win0.5".parse(), Ok(DrawScoring::HalfWin));
+        assert_eq!("LOSS".parse(), Ok(DrawScoring::Loss));
+        assert_eq!("nopoints".parse(), Ok(DrawScoring::NoPoints));
+        assert!("half".parse::<DrawScoring>().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+// Training hook: a gym-style reset/step API over the headless simulation for
+// reinforcement-learning experiments. One robot (the agent) is controlled by
+// actions instead of its program; the other robots run their programs as usual.
+
+use crate::audio::AudioManager;
+use crate::config;
+use crate::game::{Game, InitialHeading};
+use crate::robot::Robot;
+use crate::vm::executor::{Instruction, Operand};
+use crate::vm::registers::Register;
+use rand::Rng;
+use std::error::Error;
+use std::sync::Arc;
+
+/// Number of values in an observation
+pub const OBSERVATION_LEN: usize = 9;
+
+/// The agent's sensor readings, in this order: health, power, x, y, drive
+/// direction, turret direction, forward clear distance, target distance and
+/// target direction (the values of the matching VM registers). All zeros once
+/// the agent has been destroyed.
+pub type Observation = [f64; OBSERVATION_LEN];
+
+const OBSERVED_REGISTERS: [Register; OBSERVATION_LEN] = [
+    Register::Health,
+    Register::Power,
+    Register::PosX,
+    Register::PosY,
+    Register::DriveDirection,
+    Register::TurretDirection,
+    Register::ForwardClearDistance,
+    Register::TargetDistance,
+    Register::TargetDirection,
+];
+
+// Reward for winning the match; being destroyed costs the same amount
+const WIN_REWARD: f64 = 100.0;
+
+/// Controls applied to the agent for one step. Each field maps to the VM
+/// instruction of the same name, so costs, cooldowns and power use match a
+/// robot program issuing them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Action {
+    pub drive: f64,         // Drive velocity in grid units per turn
+    pub rotate: f64,        // Drive rotation in degrees (0 = keep heading)
+    pub rotate_turret: f64, // Turret rotation in degrees (0 = keep aim)
+    pub fire: f64,          // Fire power 0.0-1.0 (0 = hold fire)
+    pub scan: bool,         // Scan for targets after aiming
+}
+
+impl Action {
+    // The instruction sequence the agent runs for this action
+    fn program(&self) -> Vec<Instruction> {
+        let mut program = vec![
+            Instruction::Select(Operand::Value(1.0)),
+            Instruction::Drive(Operand::Value(self.drive)),
+        ];
+        if self.rotate != 0.0 {
+            program.push(Instruction::Rotate(Operand::Value(self.rotate)));
+        }
+        program.push(Instruction::Select(Operand::Value(2.0)));
+        if self.rotate_turret != 0.0 {
+            program.push(Instruction::Rotate(Operand::Value(self.rotate_turret)));
+        }
+        if self.fire > 0.0 {
+            program.push(Instruction::Fire(Operand::Value(self.fire)));
+        }
+        if self.scan {
+            program.push(Instruction::Scan);
+        }
+        program
+    }
+}
+
+/// A gym-like environment: `reset` starts a new match, `step` applies an
+/// action and advances the simulation by one turn.
+pub struct TrainingEnv {
+    robot_files: Vec<String>,
+    agent_id: u32,
+    max_turns: u32,
+    game: Game,
+    last_health: f64,
+    last_damage_dealt: f64,
+}
+
+impl TrainingEnv {
+    /// Creates an environment for the given robots, with robot `agent_id` (1-based,
+    /// in file order) controlled by actions. The agent's own program is ignored.
+    pub fn new(
+        robot_files: &[String],
+        agent_id: u32,
+        max_turns: u32,
+    ) -> Result<Self, Box<dyn Error>> {
+        if agent_id < 1 || agent_id as usize > robot_files.len() {
+            return Err(format!("agent robot id {} is not in the match", agent_id).into());
+        }
+        let mut env = TrainingEnv {
+            robot_files: robot_files.to_vec(),
+            agent_id,
+            max_turns,
+            game: Self::new_game(robot_files, max_turns)?,
+            last_health: 0.0,
+            last_damage_dealt: 0.0,
+        };
+        env.start()?;
+        Ok(env)
+    }
+
+    fn new_game(robot_files: &[String], max_turns: u32) -> Result<Game, Box<dyn Error>> {
+        Game::new(
+            robot_files,
+            max_turns,
+            &InitialHeading::Center,
+            &[],
+            None,
+            AudioManager::new(),
+        )
+    }
+
+    /// Starts a new match and returns the agent's first observation
+    pub fn reset(&mut self) -> Result<Observation, Box<dyn Error>> {
+        self.game = Self::new_game(&self.robot_files, self.max_turns)?;
+        self.start()
+    }
+
+    // Hands the agent over to actions at the start of a match
+    fn start(&mut self) -> Result<Observation, Box<dyn Error>> {
+        let random_value = self.game.rng.r#gen::<f64>();
+        let arena = &self.game.arena;
+        let agent_id = self.agent_id;
+        let agent = self
+            .game
+            .robots
+            .iter_mut()
+            .find(|r| r.id == agent_id)
+            .ok_or("agent robot missing from the match")?;
+        agent.program = Arc::from([]); // Idle until the first step
+        agent.update_vm_state_registers(arena, random_value);
+        self.last_health = agent.health;
+        self.last_damage_dealt = agent.damage_dealt;
+        Ok(self.observe())
+    }
+
+    /// Applies `action` to the agent and runs one turn. Returns the new
+    /// observation, the reward (damage dealt minus damage taken, plus
+    /// `WIN_REWARD` for winning or minus it for being destroyed) and whether
+    /// the match is over.
+    pub fn step(&mut self, action: Action) -> (Observation, f64, bool) {
+        if let Some(agent) = self.agent_mut() {
+            agent.program = action.program().into();
+            agent.vm_state.ip = 0;
+            agent.vm_state.fault = None;
+            agent.vm_state.instruction_cycles_remaining = 0;
+        }
+
+        for _ in 0..config::CYCLES_PER_TURN {
+            if self.is_done() {
+                break;
+            }
+            self.game.update_simulation();
+        }
+
+        let (health, damage_dealt) = self.agent().map_or((0.0, self.last_damage_dealt), |r| {
+            (r.health, r.damage_dealt)
+        });
+        let mut reward = (damage_dealt - self.last_damage_dealt) - (self.last_health - health);
+        self.last_health = health;
+        self.last_damage_dealt = damage_dealt;
+
+        let done = self.is_done();
+        if done {
+            if self.game.match_result().winner == Some(self.agent_id) {
+                reward += WIN_REWARD;
+            } else if self.agent().is_none() {
+                reward -= WIN_REWARD;
+            }
+        }
+        (self.observe(), reward, done)
+    }
+
+    fn is_done(&self) -> bool {
+        self.game.is_game_over()
+            || self.game.current_turn > self.max_turns
+            || self.agent().is_none()
+    }
+
+    fn agent(&self) -> Option<&Robot> {
+        self.game.robots.iter().find(|r| r.id == self.agent_id)
+    }
+
+    fn agent_mut(&mut self) -> Option<&mut Robot> {
+        self.game.robots.iter_mut().find(|r| r.id == self.agent_id)
+    }
+
+    fn observe(&self) -> Observation {
+        let mut observation = [0.0; OBSERVATION_LEN];
+        if let Some(agent) = self.agent() {
+            for (value, register) in observation.iter_mut().zip(OBSERVED_REGISTERS) {
+                *value = agent.vm_state.registers.get(register).unwrap_or(0.0);
+            }
+        }
+        observation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bot(name: &str) -> String {
+        format!("{}/bots/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn test_reset_and_step() {
+        let files = [bot("square.rasm"), bot("square.rasm")];
+        let mut env = TrainingEnv::new(&files, 1, 10).unwrap();
+
+        let observation = env.reset().unwrap();
+        assert_eq!(observation.len(), OBSERVATION_LEN);
+        assert_eq!(observation[0], config::DEFAULT_INITIAL_HEALTH);
+
+        let (observation, reward, done) = env.step(Action {
+            drive: 1.0,
+            rotate_turret: 45.0,
+            scan: true,
+            ..Action::default()
+        });
+        assert_eq!(observation.len(), OBSERVATION_LEN);
+        assert!(reward.is_finite());
+        assert!(!done);
+        assert_eq!(env.game.total_cycles, config::CYCLES_PER_TURN);
+    }
+
+    #[test]
+    fn test_rejects_unknown_agent() {
+        assert!(TrainingEnv::new(&[bot("square.rasm")], 2, 10).is_err());
+    }
+}
@@ -1,7 +1,9 @@
 use crate::config;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 // Common point type used throughout the game
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -16,12 +18,92 @@ impl Point {
     }
 }
 
+/// Body shape of a robot, chosen with the `.shape` program directive. It
+/// decides how the body is drawn and how large its collision footprint is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RobotShape {
+    #[default]
+    Triangle,
+    Square,
+    Circle,
+}
+
+impl RobotShape {
+    /// Collision radius of a body drawn at the given size. Collisions stay
+    /// circular: a square's corners reach past its inscribed circle, so it
+    /// collides at the mean of its inscribed and circumscribed radii.
+    pub fn footprint_radius(self, size: f64) -> f64 {
+        match self {
+            RobotShape::Triangle | RobotShape::Circle => size,
+            RobotShape::Square => size * (1.0 + std::f64::consts::SQRT_2) / 2.0,
+        }
+    }
+
+    /// Number of polygon sides the body is drawn with (None = a circle)
+    pub fn body_sides(self) -> Option<u8> {
+        match self {
+            RobotShape::Triangle => Some(3),
+            RobotShape::Square => Some(4),
+            RobotShape::Circle => None,
+        }
+    }
+}
+
+impl FromStr for RobotShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "triangle" => Ok(RobotShape::Triangle),
+            "square" => Ok(RobotShape::Square),
+            "circle" => Ok(RobotShape::Circle),
+            _ => Err(format!(
+                "Unknown shape '{}' (expected triangle, square or circle)",
+                s
+            )),
+        }
+    }
+}
+
+/// How a projectile's damage interacts with armor: armor takes a flat amount
+/// off every kinetic hit, while energy hits ignore it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Kinetic,
+    #[allow(dead_code)] // No weapon fires energy shots by default
+    Energy,
+}
+
+impl DamageType {
+    /// Damage that gets through `armor` from a hit of `damage`
+    pub fn against(self, damage: f64, armor: f64) -> f64 {
+        match self {
+            DamageType::Kinetic => (damage - armor).max(0.0),
+            DamageType::Energy => damage,
+        }
+    }
+}
+
+/// Scanner range and field of view asked for by a program's `.scanner` directive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScannerSettings {
+    pub range: f64, // Coordinate units
+    pub fov: f64,   // Degrees
+}
+
+impl Default for ScannerSettings {
+    fn default() -> Self {
+        ScannerSettings {
+            range: config::DEFAULT_SCANNER_RANGE,
+            fov: config::DEFAULT_SCANNER_FOV,
+        }
+    }
+}
+
 // Scanner component properties
 #[derive(Debug, Clone, Copy)]
 pub struct Scanner {
-    #[allow(dead_code)]
-    pub fov: f64, // Field of view in degrees
-    #[allow(dead_code)]
+    pub fov: f64,   // Field of view in degrees
     pub range: f64, // Maximum scan range in coordinate units
     #[allow(dead_code)]
     pub last_scan_distance: f64, // Last detected target distance (0.0 if none)
@@ -40,37 +122,131 @@ impl Default for Scanner {
     }
 }
 
+impl Scanner {
+    /// A scanner built to a program's settings, held to the engine limits: fov and
+    /// range are clamped to their bounds, then range is cut back so that fov x range
+    /// stays within SCANNER_COVERAGE.
+    pub fn with_settings(settings: ScannerSettings) -> Self {
+        let fov = settings
+            .fov
+            .clamp(config::SCANNER_MIN_FOV, config::SCANNER_MAX_FOV);
+        let range = settings
+            .range
+            .clamp(config::SCANNER_MIN_RANGE, config::SCANNER_MAX_RANGE)
+            .min(config::SCANNER_COVERAGE / fov);
+        Scanner {
+            fov,
+            range,
+            ..Scanner::default()
+        }
+    }
+}
+
+/// What a robot's `fire` instruction shoots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponKind {
+    Projectile, // Spawns a shot that travels across the arena
+    Laser,      // Fires a beam that hits the first robot in line this cycle
+}
+
 // Ranged weapon properties
 #[derive(Debug, Clone, Copy)]
 pub struct RangedWeapon {
-    pub base_damage: f64,      // Base damage before scaling
-    pub projectile_speed: f64, // Base projectile speed in units/cycle
+    pub kind: WeaponKind,        // What `fire` shoots
+    pub base_damage: f64,        // Base damage before scaling
+    pub projectile_speed: f64,   // Base projectile speed in units/cycle
+    pub cooldown_remaining: u32, // Cycles until the weapon can fire again (0 = ready)
+    pub damage_type: DamageType, // How the weapon's shots interact with armor
 }
 
 impl Default for RangedWeapon {
     fn default() -> Self {
         RangedWeapon {
+            kind: WeaponKind::Projectile,
             base_damage: config::DEFAULT_RANGED_DAMAGE,
             projectile_speed: config::DEFAULT_PROJECTILE_SPEED,
+            cooldown_remaining: 0,
+            damage_type: config::DEFAULT_DAMAGE_TYPE,
         }
     }
 }
 
 // Projectile state (for tracking fired projectiles)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Projectile {
     pub position: Point,
     pub prev_position: Point,
-    pub direction: f64,    // Absolute angle in degrees
-    pub speed: f64,        // Current speed in units/cycle
-    pub power: f64,        // Power level used to fire (affects damage)
-    pub base_damage: f64,  // Base damage of the projectile
-    pub source_robot: u32, // ID of robot that fired this projectile
+    pub direction: f64,               // Absolute angle in degrees
+    pub speed: f64,                   // Current speed in units/cycle
+    pub power: f64,                   // Power level used to fire (affects damage)
+    pub base_damage: f64,             // Base damage of the projectile
+    pub damage_type: DamageType,      // How the damage interacts with armor
+    pub source_robot: u32,            // ID of robot that fired this projectile
+    pub source_team: u8,              // Shooter's team when fired; spared even after it dies
+    pub ballistic: Option<Ballistic>, // Arc state for mortar shells (None = flat shot)
+    pub bounces_remaining: u32,       // Wall bounces left before the shot ends at a wall
+}
+
+/// What a pickup restores when a robot collects it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupKind {
+    Health, // PICKUP_HEALTH health, up to the starting health
+    Energy, // PICKUP_ENERGY power, up to full power
+}
+
+// A collectible lying in the arena
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pickup {
+    pub position: Point, // Center of the grid cell it was dropped in
+    pub kind: PickupKind,
+}
+
+// Vertical flight of a mortar shell, in arena units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ballistic {
+    pub height: f64,            // Height above the ground
+    pub vertical_velocity: f64, // Change in height per cycle (gravity pulls it down)
+}
+
+// A proximity mine lying in the arena
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Mine {
+    pub position: Point, // Where the owner dropped it
+    pub power: f64,      // Power level used to lay it (scales the blast)
+    pub owner: u32,      // ID of the robot that laid it (never sets it off)
+    pub team: u8,        // Owner's team: teammates neither set it off nor take its blast
 }
 
 /// Commands generated by robots to be executed by the Arena
-#[derive(Debug, Clone)] // Clone needed for queue processing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] // Clone needed for queue processing
 pub enum ArenaCommand {
     SpawnProjectile(Projectile),
-    SpawnMuzzleFlash { position: Point, direction: f64 },
+    SpawnMine(Mine),
+    SpawnMuzzleFlash {
+        position: Point,
+        direction: f64,
+    },
+    Detonate {
+        source_robot: u32,
+        source_team: u8,
+        position: Point,
+        power: f64,
+    },
+    FireBeam {
+        source_robot: u32,
+        source_team: u8,
+        start: Point,
+        direction: f64,
+        power: f64,
+        falloff: bool, // Laser weapon shots lose damage with distance; `beam` doesn't
+    },
+}
+
+// A beam shot drawn for the cycle it was fired in
+#[derive(Debug, Clone, Copy)]
+pub struct Beam {
+    pub start: Point,
+    pub end: Point, // Where the beam hit a robot, wall or obstacle
+    pub source_robot: u32,
+    pub power: f64,
 }
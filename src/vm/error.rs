@@ -43,4 +43,8 @@ pub enum VMFault {
     CallStackOverflow,
     #[error("Call stack underflow")]
     CallStackUnderflow,
+    #[error("Numeric value out of range for operation")]
+    NumericError,
+    #[error("Operand values invalid for operation")]
+    InvalidOperand,
 }
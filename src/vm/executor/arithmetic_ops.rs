@@ -28,18 +28,32 @@ impl InstructionProcessor for ArithmeticOperations {
             Instruction::Div |
             Instruction::Mod |
             Instruction::Divmod |
+            Instruction::Min |
+            Instruction::Max |
             Instruction::Pow |
             Instruction::Sqrt |
             Instruction::Log |
+            Instruction::Neg |
+            Instruction::Floor |
+            Instruction::Ceil |
+            Instruction::Round |
             // Register-based operations
             Instruction::AddOp(_, _) |
             Instruction::SubOp(_, _) |
             Instruction::MulOp(_, _) |
             Instruction::DivOp(_, _) |
             Instruction::ModOp(_, _) |
+            Instruction::MinOp(_, _) |
+            Instruction::MaxOp(_, _) |
+            Instruction::ClampOp(_, _, _) |
+            Instruction::RandNormal(_, _) |
             Instruction::PowOp(_, _) |
             Instruction::SqrtOp(_) |
-            Instruction::LogOp(_)
+            Instruction::LogOp(_) |
+            Instruction::NegOp(_) |
+            Instruction::FloorOp(_) |
+            Instruction::CeilOp(_) |
+            Instruction::RoundOp(_)
         )
     }
 
@@ -144,6 +158,29 @@ impl InstructionProcessor for ArithmeticOperations {
                     .push(a % b)
                     .map_err(|_| VMFault::StackOverflow)
             }
+            // min/max follow f64::min/max: a NaN operand yields the other operand
+            Instruction::Min | Instruction::Max => {
+                let b = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                let a = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                let result_val = if matches!(instruction, Instruction::Min) {
+                    a.min(b)
+                } else {
+                    a.max(b)
+                };
+                robot
+                    .vm_state
+                    .stack
+                    .push(result_val)
+                    .map_err(|_| VMFault::StackOverflow)
+            }
             Instruction::Divmod => {
                 let b = robot
                     .vm_state
@@ -217,6 +254,54 @@ impl InstructionProcessor for ArithmeticOperations {
                     .push(val.ln())
                     .map_err(|_| VMFault::StackOverflow)
             }
+            Instruction::Neg => {
+                let val = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                robot
+                    .vm_state
+                    .stack
+                    .push(-val)
+                    .map_err(|_| VMFault::StackOverflow)
+            }
+            Instruction::Floor => {
+                let val = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                robot
+                    .vm_state
+                    .stack
+                    .push(val.floor())
+                    .map_err(|_| VMFault::StackOverflow)
+            }
+            Instruction::Ceil => {
+                let val = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                robot
+                    .vm_state
+                    .stack
+                    .push(val.ceil())
+                    .map_err(|_| VMFault::StackOverflow)
+            }
+            Instruction::Round => {
+                let val = robot
+                    .vm_state
+                    .stack
+                    .pop()
+                    .map_err(|_| VMFault::StackUnderflow)?;
+                robot
+                    .vm_state
+                    .stack
+                    .push(val.round())
+                    .map_err(|_| VMFault::StackOverflow)
+            }
 
             // Register-based arithmetic operations
             Instruction::AddOp(left, right) => {
@@ -285,6 +370,47 @@ impl InstructionProcessor for ArithmeticOperations {
                     .set(Register::Result, result_val)
                     .map_err(|_| VMFault::PermissionError)
             }
+            Instruction::MinOp(left, right) | Instruction::MaxOp(left, right) => {
+                let left_val = left.get_value(&robot.vm_state)?;
+                let right_val = right.get_value(&robot.vm_state)?;
+                let result_val = if matches!(instruction, Instruction::MinOp(_, _)) {
+                    left_val.min(right_val)
+                } else {
+                    left_val.max(right_val)
+                };
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, result_val)
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::ClampOp(value, lo, hi) => {
+                let val = value.get_value(&robot.vm_state)?;
+                let lo_val = lo.get_value(&robot.vm_state)?;
+                let hi_val = hi.get_value(&robot.vm_state)?;
+                // Inverted (or NaN) bounds are a program error, not something to guess at
+                if lo_val.is_nan() || hi_val.is_nan() || lo_val > hi_val {
+                    return Err(VMFault::InvalidOperand);
+                }
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, val.clamp(lo_val, hi_val))
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::RandNormal(mean_op, stddev_op) => {
+                let mean = mean_op.get_value(&robot.vm_state)?;
+                let stddev = stddev_op.get_value(&robot.vm_state)?;
+                if !mean.is_finite() || !stddev.is_finite() || stddev < 0.0 {
+                    return Err(VMFault::InvalidOperand);
+                }
+                let z = crate::robot::standard_normal(&mut robot.seeded_rng);
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, mean + stddev * z)
+                    .map_err(|_| VMFault::PermissionError)
+            }
             Instruction::PowOp(base_op, exp_op) => {
                 let base = base_op.get_value(&robot.vm_state)?;
                 let exponent = exp_op.get_value(&robot.vm_state)?;
@@ -313,6 +439,38 @@ impl InstructionProcessor for ArithmeticOperations {
                     .set(Register::Result, result_val)
                     .map_err(|_| VMFault::PermissionError)
             }
+            Instruction::NegOp(op) => {
+                let val = op.get_value(&robot.vm_state)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, -val)
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::FloorOp(op) => {
+                let val = op.get_value(&robot.vm_state)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, val.floor())
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::CeilOp(op) => {
+                let val = op.get_value(&robot.vm_state)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, val.ceil())
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::RoundOp(op) => {
+                let val = op.get_value(&robot.vm_state)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, val.round())
+                    .map_err(|_| VMFault::PermissionError)
+            }
             _ => Err(VMFault::InvalidInstruction),
         }
     }
@@ -359,9 +517,13 @@ mod tests {
         assert!(processor.can_process(&Instruction::Div));
         assert!(processor.can_process(&Instruction::Mod));
         assert!(processor.can_process(&Instruction::Divmod));
+        assert!(processor.can_process(&Instruction::Min));
+        assert!(processor.can_process(&Instruction::Max));
         assert!(processor.can_process(&Instruction::Pow));
         assert!(processor.can_process(&Instruction::Sqrt));
         assert!(processor.can_process(&Instruction::Log));
+        assert!(processor.can_process(&Instruction::Neg));
+        assert!(processor.can_process(&Instruction::Round));
 
         // Register-based arithmetic operations
         assert!(processor.can_process(&Instruction::AddOp(
@@ -384,12 +546,27 @@ mod tests {
             Operand::Value(1.0),
             Operand::Value(2.0)
         )));
+        assert!(processor.can_process(&Instruction::MinOp(
+            Operand::Value(1.0),
+            Operand::Value(2.0)
+        )));
+        assert!(processor.can_process(&Instruction::MaxOp(
+            Operand::Value(1.0),
+            Operand::Value(2.0)
+        )));
+        assert!(processor.can_process(&Instruction::ClampOp(
+            Operand::Value(1.0),
+            Operand::Value(0.0),
+            Operand::Value(2.0)
+        )));
         assert!(processor.can_process(&Instruction::PowOp(
             Operand::Value(1.0),
             Operand::Value(2.0)
         )));
         assert!(processor.can_process(&Instruction::SqrtOp(Operand::Value(1.0))));
         assert!(processor.can_process(&Instruction::LogOp(Operand::Value(1.0))));
+        assert!(processor.can_process(&Instruction::NegOp(Operand::Value(1.0))));
+        assert!(processor.can_process(&Instruction::FloorOp(Operand::Value(1.0))));
 
         // Should not process non-arithmetic operations
         assert!(!processor.can_process(&Instruction::Push(Operand::Value(1.0))));
@@ -626,6 +803,51 @@ mod tests {
         assert!((robot.vm_state.stack.pop().unwrap() - 1.0).abs() < 1e-10); // Using approximate equality for floating-point
     }
 
+    #[test]
+    fn test_neg() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        robot.vm_state.stack.push(2.5).unwrap();
+
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Neg,
+            &mut command_queue,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), -2.5);
+    }
+
+    #[test]
+    fn test_floor_ceil_round() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        for (instruction, input, expected) in [
+            (Instruction::Floor, -1.5, -2.0),
+            (Instruction::Ceil, -1.5, -1.0),
+            (Instruction::Round, -2.5, -3.0), // Half rounds away from zero
+            (Instruction::Round, 2.5, 3.0),
+        ] {
+            robot.vm_state.stack.push(input).unwrap();
+            let result = processor.process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &instruction,
+                &mut command_queue,
+            );
+            assert!(result.is_ok());
+            assert_eq!(robot.vm_state.stack.pop().unwrap(), expected);
+        }
+    }
+
     // Register-based arithmetic operation tests
 
     #[test]
@@ -744,6 +966,142 @@ mod tests {
         assert_eq!(robot.vm_state.registers.get(Register::Result).unwrap(), 3.0);
     }
 
+    #[test]
+    fn test_min_max() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        for (instruction, expected) in [(Instruction::Min, 3.0), (Instruction::Max, 7.0)] {
+            robot.vm_state.stack.push(7.0).unwrap();
+            robot.vm_state.stack.push(3.0).unwrap();
+            processor
+                .process(
+                    &mut robot,
+                    &all_robots,
+                    &arena,
+                    &instruction,
+                    &mut command_queue,
+                )
+                .unwrap();
+            assert_eq!(robot.vm_state.stack.pop().unwrap(), expected);
+        }
+
+        // Operand form writes @result and propagates the non-NaN value
+        let cases = [
+            (
+                Instruction::MinOp(Operand::Register(Register::D0), Operand::Value(-2.0)),
+                -2.0,
+            ),
+            (
+                Instruction::MaxOp(Operand::Register(Register::D0), Operand::Value(-2.0)),
+                5.0,
+            ),
+            (
+                Instruction::MinOp(Operand::Value(f64::NAN), Operand::Value(4.0)),
+                4.0,
+            ),
+            (
+                Instruction::MaxOp(Operand::Value(4.0), Operand::Value(f64::NAN)),
+                4.0,
+            ),
+        ];
+        for (instruction, expected) in cases {
+            processor
+                .process(
+                    &mut robot,
+                    &all_robots,
+                    &arena,
+                    &instruction,
+                    &mut command_queue,
+                )
+                .unwrap();
+            assert_eq!(
+                robot.vm_state.registers.get(Register::Result).unwrap(),
+                expected
+            );
+        }
+        assert!(robot.vm_state.stack.pop().is_err());
+    }
+
+    #[test]
+    fn test_clamp_op() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        // @d0 = 5.0
+        let cases = [
+            (0.0, 10.0, 5.0),
+            (6.0, 10.0, 6.0),
+            (0.0, 1.0, 1.0),
+            (5.0, 5.0, 5.0),
+        ];
+        for (lo, hi, expected) in cases {
+            let clamp = Instruction::ClampOp(
+                Operand::Register(Register::D0),
+                Operand::Value(lo),
+                Operand::Value(hi),
+            );
+            processor
+                .process(&mut robot, &all_robots, &arena, &clamp, &mut command_queue)
+                .unwrap();
+            assert_eq!(
+                robot.vm_state.registers.get(Register::Result).unwrap(),
+                expected
+            );
+        }
+
+        for (lo, hi) in [(1.0, 0.0), (f64::NAN, 1.0)] {
+            let clamp = Instruction::ClampOp(
+                Operand::Register(Register::D0),
+                Operand::Value(lo),
+                Operand::Value(hi),
+            );
+            let result =
+                processor.process(&mut robot, &all_robots, &arena, &clamp, &mut command_queue);
+            assert_eq!(result, Err(VMFault::InvalidOperand));
+        }
+    }
+
+    #[test]
+    fn test_rand_normal() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+        let rand_normal = Instruction::RandNormal(Operand::Value(3.0), Operand::Value(2.0));
+
+        let mut sample = |robot: &mut Robot| {
+            processor
+                .process(robot, &all_robots, &arena, &rand_normal, &mut command_queue)
+                .unwrap();
+            robot.vm_state.registers.get(Register::Result).unwrap()
+        };
+        robot.seeded_rng = crate::robot::robot_rng(42, robot.id);
+        let samples: Vec<f64> = (0..10_000).map(|_| sample(&mut robot)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 3.0).abs() < 0.1, "mean {}", mean);
+        assert!(
+            (variance.sqrt() - 2.0).abs() < 0.1,
+            "stddev {}",
+            variance.sqrt()
+        );
+
+        // The same seed replays the same sequence
+        robot.seeded_rng = crate::robot::robot_rng(42, robot.id);
+        let replayed: Vec<f64> = (0..100).map(|_| sample(&mut robot)).collect();
+        assert_eq!(replayed, samples[..100]);
+
+        for (mean, stddev) in [(0.0, -1.0), (f64::NAN, 1.0), (0.0, f64::INFINITY)] {
+            let bad = Instruction::RandNormal(Operand::Value(mean), Operand::Value(stddev));
+            let result =
+                processor.process(&mut robot, &all_robots, &arena, &bad, &mut command_queue);
+            assert_eq!(result, Err(VMFault::InvalidOperand));
+        }
+    }
+
     #[test]
     fn test_pow_op() {
         let (mut robot, arena, mut command_queue) = setup();
@@ -801,6 +1159,51 @@ mod tests {
         assert!((robot.vm_state.registers.get(Register::Result).unwrap() - 1.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_neg_op() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::NegOp(Operand::Value(-5.0)), // -(-5.0) = 5.0
+            &mut command_queue,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.registers.get(Register::Result).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_floor_ceil_round_op() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ArithmeticOperations::new();
+        let all_robots = vec![];
+
+        robot.vm_state.registers.set(Register::D0, -1.5).unwrap();
+        for (instruction, expected) in [
+            (Instruction::FloorOp(Operand::Register(Register::D0)), -2.0),
+            (Instruction::CeilOp(Operand::Register(Register::D0)), -1.0),
+            (Instruction::RoundOp(Operand::Value(-2.5)), -3.0),
+        ] {
+            let result = processor.process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &instruction,
+                &mut command_queue,
+            );
+            assert!(result.is_ok());
+            assert_eq!(
+                robot.vm_state.registers.get(Register::Result).unwrap(),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_divmod_operation_integration() {
         let arena = Arena::new();
@@ -1,8 +1,12 @@
 use crate::arena::Arena;
-use crate::robot::{Robot, RobotStatus};
-use crate::types::{ArenaCommand, Point};
+use crate::config;
+use crate::events::Event;
+use crate::robot::{Robot, RobotStatus, standard_normal};
+use crate::types::{ArenaCommand, Point, WeaponKind};
 use crate::vm::error::VMFault;
+use crate::vm::numeric::operand_to_index;
 use crate::vm::registers::Register;
+use rand::rngs::StdRng;
 use std::collections::VecDeque;
 
 use super::processor::InstructionProcessor;
@@ -16,11 +20,32 @@ impl CombatOperations {
         CombatOperations
     }
 
-    // Shared helper for firing
-    fn handle_fire(robot: &mut Robot, power: f64, command_queue: &mut VecDeque<ArenaCommand>) {
+    // Shared helper for firing (flat shots, or lobbed mortar shells). Flat shots
+    // bounce off up to `bounces` walls (capped at PROJECTILE_MAX_BOUNCES); a laser
+    // weapon fires a beam instead, which never bounces but still waits out the cooldown.
+    fn handle_fire(
+        robot: &mut Robot,
+        power: f64,
+        mortar: bool,
+        bounces: usize,
+        command_queue: &mut VecDeque<ArenaCommand>,
+    ) {
+        if !mortar && robot.turret.ranged.kind == WeaponKind::Laser {
+            Self::handle_beam(robot, power, true, command_queue);
+            return;
+        }
         let fire_position = robot.position;
         let fire_direction = robot.turret.direction;
-        if let Some(projectile) = robot.fire_weapon(power) {
+        let shot = if mortar {
+            robot.fire_mortar(power)
+        } else {
+            robot.fire_weapon(power)
+        };
+        if let Some(mut projectile) = shot {
+            if !mortar {
+                projectile.bounces_remaining =
+                    bounces.min(config::PROJECTILE_MAX_BOUNCES as usize) as u32;
+            }
             command_queue.push_back(ArenaCommand::SpawnProjectile(projectile));
             command_queue.push_back(ArenaCommand::SpawnMuzzleFlash {
                 position: fire_position,
@@ -29,17 +54,110 @@ impl CombatOperations {
         }
     }
 
-    // Shared helper for scanning
+    // Shared helper for beams, from `beam` or a laser weapon's `fire`; the arena
+    // resolves the hit later this cycle
+    fn handle_beam(
+        robot: &mut Robot,
+        power: f64,
+        laser: bool,
+        command_queue: &mut VecDeque<ArenaCommand>,
+    ) {
+        let beam_power = if laser {
+            robot.fire_laser(power)
+        } else {
+            robot.fire_beam(power)
+        };
+        if beam_power > 0.0 {
+            command_queue.push_back(ArenaCommand::FireBeam {
+                source_robot: robot.id,
+                source_team: robot.team,
+                start: robot.position,
+                direction: robot.turret.direction,
+                power: beam_power,
+                falloff: laser,
+            });
+        }
+    }
+
+    // Shared helper for laying mines; the arena sets them off when another robot comes close
+    fn handle_mine(robot: &mut Robot, power: f64, command_queue: &mut VecDeque<ArenaCommand>) {
+        if let Some(mine) = robot.lay_mine(power) {
+            command_queue.push_back(ArenaCommand::SpawnMine(mine));
+        }
+    }
+
+    // Shared helper for self-destructing
+    fn handle_detonate(robot: &mut Robot, power: f64, command_queue: &mut VecDeque<ArenaCommand>) {
+        let position = robot.position;
+        let blast_power = robot.detonate(power);
+        command_queue.push_back(ArenaCommand::Detonate {
+            source_robot: robot.id,
+            source_team: robot.team,
+            position,
+            power: blast_power,
+        });
+    }
+
+    // Sensor model for scans: the distance and direction errors are normally
+    // distributed with standard deviations proportional to the target's distance,
+    // so close targets read almost exactly and far ones are fuzzy.
+    fn noisy_reading(distance: f64, angle: f64, rng: &mut StdRng) -> (f64, f64) {
+        let distance_error = standard_normal(rng) * config::SCAN_DISTANCE_NOISE * distance;
+        let angle_error = standard_normal(rng) * config::SCAN_DIRECTION_NOISE * distance;
+        (
+            distance + distance_error,
+            (angle + angle_error).rem_euclid(360.0),
+        )
+    }
+
+    // Shared helper for scanning. Scans cost SCAN_ENERGY_COST (SWEEP_POWER_COST for an
+    // all-around sweep); without enough power the scan doesn't run, the target registers
+    // are left as they were and @result = 0. A successful scan sets @result = 1.
     fn handle_scan<F>(
         robot: &mut Robot,
         get_robot_info: &mut F,
         robot_ids: &[u32],
         arena: &Arena,
-    ) -> Result<(f64, f64), VMFault>
+        sweep: bool,
+    ) -> Result<(), VMFault>
     where
         F: FnMut(u32) -> Option<(Point, RobotStatus)>,
     {
-        let (distance, angle) = robot.scan_for_targets_by_id(get_robot_info, robot_ids, arena);
+        let cost = if sweep {
+            config::SWEEP_POWER_COST
+        } else {
+            config::SCAN_ENERGY_COST
+        };
+        if robot.power < cost {
+            crate::debug_weapon!(
+                robot.id,
+                robot.vm_state.turn,
+                robot.vm_state.cycle,
+                "Attempted to scan with insufficient power ({:.4})",
+                robot.power
+            );
+            return robot
+                .vm_state
+                .registers
+                .set(Register::Result, 0.0)
+                .map_err(|_| VMFault::PermissionError);
+        }
+        robot.power -= cost;
+
+        let (mut distance, mut angle, target_id) = if sweep {
+            robot.sweep_for_targets_by_id(get_robot_info, robot_ids, arena)
+        } else {
+            robot.scan_for_targets_by_id(get_robot_info, robot_ids, arena)
+        };
+        if robot.sensor_noise && target_id.is_some() {
+            (distance, angle) = Self::noisy_reading(distance, angle, &mut robot.seeded_rng);
+        }
+        robot.events.push(Event::ScanPerformed {
+            robot_id: robot.id,
+            target_id,
+            distance,
+            direction: angle,
+        });
         robot
             .vm_state
             .registers
@@ -50,13 +168,53 @@ impl CombatOperations {
             .registers
             .set_internal(Register::TargetDirection, angle)
             .map_err(|_| VMFault::PermissionError)?;
-        Ok((distance, angle))
+        robot
+            .vm_state
+            .registers
+            .set_internal(Register::TargetId, target_id.map_or(-1.0, |id| id as f64))
+            .map_err(|_| VMFault::PermissionError)?;
+        // Remember where a found target stood, for after it leaves the scan cone
+        if distance > 0.0 {
+            let angle_rad = angle.to_radians();
+            robot
+                .vm_state
+                .registers
+                .set_internal(
+                    Register::LastTargetX,
+                    robot.position.x + distance * angle_rad.cos(),
+                )
+                .map_err(|_| VMFault::PermissionError)?;
+            robot
+                .vm_state
+                .registers
+                .set_internal(
+                    Register::LastTargetY,
+                    robot.position.y + distance * angle_rad.sin(),
+                )
+                .map_err(|_| VMFault::PermissionError)?;
+        }
+        robot.scanned_last_cycle = true;
+        robot
+            .vm_state
+            .registers
+            .set(Register::Result, 1.0)
+            .map_err(|_| VMFault::PermissionError)
     }
 }
 
 impl InstructionProcessor for CombatOperations {
     fn can_process(&self, instruction: &Instruction) -> bool {
-        matches!(instruction, Instruction::Fire(_) | Instruction::Scan)
+        matches!(
+            instruction,
+            Instruction::Fire(_)
+                | Instruction::FireBounce(_, _)
+                | Instruction::Mortar(_)
+                | Instruction::Beam(_)
+                | Instruction::Scan
+                | Instruction::Sweep
+                | Instruction::Detonate(_)
+                | Instruction::Mine(_)
+        )
     }
 
     fn process(
@@ -71,10 +229,38 @@ impl InstructionProcessor for CombatOperations {
             Instruction::Fire(op) => {
                 crate::debug_weapon!(robot.id, robot.vm_state.turn, robot.vm_state.cycle, "FIRE!");
                 let power = op.get_value(&robot.vm_state)?;
-                Self::handle_fire(robot, power, command_queue);
+                Self::handle_fire(robot, power, false, 0, command_queue);
                 Ok(())
             }
-            Instruction::Scan => {
+            Instruction::FireBounce(op, bounces_op) => {
+                let power = op.get_value(&robot.vm_state)?;
+                // Bounce counts must be whole, non-negative numbers
+                let bounces = operand_to_index(bounces_op.get_value(&robot.vm_state)?)
+                    .map_err(|_| VMFault::InvalidOperand)?;
+                Self::handle_fire(robot, power, false, bounces, command_queue);
+                Ok(())
+            }
+            Instruction::Mortar(op) => {
+                let power = op.get_value(&robot.vm_state)?;
+                Self::handle_fire(robot, power, true, 0, command_queue);
+                Ok(())
+            }
+            Instruction::Beam(op) => {
+                let power = op.get_value(&robot.vm_state)?;
+                Self::handle_beam(robot, power, false, command_queue);
+                Ok(())
+            }
+            Instruction::Mine(op) => {
+                let power = op.get_value(&robot.vm_state)?;
+                Self::handle_mine(robot, power, command_queue);
+                Ok(())
+            }
+            Instruction::Detonate(op) => {
+                let power = op.get_value(&robot.vm_state)?;
+                Self::handle_detonate(robot, power, command_queue);
+                Ok(())
+            }
+            Instruction::Scan | Instruction::Sweep => {
                 // Build closure and robot_ids from all_robots
                 let mut get_robot_info = |id: u32| {
                     for other_robot in all_robots {
@@ -85,7 +271,8 @@ impl InstructionProcessor for CombatOperations {
                     None
                 };
                 let robot_ids: Vec<u32> = all_robots.iter().map(|r| r.id).collect();
-                Self::handle_scan(robot, &mut get_robot_info, &robot_ids, arena)?;
+                let sweep = matches!(instruction, Instruction::Sweep);
+                Self::handle_scan(robot, &mut get_robot_info, &robot_ids, arena, sweep)?;
                 Ok(())
             }
             _ => Err(VMFault::InvalidInstruction),
@@ -111,11 +298,12 @@ where
         Instruction::Fire(op) => {
             crate::debug_weapon!(robot.id, robot.vm_state.turn, robot.vm_state.cycle, "FIRE!");
             let power = op.get_value(&robot.vm_state)?;
-            CombatOperations::handle_fire(robot, power, command_queue);
+            CombatOperations::handle_fire(robot, power, false, 0, command_queue);
             Ok(())
         }
-        Instruction::Scan => {
-            CombatOperations::handle_scan(robot, get_robot_info, robot_ids, arena)?;
+        Instruction::Scan | Instruction::Sweep => {
+            let sweep = matches!(instruction, Instruction::Sweep);
+            CombatOperations::handle_scan(robot, get_robot_info, robot_ids, arena, sweep)?;
             Ok(())
         }
         _ => {
@@ -207,6 +395,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fire_with_bounces() {
+        let arena = Arena::new();
+        let processor = CombatOperations::new();
+        let bounces_for = |requested: f64| {
+            let mut robot = create_test_robot();
+            robot.power = 1.0;
+            let mut command_queue = VecDeque::new();
+            let fire = Instruction::FireBounce(Operand::Value(0.5), Operand::Value(requested));
+            processor
+                .process(&mut robot, &[], &arena, &fire, &mut command_queue)
+                .map(|()| match command_queue.pop_front() {
+                    Some(ArenaCommand::SpawnProjectile(projectile)) => projectile.bounces_remaining,
+                    other => panic!("Expected SpawnProjectile command, got {:?}", other),
+                })
+                .inspect_err(|_| {
+                    assert!(command_queue.is_empty(), "A faulted shot fires nothing");
+                    assert_eq!(robot.power, 1.0, "A faulted shot costs no power");
+                })
+        };
+        assert_eq!(bounces_for(2.0), Ok(2));
+        assert_eq!(bounces_for(0.0), Ok(0));
+        assert_eq!(bounces_for(100.0), Ok(config::PROJECTILE_MAX_BOUNCES));
+        // Like `pick`, fractional, negative and non-finite counts are invalid operands
+        for bad in [1.9, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(bounces_for(bad), Err(VMFault::InvalidOperand), "{}", bad);
+        }
+    }
+
     #[test]
     fn test_fire_insufficient_power() {
         let mut robot = create_test_robot();
@@ -231,6 +448,130 @@ mod tests {
         assert_eq!(command_queue.len(), 0);
     }
 
+    #[test]
+    fn test_laser_fire_respects_weapon_cooldown() {
+        let mut robot = create_test_robot();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let processor = CombatOperations::new();
+        robot.vm_state.set_selected_component(2).unwrap();
+        robot.turret.ranged.kind = WeaponKind::Laser;
+        robot.power = 1.0;
+
+        let fire = Instruction::Fire(Operand::Value(0.4));
+        processor
+            .process(&mut robot, &[], &arena, &fire, &mut command_queue)
+            .unwrap();
+        assert!(matches!(
+            command_queue.pop_front(),
+            Some(ArenaCommand::FireBeam { .. })
+        ));
+        assert_eq!(robot.shots_fired, 1);
+        assert_eq!(
+            robot.turret.ranged.cooldown_remaining,
+            config::WEAPON_COOLDOWN_CYCLES
+        );
+
+        // A second fire in the same cooldown window does nothing
+        let power = robot.power;
+        processor
+            .process(&mut robot, &[], &arena, &fire, &mut command_queue)
+            .unwrap();
+        assert!(command_queue.is_empty());
+        assert_eq!(robot.power, power);
+        assert_eq!(robot.shots_fired, 1);
+    }
+
+    #[test]
+    fn test_beam_instruction() {
+        let mut robot = create_test_robot();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let processor = CombatOperations::new();
+        robot.power = 1.0;
+
+        let beam = Instruction::Beam(Operand::Value(0.4));
+        let result = processor.process(&mut robot, &[], &arena, &beam, &mut command_queue);
+
+        assert!(result.is_ok());
+        assert!((robot.power - (1.0 - 0.4 * config::BEAM_POWER_COST)).abs() < 1e-9);
+        match command_queue.pop_front() {
+            Some(ArenaCommand::FireBeam {
+                source_robot,
+                power,
+                ..
+            }) => {
+                assert_eq!(source_robot, robot.id);
+                assert!((power - 0.4).abs() < 1e-9);
+            }
+            other => panic!("Expected FireBeam command, got {:?}", other),
+        }
+
+        // Without power nothing is fired
+        robot.power = 0.0;
+        processor
+            .process(&mut robot, &[], &arena, &beam, &mut command_queue)
+            .unwrap();
+        assert!(command_queue.is_empty());
+    }
+
+    #[test]
+    fn test_detonate_instruction() {
+        let mut robot = create_test_robot();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let processor = CombatOperations::new();
+        robot.power = 0.8;
+
+        let detonate = Instruction::Detonate(Operand::Value(0.5));
+        let result = processor.process(&mut robot, &[], &arena, &detonate, &mut command_queue);
+
+        assert!(result.is_ok());
+        assert_eq!(robot.status, RobotStatus::Destroyed);
+        assert_eq!(robot.power, 0.0);
+        match command_queue.pop_front() {
+            Some(ArenaCommand::Detonate {
+                source_robot,
+                power,
+                ..
+            }) => {
+                assert_eq!(source_robot, robot.id);
+                assert!((power - 0.4).abs() < 1e-9);
+            }
+            other => panic!("Expected Detonate command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mine_instruction() {
+        let mut robot = create_test_robot();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let processor = CombatOperations::new();
+        robot.power = 0.3;
+
+        // No component needs to be selected; power is capped at what the robot has
+        let mine = Instruction::Mine(Operand::Value(0.5));
+        processor
+            .process(&mut robot, &[], &arena, &mine, &mut command_queue)
+            .unwrap();
+        assert_eq!(robot.power, 0.0);
+        match command_queue.pop_front() {
+            Some(ArenaCommand::SpawnMine(laid)) => {
+                assert_eq!(laid.owner, robot.id);
+                assert_eq!(laid.position, robot.position);
+                assert!((laid.power - 0.3).abs() < 1e-9);
+            }
+            other => panic!("Expected SpawnMine command, got {:?}", other),
+        }
+
+        // Out of power, nothing is laid
+        processor
+            .process(&mut robot, &[], &arena, &mine, &mut command_queue)
+            .unwrap();
+        assert!(command_queue.is_empty());
+    }
+
     #[test]
     fn test_scan_instruction() {
         let mut robot = create_test_robot();
@@ -283,6 +624,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scan_remembers_last_target_position() {
+        let mut robot = create_test_robot();
+        robot.vm_state.set_selected_component(2).unwrap();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let executor = InstructionExecutor::new();
+        let last_target = |robot: &Robot| {
+            let registers = &robot.vm_state.registers;
+            (
+                registers.get(Register::LastTargetX).unwrap(),
+                registers.get(Register::LastTargetY).unwrap(),
+            )
+        };
+
+        let mut other_robot = create_test_robot_at(Point { x: 0.7, y: 0.5 }, 2);
+        other_robot.status = RobotStatus::Active;
+        let all_robots = vec![robot.clone(), other_robot.clone()];
+        executor
+            .execute_instruction(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Scan,
+                &mut command_queue,
+            )
+            .unwrap();
+        let (x, y) = last_target(&robot);
+        assert!((x - 0.7).abs() < 1e-9 && (y - 0.5).abs() < 1e-9);
+
+        // The target moves out of the cone: the next scan finds nothing, the memory stays
+        other_robot.position = Point { x: 0.5, y: 0.9 };
+        let all_robots = vec![robot.clone(), other_robot];
+        executor
+            .execute_instruction(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Scan,
+                &mut command_queue,
+            )
+            .unwrap();
+        assert_eq!(
+            robot
+                .vm_state
+                .registers
+                .get(Register::TargetDistance)
+                .unwrap(),
+            0.0
+        );
+        let (x, y) = last_target(&robot);
+        assert!((x - 0.7).abs() < 1e-9 && (y - 0.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_scan_no_targets() {
         let mut robot = create_test_robot();
@@ -320,6 +715,301 @@ mod tests {
         assert_eq!(angle, 0.0);
     }
 
+    #[test]
+    fn test_scan_reports_target_id() {
+        let mut robot = create_test_robot();
+        robot.vm_state.set_selected_component(2).unwrap();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let executor = InstructionExecutor::new();
+        assert_eq!(
+            robot.vm_state.registers.get(Register::TargetId).unwrap(),
+            -1.0
+        );
+
+        // Both robots are in the cone; the closer one (id 3) wins
+        let mut far_robot = create_test_robot_at(Point { x: 0.9, y: 0.5 }, 2);
+        far_robot.status = RobotStatus::Active;
+        let mut near_robot = create_test_robot_at(Point { x: 0.7, y: 0.5 }, 3);
+        near_robot.status = RobotStatus::Active;
+        let all_robots = vec![robot.clone(), far_robot, near_robot];
+        executor
+            .execute_instruction(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Scan,
+                &mut command_queue,
+            )
+            .unwrap();
+        assert_eq!(
+            robot.vm_state.registers.get(Register::TargetId).unwrap(),
+            3.0
+        );
+
+        // Nothing in the cone resets it to -1
+        let all_robots = vec![robot.clone()];
+        executor
+            .execute_instruction(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Scan,
+                &mut command_queue,
+            )
+            .unwrap();
+        assert_eq!(
+            robot.vm_state.registers.get(Register::TargetId).unwrap(),
+            -1.0
+        );
+    }
+
+    #[test]
+    fn test_scan_sensor_noise_scales_with_distance() {
+        let mut robot = create_test_robot();
+        robot.vm_state.set_selected_component(2).unwrap();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let executor = InstructionExecutor::new();
+        let mut target = create_test_robot_at(Point { x: 0.9, y: 0.5 }, 2);
+        target.status = RobotStatus::Active;
+        let all_robots = vec![robot.clone(), target];
+        let mut scan = |robot: &mut Robot| {
+            executor
+                .execute_instruction(
+                    robot,
+                    &all_robots,
+                    &arena,
+                    &Instruction::Scan,
+                    &mut command_queue,
+                )
+                .unwrap();
+            let registers = &robot.vm_state.registers;
+            (
+                registers.get(Register::TargetDistance).unwrap(),
+                registers.get(Register::TargetDirection).unwrap(),
+            )
+        };
+
+        // Perfect sensors report the exact reading
+        let (distance, angle) = scan(&mut robot);
+        assert!((distance - 0.4).abs() < 1e-12);
+        assert_eq!(angle, 0.0);
+
+        // Noisy sensors stay close to it
+        robot.sensor_noise = true;
+        let (distance, _) = scan(&mut robot);
+        assert_ne!(distance, 0.4);
+        assert!((distance - 0.4).abs() < 0.05);
+
+        // The average error grows with the distance to the target
+        let mut rng = crate::robot::robot_rng(7, 1);
+        let mut mean_errors = |distance: f64| {
+            let (mut distance_error, mut angle_error) = (0.0, 0.0);
+            for _ in 0..500 {
+                let (d, a) = CombatOperations::noisy_reading(distance, 90.0, &mut rng);
+                distance_error += (d - distance).abs() / 500.0;
+                angle_error += (a - 90.0).abs() / 500.0;
+            }
+            (distance_error, angle_error)
+        };
+        let near = mean_errors(0.1);
+        let far = mean_errors(1.0);
+        assert!(near.0 > 0.0 && far.0 > 5.0 * near.0);
+        assert!(near.1 > 0.0 && far.1 > 5.0 * near.1);
+    }
+
+    #[test]
+    fn test_power_starved_scan_keeps_stale_target() {
+        let executor = InstructionExecutor::new();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let mut target = create_test_robot_at(Point { x: 0.7, y: 0.5 }, 2);
+        target.status = RobotStatus::Active;
+        let scan = |robot: &mut Robot, target: &Robot, command_queue: &mut VecDeque<_>| {
+            let all_robots = vec![robot.clone(), target.clone()];
+            executor
+                .execute_instruction(
+                    robot,
+                    &all_robots,
+                    &arena,
+                    &Instruction::Scan,
+                    command_queue,
+                )
+                .unwrap();
+            (
+                robot.vm_state.registers.get(Register::Result).unwrap(),
+                robot
+                    .vm_state
+                    .registers
+                    .get(Register::TargetDistance)
+                    .unwrap(),
+            )
+        };
+
+        let mut starved = create_test_robot();
+        starved.vm_state.set_selected_component(2).unwrap();
+        starved.power = config::SCAN_ENERGY_COST;
+        let (result, first_distance) = scan(&mut starved, &target, &mut command_queue);
+        assert_eq!(result, 1.0);
+        assert!(starved.power.abs() < 1e-9);
+
+        // The target moves, but the starved robot's scan fails and keeps the old reading
+        target.position = Point { x: 0.8, y: 0.5 };
+        let (result, distance) = scan(&mut starved, &target, &mut command_queue);
+        assert_eq!(result, 0.0);
+        assert_eq!(distance, first_distance);
+
+        // A powered robot in the same spot sees the new position
+        let mut powered = create_test_robot();
+        powered.vm_state.set_selected_component(2).unwrap();
+        powered.power = 1.0;
+        let (result, distance) = scan(&mut powered, &target, &mut command_queue);
+        assert_eq!(result, 1.0);
+        assert!(distance > first_distance);
+    }
+
+    #[test]
+    fn test_scan_power_cost() {
+        let executor = InstructionExecutor::new();
+        let arena = Arena::new();
+        let mut command_queue = VecDeque::new();
+        let target_pos = Point { x: 0.7, y: 0.5 };
+        let mut target = create_test_robot_at(target_pos, 2);
+        target.status = RobotStatus::Active;
+
+        // Successful scan deducts power and reports success
+        let mut robot = create_test_robot();
+        robot.vm_state.set_selected_component(2).unwrap();
+        robot.power = 0.5;
+        let all_robots = vec![robot.clone(), target.clone()];
+        let result = executor.execute_instruction(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Scan,
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert!((robot.power - (0.5 - config::SCAN_ENERGY_COST)).abs() < 1e-9);
+        assert_eq!(robot.vm_state.registers.get(Register::Result).unwrap(), 1.0);
+        assert!(
+            robot
+                .vm_state
+                .registers
+                .get(Register::TargetDistance)
+                .unwrap()
+                > 0.0
+        );
+
+        // Low-power scan fails without scanning
+        let mut robot = create_test_robot();
+        robot.vm_state.set_selected_component(2).unwrap();
+        robot.power = config::SCAN_ENERGY_COST / 2.0;
+        let result = executor.execute_instruction(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Scan,
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.power, config::SCAN_ENERGY_COST / 2.0);
+        assert_eq!(robot.vm_state.registers.get(Register::Result).unwrap(), 0.0);
+        assert_eq!(
+            robot
+                .vm_state
+                .registers
+                .get(Register::TargetDistance)
+                .unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_sweep_finds_targets_behind_the_turret() {
+        let arena = Arena::new();
+        let processor = CombatOperations::new();
+        let mut command_queue = VecDeque::new();
+        let mut enemy = create_test_robot_at(Point { x: 0.2, y: 0.5 }, 2);
+        enemy.status = RobotStatus::Active;
+        let all_robots = [enemy];
+        let target = |robot: &Robot| robot.vm_state.registers.get(Register::TargetId).unwrap();
+
+        // The turret faces east (0 degrees); the enemy is due west, behind it
+        let mut robot = create_test_robot();
+        robot.turret.direction = 0.0;
+        processor
+            .process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Scan,
+                &mut command_queue,
+            )
+            .unwrap();
+        assert_eq!(target(&robot), -1.0);
+
+        processor
+            .process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Sweep,
+                &mut command_queue,
+            )
+            .unwrap();
+        assert_eq!(target(&robot), 2.0);
+        let distance = robot
+            .vm_state
+            .registers
+            .get(Register::TargetDistance)
+            .unwrap();
+        let direction = robot
+            .vm_state
+            .registers
+            .get(Register::TargetDirection)
+            .unwrap();
+        assert!((distance - 0.3).abs() < 1e-9);
+        assert!((direction - 180.0).abs() < 1e-9);
+        assert!(
+            (robot.power
+                - (config::DEFAULT_INITIAL_POWER
+                    - config::SCAN_ENERGY_COST
+                    - config::SWEEP_POWER_COST))
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            Instruction::Sweep.cycle_cost(&robot.vm_state)
+                > Instruction::Scan.cycle_cost(&robot.vm_state)
+        );
+    }
+
+    #[test]
+    fn test_scan_can_skip_teammates() {
+        let mut robot = create_test_robot();
+        robot.team = 1;
+        robot.teammates = vec![2];
+        let arena = Arena::new();
+        let mut teammate = create_test_robot_at(Point { x: 0.7, y: 0.5 }, 2);
+        teammate.status = RobotStatus::Active;
+        teammate.team = 1;
+        let robots = [teammate];
+        let mut get_robot_info = |id: u32| {
+            robots
+                .iter()
+                .find(|r| r.id == id)
+                .map(|r| (r.position, r.status))
+        };
+
+        let (_, _, target) = robot.scan_for_targets_by_id(&mut get_robot_info, &[2], &arena);
+        assert_eq!(target, Some(2));
+        robot.skip_teammates = true;
+        let (_, _, target) = robot.scan_for_targets_by_id(&mut get_robot_info, &[2], &arena);
+        assert_eq!(target, None);
+    }
+
     #[test]
     fn test_scan_by_id() {
         let mut robot = create_test_robot();
@@ -2,7 +2,8 @@ use crate::arena::Arena;
 use crate::config;
 use crate::robot::Robot;
 use crate::types::ArenaCommand;
-use crate::vm::error::VMFault;
+use crate::vm::error::{StackError, VMFault};
+use crate::vm::numeric::operand_to_index;
 use crate::vm::registers::Register;
 use std::collections::VecDeque;
 
@@ -10,8 +11,25 @@ use super::processor::InstructionProcessor;
 use crate::vm::instruction::Instruction;
 
 /// Processor for robot component operations
+///
+/// Component selection rules (`@component`):
+/// - `select`/`deselect` work with any selection; only IDs 0 (none), 1 (drive),
+///   2 (turret) and 3 (shield) can be selected.
+/// - `whoami` pushes the selected ID onto the stack, for shared subroutines.
+/// - `drive` requires the drive (1) to be selected.
+/// - `rotate` acts on whichever of the drive (1) or turret (2) is selected.
+/// - `shield` requires the shield (3) to be selected.
+/// - Issuing `drive`, `rotate` or `shield` with nothing selected faults with
+///   `NoComponentSelected`; with an incompatible component it faults with
+///   `InvalidComponentForOp`.
 pub struct ComponentOperations;
 
+impl Default for ComponentOperations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ComponentOperations {
     pub fn new() -> Self {
         ComponentOperations
@@ -24,8 +42,10 @@ impl InstructionProcessor for ComponentOperations {
             instruction,
             Instruction::Select(_)
                 | Instruction::Deselect
+                | Instruction::Whoami
                 | Instruction::Rotate(_)
                 | Instruction::Drive(_)
+                | Instruction::Shield(_)
         )
     }
 
@@ -41,11 +61,15 @@ impl InstructionProcessor for ComponentOperations {
             .vm_state
             .registers
             .get(Register::Component)
-            .unwrap_or(0.0) as u8;
+            .ok()
+            .and_then(|v| operand_to_index(v).ok())
+            .unwrap_or(0);
 
         match instruction {
             Instruction::Select(op) => {
-                let component_id = op.get_value_mut(&mut robot.vm_state)? as u8;
+                // Negative, fractional and non-finite ids are invalid, like ids above 3
+                let component_id = operand_to_index(op.get_value_mut(&mut robot.vm_state)?)
+                    .map_err(|_| VMFault::InvalidComponentForOp)?;
                 crate::debug_instructions!(
                     robot.id,
                     robot.vm_state.turn,
@@ -55,8 +79,8 @@ impl InstructionProcessor for ComponentOperations {
                     selected_component
                 );
                 match component_id {
-                    0..=2 => {
-                        let res = robot.vm_state.set_selected_component(component_id);
+                    0..=3 => {
+                        let res = robot.vm_state.set_selected_component(component_id as u8);
                         crate::debug_instructions!(
                             robot.id,
                             robot.vm_state.turn,
@@ -74,14 +98,22 @@ impl InstructionProcessor for ComponentOperations {
                 .vm_state
                 .set_selected_component(0)
                 .map_err(|_| VMFault::PermissionError),
+            Instruction::Whoami => robot
+                .vm_state
+                .stack
+                .push(selected_component as f64)
+                .map_err(|e| match e {
+                    StackError::Overflow => VMFault::StackOverflow,
+                    StackError::Underflow => VMFault::StackUnderflow,
+                }),
             Instruction::Rotate(op) => {
                 let angle = op.get_value(&robot.vm_state)?;
                 let component_val = robot
                     .vm_state
                     .registers
                     .get(Register::Component)
-                    .map_err(|_| VMFault::InvalidRegister)?
-                    as u8;
+                    .map_err(|_| VMFault::InvalidRegister)
+                    .and_then(operand_to_index)?;
 
                 crate::debug_instructions!(
                     robot.id,
@@ -139,67 +171,85 @@ impl InstructionProcessor for ComponentOperations {
                     .vm_state
                     .registers
                     .get(Register::Component)
-                    .unwrap_or(0.0) as u8;
-                if selected_component == 1 {
-                    // Drive component required
-                    crate::debug_instructions!(
-                        robot.id,
-                        robot.vm_state.turn,
-                        robot.vm_state.cycle,
-                        "Drive instruction. Value: {}",
-                        val
-                    );
-
-                    // When user inputs drive 1.0, we want the robot to move 1.0 GRID unit per TURN
-                    // A grid unit is config::UNIT_SIZE coordinate units (0.05)
-                    // So we convert grid units to coordinate units per cycle:
-                    // grid_units * UNIT_SIZE / CYCLES_PER_TURN = coordinate_units_per_cycle
-                    let units_per_cycle = val * config::DRIVE_VELOCITY_FACTOR;
-
-                    // Clamp to a maximum (let's say max is ±5 grid units per turn, or ±0.25 coordinate units)
-                    let max_units_per_cycle =
-                        config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR;
-                    let clamped_velocity =
-                        units_per_cycle.clamp(-max_units_per_cycle, max_units_per_cycle);
-
-                    if clamped_velocity != units_per_cycle {
+                    .ok()
+                    .and_then(|v| operand_to_index(v).ok())
+                    .unwrap_or(0);
+                match selected_component {
+                    1 => {
+                        // Drive component required
                         crate::debug_instructions!(
                             robot.id,
                             robot.vm_state.turn,
                             robot.vm_state.cycle,
-                            "Drive velocity clamped from {} to {} coordinate units per cycle",
-                            units_per_cycle,
-                            clamped_velocity
+                            "Drive instruction. Value: {}",
+                            val
                         );
-                    }
 
-                    robot.set_drive_velocity(clamped_velocity);
-                    crate::debug_instructions!(
-                        robot.id,
-                        robot.vm_state.turn,
-                        robot.vm_state.cycle,
-                        "Drive instruction set velocity to {} units per cycle ({} units per turn)",
-                        robot.drive.velocity,
-                        robot.drive.velocity * config::CYCLES_PER_TURN as f64 / config::UNIT_SIZE
-                    );
-
-                    // Update the velocity register to reflect the new target velocity
-                    robot
-                        .vm_state
-                        .registers
-                        .set_internal(Register::DriveVelocity, robot.drive.velocity)
-                        .unwrap();
-
-                    Ok(())
-                } else {
-                    crate::debug_instructions!(
-                        robot.id,
-                        robot.vm_state.turn,
-                        robot.vm_state.cycle,
-                        "Drive instruction FAILED - Invalid component (selected: {})",
-                        selected_component
-                    );
-                    Err(VMFault::InvalidComponentForOp)
+                        // When user inputs drive 1.0, we want the robot to move 1.0 GRID unit per TURN
+                        // A grid unit is config::UNIT_SIZE coordinate units (0.05)
+                        // So we convert grid units to coordinate units per cycle:
+                        // grid_units * UNIT_SIZE / CYCLES_PER_TURN = coordinate_units_per_cycle
+                        let units_per_cycle = val * config::DRIVE_VELOCITY_FACTOR;
+
+                        // Clamp to a maximum (let's say max is ±5 grid units per turn, or ±0.25 coordinate units)
+                        let max_units_per_cycle =
+                            config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR;
+                        let clamped_velocity =
+                            units_per_cycle.clamp(-max_units_per_cycle, max_units_per_cycle);
+
+                        if clamped_velocity != units_per_cycle {
+                            crate::debug_instructions!(
+                                robot.id,
+                                robot.vm_state.turn,
+                                robot.vm_state.cycle,
+                                "Drive velocity clamped from {} to {} coordinate units per cycle",
+                                units_per_cycle,
+                                clamped_velocity
+                            );
+                        }
+
+                        robot.set_drive_velocity(clamped_velocity);
+                        crate::debug_instructions!(
+                            robot.id,
+                            robot.vm_state.turn,
+                            robot.vm_state.cycle,
+                            "Drive instruction set velocity to {} units per cycle ({} units per turn)",
+                            robot.drive.velocity,
+                            robot.drive.velocity * config::CYCLES_PER_TURN as f64
+                                / config::UNIT_SIZE
+                        );
+
+                        // Update the velocity register to reflect the new target velocity
+                        robot
+                            .vm_state
+                            .registers
+                            .set_internal(Register::DriveVelocity, robot.drive.velocity)
+                            .unwrap();
+
+                        Ok(())
+                    }
+                    0 => Err(VMFault::NoComponentSelected),
+                    _ => {
+                        crate::debug_instructions!(
+                            robot.id,
+                            robot.vm_state.turn,
+                            robot.vm_state.cycle,
+                            "Drive instruction FAILED - Invalid component (selected: {})",
+                            selected_component
+                        );
+                        Err(VMFault::InvalidComponentForOp)
+                    }
+                }
+            }
+            Instruction::Shield(op) => {
+                let on = op.get_value(&robot.vm_state)? != 0.0;
+                match selected_component {
+                    3 => {
+                        robot.set_shield(on);
+                        Ok(())
+                    }
+                    0 => Err(VMFault::NoComponentSelected),
+                    _ => Err(VMFault::InvalidComponentForOp),
                 }
             }
             _ => Err(VMFault::InvalidInstruction),
@@ -244,6 +294,7 @@ mod tests {
         let processor = ComponentOperations::new();
         assert!(processor.can_process(&Instruction::Select(Operand::Value(1.0))));
         assert!(processor.can_process(&Instruction::Deselect));
+        assert!(processor.can_process(&Instruction::Whoami));
         assert!(processor.can_process(&Instruction::Rotate(Operand::Value(90.0))));
         assert!(processor.can_process(&Instruction::Drive(Operand::Value(1.0))));
 
@@ -251,6 +302,24 @@ mod tests {
         assert!(!processor.can_process(&Instruction::Add));
     }
 
+    #[test]
+    fn test_whoami_pushes_selected_component() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ComponentOperations::new();
+        for instruction in [
+            Instruction::Select(Operand::Value(2.0)),
+            Instruction::Whoami,
+            Instruction::Deselect,
+            Instruction::Whoami,
+        ] {
+            processor
+                .process(&mut robot, &[], &arena, &instruction, &mut command_queue)
+                .unwrap();
+        }
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 0.0);
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 2.0);
+    }
+
     #[test]
     fn test_select_component() {
         let (mut robot, arena, mut command_queue) = setup();
@@ -297,6 +366,39 @@ mod tests {
         assert_eq!(result.unwrap_err(), VMFault::InvalidComponentForOp);
     }
 
+    #[test]
+    fn test_shield_requires_shield_component() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ComponentOperations::new();
+        let shield_on = Instruction::Shield(Operand::Value(1.0));
+
+        let result = processor.process(&mut robot, &[], &arena, &shield_on, &mut command_queue);
+        assert_eq!(result, Err(VMFault::NoComponentSelected));
+        robot.vm_state.set_selected_component(2).unwrap();
+        let result = processor.process(&mut robot, &[], &arena, &shield_on, &mut command_queue);
+        assert_eq!(result, Err(VMFault::InvalidComponentForOp));
+        assert!(!robot.shield.active);
+
+        processor
+            .process(
+                &mut robot,
+                &[],
+                &arena,
+                &Instruction::Select(Operand::Value(3.0)),
+                &mut command_queue,
+            )
+            .unwrap();
+        processor
+            .process(&mut robot, &[], &arena, &shield_on, &mut command_queue)
+            .unwrap();
+        assert!(robot.shield.active);
+        let shield_off = Instruction::Shield(Operand::Value(0.0));
+        processor
+            .process(&mut robot, &[], &arena, &shield_off, &mut command_queue)
+            .unwrap();
+        assert!(!robot.shield.active);
+    }
+
     #[test]
     fn test_drive_requires_drive_component() {
         let (mut robot, arena, mut command_queue) = setup();
@@ -339,6 +441,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_drive_and_rotate_selection_rules() {
+        let processor = ComponentOperations::new();
+        let drive = Instruction::Drive(Operand::Value(1.0));
+        let rotate = Instruction::Rotate(Operand::Value(45.0));
+
+        // (selected component, expected drive result, expected rotate result)
+        let cases = [
+            (
+                0,
+                Err(VMFault::NoComponentSelected),
+                Err(VMFault::NoComponentSelected),
+            ),
+            (1, Ok(()), Ok(())),
+            (2, Err(VMFault::InvalidComponentForOp), Ok(())),
+            (
+                99,
+                Err(VMFault::InvalidComponentForOp),
+                Err(VMFault::InvalidComponentForOp),
+            ),
+        ];
+
+        for (component, expected_drive, expected_rotate) in cases {
+            let (mut robot, arena, mut command_queue) = setup();
+            robot.vm_state.set_selected_component(component).unwrap();
+            assert_eq!(
+                processor.process(&mut robot, &[], &arena, &drive, &mut command_queue),
+                expected_drive,
+                "drive with component {}",
+                component
+            );
+            assert_eq!(
+                processor.process(&mut robot, &[], &arena, &rotate, &mut command_queue),
+                expected_rotate,
+                "rotate with component {}",
+                component
+            );
+        }
+    }
+
     #[test]
     fn test_drive_sets_velocity() {
         let mut robot = create_test_robot();
@@ -375,9 +517,8 @@ mod tests {
         assert_eq!(robot.drive.velocity, expected_max);
 
         // Test with a value lower than the minimum
-        let excessive_reverse_velocity = -1.0 * (config::MAX_DRIVE_UNITS_PER_TURN + 1.0);
-        let expected_min =
-            -1.0 * (config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR);
+        let excessive_reverse_velocity = -(config::MAX_DRIVE_UNITS_PER_TURN + 1.0);
+        let expected_min = -(config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR);
         let reverse_drive_excessive =
             Instruction::Drive(Operand::Value(excessive_reverse_velocity));
         let result = processor.process(
@@ -493,9 +634,8 @@ mod tests {
         assert_eq!(robot.drive.velocity, expected_max);
 
         // Test with a value lower than the minimum
-        let excessive_reverse_velocity = -1.0 * (config::MAX_DRIVE_UNITS_PER_TURN + 1.0);
-        let expected_min =
-            -1.0 * (config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR);
+        let excessive_reverse_velocity = -(config::MAX_DRIVE_UNITS_PER_TURN + 1.0);
+        let expected_min = -(config::MAX_DRIVE_UNITS_PER_TURN * config::DRIVE_VELOCITY_FACTOR);
         let reverse_drive_excessive =
             Instruction::Drive(Operand::Value(excessive_reverse_velocity));
         let result = processor.process(
@@ -31,6 +31,7 @@ impl InstructionProcessor for ControlFlowOperations {
                 | Instruction::Call(_)
                 | Instruction::Ret
                 | Instruction::Loop(_)
+                | Instruction::JmpReg(_)
         )
     }
 
@@ -277,6 +278,27 @@ impl InstructionProcessor for ControlFlowOperations {
                 }
                 Ok(())
             }
+            Instruction::JmpReg(reg) => {
+                let target = robot
+                    .vm_state
+                    .registers
+                    .get(*reg)
+                    .map_err(|_| VMFault::InvalidRegister)?;
+                crate::debug_instructions!(
+                    robot.id,
+                    robot.vm_state.turn,
+                    robot.vm_state.cycle,
+                    "JmpReg: {:?} = {:.4}. Jumping there",
+                    reg,
+                    target
+                );
+                // Only whole indices of instructions in the program are valid targets
+                if target < 0.0 || target.fract() != 0.0 || target >= robot.program.len() as f64 {
+                    return Err(VMFault::InvalidInstruction);
+                }
+                robot.vm_state.ip = target as usize;
+                Ok(())
+            }
             _ => Err(VMFault::InvalidInstruction),
         }
     }
@@ -743,6 +765,40 @@ mod tests {
 
     // Call and return tests
 
+    #[test]
+    fn test_jmp_reg() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = ControlFlowOperations::new();
+        let all_robots = vec![];
+        robot.program = std::sync::Arc::from(vec![Instruction::Nop; 8]);
+
+        robot.vm_state.registers.set(Register::D0, 7.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::JmpReg(Register::D0),
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.ip, 7);
+
+        // Negative, fractional, past the end of the program and NaN all fault
+        for target in [-1.0, 2.5, 8.0, f64::NAN] {
+            robot.vm_state.ip = 3;
+            robot.vm_state.registers.set(Register::D0, target).unwrap();
+            let result = processor.process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::JmpReg(Register::D0),
+                &mut command_queue,
+            );
+            assert!(matches!(result, Err(VMFault::InvalidInstruction)));
+            assert_eq!(robot.vm_state.ip, 3);
+        }
+    }
+
     #[test]
     fn test_call() {
         let (mut robot, arena, mut command_queue) = setup();
@@ -22,6 +22,12 @@ pub struct InstructionExecutor {
     processors: Vec<Box<dyn InstructionProcessor>>,
 }
 
+impl Default for InstructionExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InstructionExecutor {
     /// Create a new executor with all processors registered
     pub fn new() -> Self {
@@ -87,8 +93,8 @@ impl InstructionExecutor {
     where
         F: FnMut(u32) -> Option<(Point, RobotStatus)>,
     {
-        // Special case for Scan which needs access to robot IDs
-        if matches!(instr, Instruction::Scan) {
+        // Special case for Scan and Sweep, which need access to robot IDs
+        if matches!(instr, Instruction::Scan | Instruction::Sweep) {
             return super::combat_ops::process_by_id(
                 robot,
                 get_robot_info,
@@ -114,7 +120,7 @@ mod tests {
     use crate::vm::instruction::Instruction;
     use crate::vm::operand::Operand;
     use crate::vm::registers::Register;
-    use std::collections::VecDeque;
+    use std::collections::{HashSet, VecDeque};
 
     fn setup_test_vm() -> (Robot, Arena, VecDeque<ArenaCommand>) {
         let arena = Arena::new();
@@ -143,6 +149,157 @@ mod tests {
         executor.execute_instruction(robot, &all_robots, arena, instruction, command_queue)
     }
 
+    // One instance of every Instruction variant
+    fn instruction_samples() -> Vec<Instruction> {
+        let v = || Operand::Value(1.0);
+        vec![
+            Instruction::Push(v()),
+            Instruction::Pop(Register::D0),
+            Instruction::PopDiscard,
+            Instruction::Dup,
+            Instruction::Swap,
+            Instruction::Over,
+            Instruction::Rot,
+            Instruction::Pick(v()),
+            Instruction::Mov(Register::D0, v()),
+            Instruction::MovC(Register::D0, v(), v(), v()),
+            Instruction::MovIdx(v(), v()),
+            Instruction::LodIdx(Register::D0, v()),
+            Instruction::Cmp(v(), v()),
+            Instruction::Lod(Register::D0),
+            Instruction::Sto(v()),
+            Instruction::Add,
+            Instruction::Sub,
+            Instruction::Mul,
+            Instruction::Div,
+            Instruction::Mod,
+            Instruction::Divmod,
+            Instruction::Min,
+            Instruction::Max,
+            Instruction::Pow,
+            Instruction::Sqrt,
+            Instruction::Log,
+            Instruction::Sin,
+            Instruction::Cos,
+            Instruction::Tan,
+            Instruction::Asin,
+            Instruction::Acos,
+            Instruction::Atan,
+            Instruction::Atan2,
+            Instruction::Abs,
+            Instruction::Neg,
+            Instruction::Floor,
+            Instruction::Ceil,
+            Instruction::Round,
+            Instruction::AddOp(v(), v()),
+            Instruction::SubOp(v(), v()),
+            Instruction::MulOp(v(), v()),
+            Instruction::DivOp(v(), v()),
+            Instruction::ModOp(v(), v()),
+            Instruction::MinOp(v(), v()),
+            Instruction::MaxOp(v(), v()),
+            Instruction::ClampOp(v(), v(), v()),
+            Instruction::RandNormal(v(), v()),
+            Instruction::PowOp(v(), v()),
+            Instruction::SqrtOp(v()),
+            Instruction::LogOp(v()),
+            Instruction::SinOp(v()),
+            Instruction::CosOp(v()),
+            Instruction::TanOp(v()),
+            Instruction::AsinOp(v()),
+            Instruction::AcosOp(v()),
+            Instruction::AtanOp(v()),
+            Instruction::Atan2Op(v(), v()),
+            Instruction::AbsOp(v()),
+            Instruction::NegOp(v()),
+            Instruction::FloorOp(v()),
+            Instruction::CeilOp(v()),
+            Instruction::RoundOp(v()),
+            Instruction::And,
+            Instruction::Or,
+            Instruction::Xor,
+            Instruction::Not,
+            Instruction::Shl,
+            Instruction::Shr,
+            Instruction::Rol,
+            Instruction::Ror,
+            Instruction::AndOp(v(), v()),
+            Instruction::OrOp(v(), v()),
+            Instruction::XorOp(v(), v()),
+            Instruction::NotOp(v()),
+            Instruction::ShlOp(v(), v()),
+            Instruction::ShrOp(v(), v()),
+            Instruction::RolOp(v(), v()),
+            Instruction::RorOp(v(), v()),
+            Instruction::Jmp(0),
+            Instruction::Jz(0),
+            Instruction::Jnz(0),
+            Instruction::Jl(0),
+            Instruction::Jle(0),
+            Instruction::Jg(0),
+            Instruction::Jge(0),
+            Instruction::Call(0),
+            Instruction::Ret,
+            Instruction::Loop(0),
+            Instruction::JmpReg(Register::D0),
+            Instruction::Select(v()),
+            Instruction::Deselect,
+            Instruction::Whoami,
+            Instruction::Rotate(v()),
+            Instruction::Drive(v()),
+            Instruction::Fire(v()),
+            Instruction::Beam(v()),
+            Instruction::Mortar(v()),
+            Instruction::Scan,
+            Instruction::Detonate(v()),
+            Instruction::Nop,
+            Instruction::Dbg(v()),
+            Instruction::Sleep(v()),
+            Instruction::Halt,
+            Instruction::Yield,
+            Instruction::RandRange(v(), v()),
+            Instruction::FireBounce(v(), v()),
+            Instruction::Shield(v()),
+            Instruction::Mine(v()),
+            Instruction::Sweep,
+        ]
+    }
+
+    // Number of Instruction variants. Instruction::cycle_cost matches every variant
+    // without a wildcard, so a new variant fails to compile there first; bump this
+    // and add it to instruction_samples at the same time.
+    const INSTRUCTION_VARIANTS: usize = 109;
+
+    #[test]
+    fn test_every_instruction_has_exactly_one_processor() {
+        let samples = instruction_samples();
+        let variants: HashSet<_> = samples.iter().map(std::mem::discriminant).collect();
+        assert_eq!(
+            variants.len(),
+            samples.len(),
+            "instruction_samples lists a variant twice"
+        );
+        assert_eq!(
+            variants.len(),
+            INSTRUCTION_VARIANTS,
+            "instruction_samples must list every Instruction variant"
+        );
+
+        let executor = InstructionExecutor::new();
+        for instruction in &samples {
+            let claims = executor
+                .processors
+                .iter()
+                .filter(|processor| processor.can_process(instruction))
+                .count();
+            assert_eq!(
+                claims, 1,
+                "{:?} is claimed by {} processors",
+                instruction, claims
+            );
+        }
+    }
+
     #[test]
     fn test_stack_operations_delegation() {
         let arena = Arena::new();
@@ -6,8 +6,11 @@ use std::collections::VecDeque;
 use super::processor::InstructionProcessor;
 use crate::vm::error::VMFault;
 use crate::vm::instruction::Instruction;
+use crate::vm::numeric::operand_to_cycles;
+use crate::vm::registers::Register;
+use rand::Rng;
 
-/// Processor for miscellaneous operations like Nop and Dbg
+/// Processor for miscellaneous operations like Nop, Dbg and Halt
 pub struct MiscellaneousOperations;
 
 impl MiscellaneousOperations {
@@ -20,7 +23,12 @@ impl InstructionProcessor for MiscellaneousOperations {
     fn can_process(&self, instruction: &Instruction) -> bool {
         matches!(
             instruction,
-            Instruction::Nop | Instruction::Dbg(_) | Instruction::Sleep(_)
+            Instruction::Nop
+                | Instruction::Dbg(_)
+                | Instruction::Sleep(_)
+                | Instruction::Halt
+                | Instruction::Yield
+                | Instruction::RandRange(_, _)
         )
     }
 
@@ -50,18 +58,43 @@ impl InstructionProcessor for MiscellaneousOperations {
                     "DBG instruction: {}",
                     val
                 );
+                robot.debug_output.push(val);
 
                 // Advance IP and return
                 robot.vm_state.advance_ip();
                 Ok(())
             }
             Instruction::Sleep(op) => {
-                let cycles = op.get_value(&robot.vm_state)?.max(1.0) as u32;
+                let cycles = operand_to_cycles(op.get_value(&robot.vm_state)?)?;
                 // Set the remaining cycles for this instruction (minus one for the current cycle)
                 robot.vm_state.instruction_cycles_remaining = cycles - 1;
                 // Only advance IP after sleep completes (handled by VM cycle logic)
                 Ok(())
             }
+            Instruction::Yield => {
+                // The wait for the rest of the turn comes from the instruction's cycle cost
+                robot.vm_state.advance_ip();
+                Ok(())
+            }
+            Instruction::RandRange(lo_op, hi_op) => {
+                let lo = lo_op.get_value(&robot.vm_state)?;
+                let hi = hi_op.get_value(&robot.vm_state)?;
+                // The RNG can only sample between finite, increasing bounds a finite span apart
+                if !lo.is_finite() || !hi.is_finite() || lo >= hi || !(hi - lo).is_finite() {
+                    return Err(VMFault::InvalidInstruction);
+                }
+                let value = robot.seeded_rng.gen_range(lo..hi);
+                robot
+                    .vm_state
+                    .registers
+                    .set(Register::Result, value)
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::Halt => {
+                // Stop running instructions for good; components keep their last orders
+                robot.vm_state.halted = true;
+                Ok(())
+            }
             _ => Err(VMFault::InvalidInstruction),
         }
     }
@@ -99,6 +132,12 @@ mod tests {
         assert!(processor.can_process(&Instruction::Nop));
         assert!(processor.can_process(&Instruction::Dbg(Operand::Value(1.0))));
         assert!(processor.can_process(&Instruction::Sleep(Operand::Value(1.0))));
+        assert!(processor.can_process(&Instruction::Halt));
+        assert!(processor.can_process(&Instruction::Yield));
+        assert!(processor.can_process(&Instruction::RandRange(
+            Operand::Value(0.0),
+            Operand::Value(1.0)
+        )));
 
         // Should not process other operations
         assert!(!processor.can_process(&Instruction::Push(Operand::Value(1.0))));
@@ -175,6 +214,9 @@ mod tests {
 
         // Command queue should still be empty
         assert_eq!(command_queue.len(), 0);
+
+        // Value is captured for the on-screen debug console
+        assert_eq!(robot.debug_output, vec![123.0]);
     }
 
     #[test]
@@ -211,6 +253,28 @@ mod tests {
 
         // Command queue should still be empty (Sleep only waits, doesn't queue commands)
         assert_eq!(command_queue.len(), 0);
+
+        // Durations convert like other integer operands: truncated, at least one cycle
+        let sleep = Instruction::Sleep(Operand::Value(2.7));
+        assert!(
+            processor
+                .process(&mut robot, &all_robots, &arena, &sleep, &mut command_queue)
+                .is_ok()
+        );
+        assert_eq!(robot.vm_state.instruction_cycles_remaining, 1);
+        assert_eq!(sleep.cycle_cost(&robot.vm_state), 2);
+        let sleep = Instruction::Sleep(Operand::Value(-4.0));
+        assert!(
+            processor
+                .process(&mut robot, &all_robots, &arena, &sleep, &mut command_queue)
+                .is_ok()
+        );
+        assert_eq!(robot.vm_state.instruction_cycles_remaining, 0);
+        let sleep = Instruction::Sleep(Operand::Value(f64::INFINITY));
+        assert_eq!(
+            processor.process(&mut robot, &all_robots, &arena, &sleep, &mut command_queue),
+            Err(VMFault::NumericError)
+        );
     }
 
     #[test]
@@ -227,4 +291,58 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), VMFault::InvalidInstruction));
     }
+
+    #[test]
+    fn test_randrange() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = MiscellaneousOperations::new();
+        let all_robots = vec![];
+        let randrange = Instruction::RandRange(Operand::Value(-90.0), Operand::Value(270.0));
+
+        robot.seeded_rng = crate::robot::robot_rng(7, robot.id);
+        let samples: Vec<f64> = (0..10_000)
+            .map(|_| {
+                processor
+                    .process(
+                        &mut robot,
+                        &all_robots,
+                        &arena,
+                        &randrange,
+                        &mut command_queue,
+                    )
+                    .unwrap();
+                robot.vm_state.registers.get(Register::Result).unwrap()
+            })
+            .collect();
+        assert!(samples.iter().all(|s| (-90.0..270.0).contains(s)));
+        // Uniform: the mean sits mid-range and every quarter of the range gets its share
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean - 90.0).abs() < 5.0, "mean {}", mean);
+        for quarter in 0..4 {
+            let lo = -90.0 + 90.0 * quarter as f64;
+            let count = samples
+                .iter()
+                .filter(|s| (lo..lo + 90.0).contains(*s))
+                .count();
+            assert!(
+                (2300..2700).contains(&count),
+                "quarter {} got {}",
+                quarter,
+                count
+            );
+        }
+
+        for (lo, hi) in [
+            (1.0, 1.0),
+            (2.0, 1.0),
+            (f64::NAN, 1.0),
+            (0.0, f64::INFINITY),
+            (-1e308, 1e308),
+        ] {
+            let bad = Instruction::RandRange(Operand::Value(lo), Operand::Value(hi));
+            let result =
+                processor.process(&mut robot, &all_robots, &arena, &bad, &mut command_queue);
+            assert_eq!(result, Err(VMFault::InvalidInstruction));
+        }
+    }
 }
@@ -6,6 +6,8 @@ use std::collections::VecDeque;
 use super::processor::InstructionProcessor;
 use crate::vm::error::VMFault;
 use crate::vm::instruction::Instruction;
+use crate::vm::numeric::operand_to_index;
+use crate::vm::registers::Register;
 
 /// Processor for register manipulation instructions
 pub struct RegisterOperations;
@@ -21,6 +23,9 @@ impl InstructionProcessor for RegisterOperations {
         matches!(
             instruction,
             Instruction::Mov(_, _)
+                | Instruction::MovC(_, _, _, _)
+                | Instruction::MovIdx(_, _)
+                | Instruction::LodIdx(_, _)
                 | Instruction::Lod(_)
                 | Instruction::Sto(_)
                 | Instruction::Cmp(_, _)
@@ -45,20 +50,64 @@ impl InstructionProcessor for RegisterOperations {
                     .map_err(|_| VMFault::PermissionError);
 
                 // Special handling for @d7 register
-                if let Ok(()) = result {
-                    if let crate::vm::registers::Register::D7 = reg {
-                        crate::debug_instructions!(
-                            robot.id,
-                            robot.vm_state.turn,
-                            robot.vm_state.cycle,
-                            "Mov: Setting @d7 to {:.1}",
-                            val
-                        );
-                    }
+                if let Ok(()) = result
+                    && let crate::vm::registers::Register::D7 = reg
+                {
+                    crate::debug_instructions!(
+                        robot.id,
+                        robot.vm_state.turn,
+                        robot.vm_state.cycle,
+                        "Mov: Setting @d7 to {:.1}",
+                        val
+                    );
                 }
 
                 result
             }
+            Instruction::MovC(reg, op, lo_op, hi_op) => {
+                // Clamp into [lo, hi]; reversed bounds are swapped rather than faulting
+                let val = op.get_value(&robot.vm_state)?;
+                let lo = lo_op.get_value(&robot.vm_state)?;
+                let hi = hi_op.get_value(&robot.vm_state)?;
+                let (lo, hi) = if lo <= hi { (lo, hi) } else { (hi, lo) };
+                robot
+                    .vm_state
+                    .registers
+                    .set(*reg, val.max(lo).min(hi))
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::MovIdx(index_op, op) => {
+                // Write to the data register selected by index, faulting outside @d0..@d18
+                let index = index_op.get_value(&robot.vm_state)?;
+                let reg = operand_to_index(index)
+                    .ok()
+                    .and_then(Register::data)
+                    .ok_or(VMFault::InvalidRegister)?;
+                let val = op.get_value(&robot.vm_state)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(reg, val)
+                    .map_err(|_| VMFault::PermissionError)
+            }
+            Instruction::LodIdx(dest, index_op) => {
+                // Read from the data register selected by index, faulting outside @d0..@d18
+                let index = index_op.get_value(&robot.vm_state)?;
+                let src = operand_to_index(index)
+                    .ok()
+                    .and_then(Register::data)
+                    .ok_or(VMFault::InvalidRegister)?;
+                let val = robot
+                    .vm_state
+                    .registers
+                    .get(src)
+                    .map_err(|_| VMFault::InvalidRegister)?;
+                robot
+                    .vm_state
+                    .registers
+                    .set(*dest, val)
+                    .map_err(|_| VMFault::PermissionError)
+            }
             Instruction::Lod(reg) => {
                 // Load from memory at @index to register
                 let value = robot.vm_state.load_memory_at_index()?;
@@ -216,6 +265,100 @@ mod tests {
         assert!(matches!(result.unwrap_err(), VMFault::PermissionError));
     }
 
+    #[test]
+    fn test_movc_clamps_value() {
+        let (mut robot, arena, mut command_queue) = setup_vm_state();
+        let processor = RegisterOperations::new();
+
+        for (source, expected) in [
+            ("movc @d0 15 0 10", 10.0),
+            ("movc @d0 -3 0 10", 0.0),
+            ("movc @d0 4.5 0 10", 4.5),
+        ] {
+            let program = crate::vm::parser::parse_assembly(source, None).unwrap();
+            let result = processor.process(
+                &mut robot,
+                &[],
+                &arena,
+                &program.instructions[0],
+                &mut command_queue,
+            );
+            assert!(result.is_ok());
+            assert_eq!(
+                robot.vm_state.registers.get(Register::D0).unwrap(),
+                expected
+            );
+        }
+
+        // Read-only destinations fault like mov
+        let result = processor.process(
+            &mut robot,
+            &[],
+            &arena,
+            &Instruction::MovC(
+                Register::Turn,
+                Operand::Value(5.0),
+                Operand::Value(0.0),
+                Operand::Value(10.0),
+            ),
+            &mut command_queue,
+        );
+        assert_eq!(result, Err(VMFault::PermissionError));
+    }
+
+    #[test]
+    fn test_indexed_data_register_access() {
+        let (mut robot, arena, mut command_queue) = setup_vm_state();
+        let processor = RegisterOperations::new();
+
+        // Write @d5 through an index of 5
+        let result = processor.process(
+            &mut robot,
+            &[],
+            &arena,
+            &Instruction::MovIdx(Operand::Value(5.0), Operand::Value(77.0)),
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.registers.get(Register::D5).unwrap(), 77.0);
+
+        // Read it back through an index held in a register
+        robot.vm_state.registers.set(Register::D1, 5.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &[],
+            &arena,
+            &Instruction::LodIdx(Register::D2, Operand::Register(Register::D1)),
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.registers.get(Register::D2).unwrap(), 77.0);
+
+        // Indices outside @d0..@d18 fault
+        for bad_index in [99.0, 19.0, -1.0, 2.5] {
+            assert_eq!(
+                processor.process(
+                    &mut robot,
+                    &[],
+                    &arena,
+                    &Instruction::MovIdx(Operand::Value(bad_index), Operand::Value(1.0)),
+                    &mut command_queue,
+                ),
+                Err(VMFault::InvalidRegister)
+            );
+            assert_eq!(
+                processor.process(
+                    &mut robot,
+                    &[],
+                    &arena,
+                    &Instruction::LodIdx(Register::D0, Operand::Value(bad_index)),
+                    &mut command_queue,
+                ),
+                Err(VMFault::InvalidRegister)
+            );
+        }
+    }
+
     #[test]
     fn test_lod_instruction() {
         let (mut robot, arena, mut command_queue) = setup_vm_state();
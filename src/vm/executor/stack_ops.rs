@@ -25,6 +25,9 @@ impl InstructionProcessor for StackOperations {
                 | Instruction::PopDiscard
                 | Instruction::Dup
                 | Instruction::Swap
+                | Instruction::Over
+                | Instruction::Rot
+                | Instruction::Pick(_)
         )
     }
 
@@ -69,6 +72,29 @@ impl InstructionProcessor for StackOperations {
                 StackError::Underflow => VMFault::StackUnderflow,
                 StackError::Overflow => VMFault::StackOverflow,
             }),
+            Instruction::Over => robot.vm_state.stack.over().map_err(|e| match e {
+                StackError::Overflow => VMFault::StackOverflow,
+                StackError::Underflow => VMFault::StackUnderflow,
+            }),
+            Instruction::Rot => robot.vm_state.stack.rot().map_err(|e| match e {
+                StackError::Underflow => VMFault::StackUnderflow,
+                StackError::Overflow => VMFault::StackOverflow,
+            }),
+            Instruction::Pick(op) => {
+                let depth = op.get_value(&robot.vm_state)?;
+                // Depths must be whole, non-negative numbers
+                if !depth.is_finite() || depth < 0.0 || depth.fract() != 0.0 {
+                    return Err(VMFault::InvalidOperand);
+                }
+                robot
+                    .vm_state
+                    .stack
+                    .pick(depth as usize)
+                    .map_err(|e| match e {
+                        StackError::Overflow => VMFault::StackOverflow,
+                        StackError::Underflow => VMFault::StackUnderflow,
+                    })
+            }
             _ => Err(VMFault::InvalidInstruction),
         }
     }
@@ -111,6 +137,9 @@ mod tests {
         assert!(processor.can_process(&Instruction::PopDiscard));
         assert!(processor.can_process(&Instruction::Dup));
         assert!(processor.can_process(&Instruction::Swap));
+        assert!(processor.can_process(&Instruction::Over));
+        assert!(processor.can_process(&Instruction::Rot));
+        assert!(processor.can_process(&Instruction::Pick(Operand::Value(0.0))));
 
         // Should not process non-stack operations
         assert!(!processor.can_process(&Instruction::Nop));
@@ -271,4 +300,111 @@ mod tests {
         assert_eq!(robot.vm_state.stack.pop().unwrap(), 1.0);
         assert_eq!(robot.vm_state.stack.pop().unwrap(), 2.0);
     }
+
+    #[test]
+    fn test_over() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = StackOperations::new();
+        let all_robots = vec![];
+
+        robot.vm_state.stack.push(1.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Over,
+            &mut command_queue,
+        );
+        assert!(matches!(result.unwrap_err(), VMFault::StackUnderflow));
+
+        robot.vm_state.stack.push(2.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Over,
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 1.0);
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 2.0);
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_rot() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = StackOperations::new();
+        let all_robots = vec![];
+
+        robot.vm_state.stack.push(1.0).unwrap();
+        robot.vm_state.stack.push(2.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Rot,
+            &mut command_queue,
+        );
+        assert!(matches!(result.unwrap_err(), VMFault::StackUnderflow));
+
+        robot.vm_state.stack.push(3.0).unwrap();
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Rot,
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        // The third value from the top is now on top
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 1.0);
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 3.0);
+        assert_eq!(robot.vm_state.stack.pop().unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_pick() {
+        let (mut robot, arena, mut command_queue) = setup();
+        let processor = StackOperations::new();
+        let all_robots = vec![];
+
+        robot.vm_state.stack.push(10.0).unwrap();
+        robot.vm_state.stack.push(20.0).unwrap();
+        robot.vm_state.stack.push(30.0).unwrap();
+        robot.vm_state.registers.set(Register::D0, 2.0).unwrap();
+
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Pick(Operand::Register(Register::D0)),
+            &mut command_queue,
+        );
+        assert!(result.is_ok());
+        assert_eq!(robot.vm_state.stack.view(), &[10.0, 20.0, 30.0, 10.0]);
+
+        // Deeper than the stack
+        let result = processor.process(
+            &mut robot,
+            &all_robots,
+            &arena,
+            &Instruction::Pick(Operand::Value(4.0)),
+            &mut command_queue,
+        );
+        assert!(matches!(result.unwrap_err(), VMFault::StackUnderflow));
+
+        // Not a whole, non-negative depth
+        for depth in [-1.0, 0.5] {
+            let result = processor.process(
+                &mut robot,
+                &all_robots,
+                &arena,
+                &Instruction::Pick(Operand::Value(depth)),
+                &mut command_queue,
+            );
+            assert!(matches!(result.unwrap_err(), VMFault::InvalidOperand));
+        }
+        assert_eq!(robot.vm_state.stack.view().len(), 4);
+    }
 }
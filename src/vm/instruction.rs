@@ -1,4 +1,6 @@
+use crate::config;
 use crate::vm::executor::Operand;
+use crate::vm::numeric::operand_to_cycles;
 use crate::vm::registers::Register;
 use crate::vm::state::VMState;
 
@@ -10,8 +12,14 @@ pub enum Instruction {
     PopDiscard,
     Dup,
     Swap,
+    Over,
+    Rot,
+    Pick(Operand),
     // Register ops
     Mov(Register, Operand),
+    MovC(Register, Operand, Operand, Operand), // Write value clamped to [lo, hi]
+    MovIdx(Operand, Operand),                  // Write value to @d<index>
+    LodIdx(Register, Operand),                 // Read @d<index> into register
     Cmp(Operand, Operand),
     // Memory ops
     Lod(Register),
@@ -23,6 +31,8 @@ pub enum Instruction {
     Div,
     Mod,
     Divmod,
+    Min,
+    Max,
     Pow,
     Sqrt,
     Log,
@@ -34,12 +44,20 @@ pub enum Instruction {
     Atan,
     Atan2,
     Abs,
+    Neg,
+    Floor,
+    Ceil,
+    Round,
     // Math ops (operand form -> @result)
     AddOp(Operand, Operand),
     SubOp(Operand, Operand),
     MulOp(Operand, Operand),
     DivOp(Operand, Operand),
     ModOp(Operand, Operand),
+    MinOp(Operand, Operand),
+    MaxOp(Operand, Operand),
+    ClampOp(Operand, Operand, Operand), // value, lo, hi
+    RandNormal(Operand, Operand),       // mean, stddev
     PowOp(Operand, Operand),
     SqrtOp(Operand),
     LogOp(Operand),
@@ -51,6 +69,10 @@ pub enum Instruction {
     AtanOp(Operand),
     Atan2Op(Operand, Operand),
     AbsOp(Operand),
+    NegOp(Operand),
+    FloorOp(Operand),
+    CeilOp(Operand),
+    RoundOp(Operand),
     // Binary ops (stack-based)
     And,
     Or,
@@ -58,6 +80,8 @@ pub enum Instruction {
     Not,
     Shl,
     Shr,
+    Rol,
+    Ror,
     // Binary ops (operand form -> @result)
     AndOp(Operand, Operand),
     OrOp(Operand, Operand),
@@ -65,6 +89,8 @@ pub enum Instruction {
     NotOp(Operand),
     ShlOp(Operand, Operand),
     ShrOp(Operand, Operand),
+    RolOp(Operand, Operand),
+    RorOp(Operand, Operand),
     // Control flow
     Jmp(usize),
     Jz(usize),
@@ -76,54 +102,79 @@ pub enum Instruction {
     Call(usize),
     Ret,
     Loop(usize),
+    JmpReg(Register), // Jump to the instruction index held in a register
     // Component ops
     Select(Operand),
     Deselect,
+    Whoami, // Pushes the selected component id
     Rotate(Operand),
     Drive(Operand),
+    Shield(Operand), // Nonzero turns the shield on, zero turns it off
     // Combat ops
     Fire(Operand),
+    FireBounce(Operand, Operand), // Power, wall bounces
+    Beam(Operand),
+    Mortar(Operand),
     Scan,
+    Sweep, // Scan in every direction, ignoring the scanner's field of view
+    Detonate(Operand),
+    Mine(Operand), // Power level of the mine laid at the robot's position
     // Misc
     Nop,
     Dbg(Operand),
     Sleep(Operand),
+    Halt,
+    Yield,
+    RandRange(Operand, Operand), // lo, hi
 }
 
 impl Instruction {
     /// Returns the number of simulation cycles this instruction takes to execute.
     pub fn cycle_cost(&self, vm_state: &VMState) -> u32 {
         use crate::vm::executor::Instruction::{
-            Abs, Acos, Add, And, Asin, Atan, Atan2, Cos, Deselect, Div, Divmod, Dup, Log, Mod, Mul,
-            Nop, Not, Or, PopDiscard, Pow, Ret, Scan, Shl, Shr, Sin, Sqrt, Sub, Swap, Tan, Xor,
+            Abs, Acos, Add, And, Asin, Atan, Atan2, Ceil, Cos, Deselect, Div, Divmod, Dup, Floor,
+            Halt, Log, Max, Min, Mod, Mul, Neg, Nop, Not, Or, PopDiscard, Pow, Ret, Rol, Ror,
+            Round, Scan, Shl, Shr, Sin, Sqrt, Sub, Swap, Sweep, Tan, Whoami, Xor, Yield,
         };
         use Instruction::*;
         match self {
             // 1 Cycle
-            Push(_) | Pop(_) | PopDiscard | Dup | Swap => 1,
-            Mov(_, _) | Cmp(_, _) => 1,
+            Push(_) | Pop(_) | PopDiscard | Dup | Swap | Over | Rot | Pick(_) => 1,
+            Mov(_, _) | MovC(_, _, _, _) | Cmp(_, _) => 1,
+            MovIdx(_, _) | LodIdx(_, _) => 1,
             Lod(_) | Sto(_) => 1,
-            And | Or | Xor | Not | Shl | Shr => 1,
+            And | Or | Xor | Not | Shl | Shr | Rol | Ror => 1,
             Jmp(_) | Jz(_) | Jnz(_) | Jl(_) | Jle(_) | Jg(_) | Jge(_) => 1,
-            Select(_) | Deselect | Drive(_) => 1,
-            Nop | Dbg(_) => 1,
-            Loop(_) => 1,
+            Select(_) | Deselect | Whoami | Drive(_) | Shield(_) => 1,
+            Nop | Dbg(_) | Halt => 1,
+            Loop(_) | JmpReg(_) => 1,
 
             // Arithmetic Ops (Stack Form)
-            Add | Sub | Mul | Div | Mod | Divmod | Abs => 1,
+            Add | Sub | Mul | Div | Mod | Divmod | Abs | Neg | Floor | Ceil | Round | Min | Max => {
+                1
+            }
             Pow | Sqrt | Log => 2,
             Sin | Cos | Tan => 2,
             Asin | Acos | Atan | Atan2 => 2,
 
             // Arithmetic Ops (Operand Form)
             AddOp(_, _) | SubOp(_, _) | MulOp(_, _) | DivOp(_, _) | ModOp(_, _) => 1,
-            AbsOp(_) => 1,
+            MinOp(_, _) | MaxOp(_, _) | ClampOp(_, _, _) => 1,
+            AbsOp(_) | NegOp(_) | FloorOp(_) | CeilOp(_) | RoundOp(_) => 1,
             PowOp(_, _) | SqrtOp(_) | LogOp(_) => 2,
+            RandNormal(_, _) | RandRange(_, _) => 2,
             SinOp(_) | CosOp(_) | TanOp(_) => 2,
             AsinOp(_) | AcosOp(_) | AtanOp(_) | Atan2Op(_, _) => 2,
 
             // Binary Ops (Operand Form)
-            AndOp(_, _) | OrOp(_, _) | XorOp(_, _) | NotOp(_) | ShlOp(_, _) | ShrOp(_, _) => 1,
+            AndOp(_, _)
+            | OrOp(_, _)
+            | XorOp(_, _)
+            | NotOp(_)
+            | ShlOp(_, _)
+            | ShrOp(_, _)
+            | RolOp(_, _)
+            | RorOp(_, _) => 1,
 
             // Control Flow / Subroutines
             Call(_) | Ret => 2,
@@ -144,16 +195,27 @@ impl Instruction {
             }
 
             // 3 Cycles
-            Fire(_) => 3,
+            Fire(_) | FireBounce(_, _) | Beam(_) | Mortar(_) | Mine(_) => 3,
 
             // 1 Cycles
             Scan => 1,
 
+            // 4 Cycles: looking all the way around takes longer than a scan
+            Sweep => 4,
+
+            // 1 Cycles
+            Detonate(_) => 1,
+
+            // The rest of the current turn, so the next instruction runs at its end
+            Yield => config::CYCLES_PER_TURN
+                .saturating_sub(vm_state.cycle)
+                .max(1),
+
             // 1 Cycles
             Sleep(op) => {
                 // Try to get the value from the operand, default to 1 if invalid
                 op.get_value(vm_state)
-                    .map(|v| v.max(1.0) as u32)
+                    .and_then(operand_to_cycles)
                     .unwrap_or(1)
             }
         }
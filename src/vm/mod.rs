@@ -3,6 +3,7 @@
 pub mod error;
 pub mod executor;
 pub mod instruction;
+pub mod numeric;
 mod operand;
 pub mod parser;
 pub mod registers;
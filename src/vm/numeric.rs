@@ -0,0 +1,102 @@
+// Float-to-integer conversion shared by the VM. All VM values are f64; these
+// are the only places they become integers, so every instruction applies the
+// same rounding and range rules.
+//
+// Rules:
+// - Indices (`operand_to_index`): memory/@index positions, data register
+//   indices and component ids. The value must be a finite whole number in
+//   0..=2^53; fractions are NOT rounded. Anything else is a NumericError.
+//   (-0.0 counts as 0.) Callers then apply their own bounds check.
+// - Integers (`operand_to_int`): bitwise operands and shift counts. The value
+//   is truncated toward zero and must be finite and within +/-2^53 (the range
+//   an f64 holds exactly), otherwise it is a NumericError.
+// - Cycle counts (`operand_to_cycles`): `sleep` durations. Converted like
+//   integers, then raised to at least 1 and capped at u32::MAX.
+// - Bit patterns (`low_32_bits`): `rol`/`ror` work on the low 32 bits of an
+//   integer operand; the higher bits are dropped (two's complement wrapping).
+
+use crate::vm::error::VMFault;
+
+/// Largest integer magnitude an f64 holds exactly (2^53)
+pub const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Converts a VM value used as an index. See the module rules.
+pub fn operand_to_index(v: f64) -> Result<usize, VMFault> {
+    if !v.is_finite() || v.fract() != 0.0 || !(0.0..=MAX_SAFE_INTEGER).contains(&v) {
+        return Err(VMFault::NumericError);
+    }
+    Ok(v as usize)
+}
+
+/// Converts a VM value used as a signed integer, truncating toward zero. See the module rules.
+pub fn operand_to_int(v: f64) -> Result<i64, VMFault> {
+    if !v.is_finite() || v.abs() > MAX_SAFE_INTEGER {
+        return Err(VMFault::NumericError);
+    }
+    Ok(v.trunc() as i64)
+}
+
+/// Converts a VM value used as a cycle count. See the module rules.
+pub fn operand_to_cycles(v: f64) -> Result<u32, VMFault> {
+    let cycles = operand_to_int(v)?;
+    Ok(cycles.clamp(1, u32::MAX as i64) as u32)
+}
+
+/// The low 32 bits of an integer operand. See the module rules.
+pub fn low_32_bits(v: i64) -> u32 {
+    v as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operand_to_index() {
+        assert_eq!(operand_to_index(0.0), Ok(0));
+        assert_eq!(operand_to_index(-0.0), Ok(0));
+        assert_eq!(operand_to_index(42.0), Ok(42));
+        assert_eq!(operand_to_index(MAX_SAFE_INTEGER), Ok(1 << 53));
+
+        for bad in [
+            -1.0,
+            2.5,
+            -0.5,
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            MAX_SAFE_INTEGER * 2.0,
+        ] {
+            assert_eq!(operand_to_index(bad), Err(VMFault::NumericError), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_operand_to_int() {
+        assert_eq!(operand_to_int(7.0), Ok(7));
+        assert_eq!(operand_to_int(2.9), Ok(2));
+        assert_eq!(operand_to_int(-2.9), Ok(-2));
+        assert_eq!(operand_to_int(-MAX_SAFE_INTEGER), Ok(-(1 << 53)));
+
+        for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e20, -1e20] {
+            assert_eq!(operand_to_int(bad), Err(VMFault::NumericError), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_operand_to_cycles() {
+        assert_eq!(operand_to_cycles(3.0), Ok(3));
+        assert_eq!(operand_to_cycles(3.9), Ok(3));
+        assert_eq!(operand_to_cycles(0.0), Ok(1));
+        assert_eq!(operand_to_cycles(-5.0), Ok(1));
+        assert_eq!(operand_to_cycles(1e12), Ok(u32::MAX));
+        assert_eq!(operand_to_cycles(f64::NAN), Err(VMFault::NumericError));
+        assert_eq!(operand_to_cycles(f64::INFINITY), Err(VMFault::NumericError));
+    }
+
+    #[test]
+    fn test_low_32_bits() {
+        assert_eq!(low_32_bits(0x1_0000_0005), 5);
+        assert_eq!(low_32_bits(-1), u32::MAX);
+    }
+}
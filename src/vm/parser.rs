@@ -1,33 +1,160 @@
 // VM Assembly Parser: parses .rasm files, resolves labels/constants, produces instruction list
 
 use super::registers::Register;
+use crate::config;
+use crate::types::{RobotShape, ScannerSettings};
 use crate::vm::instruction::Instruction;
 use crate::vm::operand::Operand;
-use std::collections::HashMap;
+use crate::vm::state::DEFAULT_MEMORY_SIZE;
+use std::collections::{BTreeMap, HashMap};
 
 /// Error type for assembly parsing
 #[derive(Debug, Clone)]
 pub struct ParseError {
     pub line: usize,
+    pub column: usize, // 1-based column of the offending token, 0 when the error covers the whole line
     pub message: String,
 }
 
+impl ParseError {
+    /// "line N" or "line N, column M" when the column is known
+    pub fn location(&self) -> String {
+        if self.column > 0 {
+            format!("line {}, column {}", self.line, self.column)
+        } else {
+            format!("line {}", self.line)
+        }
+    }
+}
+
+/// The source line a statement came from, so operand, register and
+/// expression errors can point at the column of the offending token
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SourceLine<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> SourceLine<'a> {
+    pub(crate) fn new(number: usize, text: &'a str) -> Self {
+        SourceLine { number, text }
+    }
+
+    // 1-based column of `token`, which must be a slice of this line's text
+    // (0 if it isn't, e.g. for a token built up outside the line)
+    fn column_of(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize).wrapping_sub(start);
+        match self.text.get(..offset) {
+            Some(before) if offset + token.len() <= self.text.len() => before.chars().count() + 1,
+            _ => 0,
+        }
+    }
+
+    // Error at `token`'s column on this line
+    fn error_at(&self, token: &str, message: String) -> ParseError {
+        ParseError {
+            line: self.number,
+            column: self.column_of(token),
+            message,
+        }
+    }
+}
+
 /// Result of parsing an assembly program
 #[derive(Debug, Clone)]
 pub struct ParsedProgram {
     pub instructions: Vec<Instruction>,
+    pub data: Vec<f64>, // Initial memory contents from .data directives, starting at slot 0
+    pub shape: RobotShape, // Body shape from the .shape directive
+    pub scanner: ScannerSettings, // Scanner range and fov from the .scanner directive
+    pub references: SymbolReferences, // Where labels and constants are used, for xref reports
+}
+
+/// Label and constant names resolved during parsing, with the instruction
+/// indices that refer to them (the instructions themselves keep only the
+/// resolved index or value)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolReferences {
+    pub label_targets: BTreeMap<String, usize>, // Instruction index each label points to
+    pub labels: BTreeMap<String, Vec<usize>>,   // Jump/call/loop sites for every label
+    pub constants: BTreeMap<String, Vec<usize>>, // Operand uses for every .const and any used built-in
+}
+
+impl SymbolReferences {
+    /// Cross-reference report, one line per label then per constant
+    pub fn report_lines(&self) -> Vec<String> {
+        let sites = |indices: &[usize]| {
+            if indices.is_empty() {
+                "unused".to_string()
+            } else {
+                indices
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+        let mut lines = vec!["Labels:".to_string()];
+        for (name, indices) in &self.labels {
+            lines.push(format!(
+                "  {} (-> {}): {}",
+                name,
+                self.label_targets[name],
+                sites(indices)
+            ));
+        }
+        lines.push("Constants:".to_string());
+        for (name, indices) in &self.constants {
+            lines.push(format!("  {}: {}", name, sites(indices)));
+        }
+        lines
+    }
+}
+
+/// Parses a numeric literal: a decimal number (with an optional exponent, as
+/// in `6.674e-11`), or an integer written in hex (`0xFF`) or binary
+/// (`0b1010`), optionally with a leading minus sign. Digits may be grouped
+/// with `_` separators (`1_000_000`).
+fn parse_number(s: &str) -> Option<f64> {
+    let without_separators;
+    let s = if s.contains('_')
+        && s.trim_start_matches('-')
+            .starts_with(|c: char| c.is_ascii_digit())
+    {
+        without_separators = s.replace('_', "");
+        &without_separators
+    } else {
+        s // Not a number (e.g. a constant named `_1`), leave it alone
+    };
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, s),
+    };
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x" | "0X") => (16, &unsigned[2..]),
+        Some("0b" | "0B") => (2, &unsigned[2..]),
+        _ => return s.parse::<f64>().ok(),
+    };
+    if digits.starts_with('+') {
+        return None; // from_str_radix would accept a sign after the prefix
+    }
+    u64::from_str_radix(digits, radix)
+        .ok()
+        .map(|value| sign * value as f64)
 }
 
 /// Parse and evaluate a constant expression
-/// Supports basic math operations (+, -, *, /, %) and parentheses
+/// Supports basic math operations (+, -, *, /, %), parentheses and the
+/// functions min(a, b), max(a, b), clamp(x, lo, hi), sqrt(x) and abs(x)
 /// Example: "ARENA_WIDTH / 2" or "(VALUE_A + VALUE_B) * 1.5"
 fn parse_constant_expression(
     expr: &str,
     constants: &HashMap<String, f64>,
-    line: usize,
+    at: SourceLine,
 ) -> Result<f64, ParseError> {
     // First try to parse as a simple number for backward compatibility
-    if let Ok(val) = expr.parse::<f64>() {
+    if let Some(val) = parse_number(expr) {
         return Ok(val);
     }
 
@@ -38,36 +165,56 @@ fn parse_constant_expression(
 
     // Simple recursive descent parser for expressions
 
-    // Tokenize the expression - split by operators and parentheses while preserving them
-    let expr = expr
-        .replace("(", " ( ")
-        .replace(")", " ) ")
-        .replace("+", " + ")
-        .replace("-", " - ")
-        .replace("*", " * ")
-        .replace("/", " / ")
-        .replace("%", " % ");
-
-    let tokens: Vec<&str> = expr.split_whitespace().collect();
+    // Tokenize the expression - split by operators and parentheses while preserving them.
+    // Tokens stay slices of `expr` so errors can report their column.
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut token_start = None;
+    for (i, c) in expr.char_indices() {
+        // The sign of an exponent (`6.674e-11`) belongs to its number
+        let exponent_sign = matches!(c, '+' | '-')
+            && token_start.is_some_and(|start| {
+                let token = &expr[start..i];
+                token.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                    && !token.starts_with("0x")
+                    && !token.starts_with("0X")
+                    && token.ends_with(['e', 'E'])
+            });
+        if exponent_sign {
+            continue;
+        }
+        if c.is_whitespace() || "()+-*/%,".contains(c) {
+            if let Some(start) = token_start.take() {
+                tokens.push(&expr[start..i]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&expr[i..i + 1]);
+            }
+        } else if token_start.is_none() {
+            token_start = Some(i);
+        }
+    }
+    if let Some(start) = token_start {
+        tokens.push(&expr[start..]);
+    }
 
     // Define a recursive parsing function
     fn parse_expr(
         tokens: &[&str],
         pos: &mut usize,
         constants: &HashMap<String, f64>,
-        line: usize,
+        at: SourceLine,
     ) -> Result<f64, ParseError> {
-        let mut left = parse_term(tokens, pos, constants, line)?;
+        let mut left = parse_term(tokens, pos, constants, at)?;
 
         while *pos < tokens.len() {
             match tokens[*pos] {
                 "+" => {
                     *pos += 1;
-                    left += parse_term(tokens, pos, constants, line)?;
+                    left += parse_term(tokens, pos, constants, at)?;
                 }
                 "-" => {
                     *pos += 1;
-                    left -= parse_term(tokens, pos, constants, line)?;
+                    left -= parse_term(tokens, pos, constants, at)?;
                 }
                 _ => break,
             }
@@ -80,22 +227,24 @@ fn parse_constant_expression(
         tokens: &[&str],
         pos: &mut usize,
         constants: &HashMap<String, f64>,
-        line: usize,
+        at: SourceLine,
     ) -> Result<f64, ParseError> {
-        let mut left = parse_factor(tokens, pos, constants, line)?;
+        let line = at.number;
+        let mut left = parse_factor(tokens, pos, constants, at)?;
 
         while *pos < tokens.len() {
             match tokens[*pos] {
                 "*" => {
                     *pos += 1;
-                    left *= parse_factor(tokens, pos, constants, line)?;
+                    left *= parse_factor(tokens, pos, constants, at)?;
                 }
                 "/" => {
                     *pos += 1;
-                    let right = parse_factor(tokens, pos, constants, line)?;
+                    let right = parse_factor(tokens, pos, constants, at)?;
                     if right == 0.0 {
                         return Err(ParseError {
                             line,
+                            column: 0,
                             message: "Division by zero in constant expression".to_string(),
                         });
                     }
@@ -103,10 +252,11 @@ fn parse_constant_expression(
                 }
                 "%" => {
                     *pos += 1;
-                    let right = parse_factor(tokens, pos, constants, line)?;
+                    let right = parse_factor(tokens, pos, constants, at)?;
                     if right == 0.0 {
                         return Err(ParseError {
                             line,
+                            column: 0,
                             message: "Modulo by zero in constant expression".to_string(),
                         });
                     }
@@ -123,11 +273,13 @@ fn parse_constant_expression(
         tokens: &[&str],
         pos: &mut usize,
         constants: &HashMap<String, f64>,
-        line: usize,
+        at: SourceLine,
     ) -> Result<f64, ParseError> {
+        let line = at.number;
         if *pos >= tokens.len() {
             return Err(ParseError {
                 line,
+                column: 0,
                 message: "Unexpected end of expression".to_string(),
             });
         }
@@ -137,59 +289,198 @@ fn parse_constant_expression(
 
         match token {
             "(" => {
-                let val = parse_expr(tokens, pos, constants, line)?;
+                let val = parse_expr(tokens, pos, constants, at)?;
                 if *pos < tokens.len() && tokens[*pos] == ")" {
                     *pos += 1;
                     Ok(val)
                 } else {
                     Err(ParseError {
                         line,
+                        column: 0,
                         message: "Missing closing parenthesis".to_string(),
                     })
                 }
             }
             "-" => {
                 // Unary minus
-                let val = parse_factor(tokens, pos, constants, line)?;
+                let val = parse_factor(tokens, pos, constants, at)?;
                 Ok(-val)
             }
+            _ if tokens.get(*pos) == Some(&"(") => {
+                // Function call: name(arg, ...)
+                *pos += 1;
+                let mut args = vec![parse_expr(tokens, pos, constants, at)?];
+                while tokens.get(*pos) == Some(&",") {
+                    *pos += 1;
+                    args.push(parse_expr(tokens, pos, constants, at)?);
+                }
+                if tokens.get(*pos) != Some(&")") {
+                    return Err(ParseError {
+                        line,
+                        column: 0,
+                        message: "Missing closing parenthesis".to_string(),
+                    });
+                }
+                *pos += 1;
+                call_function(token, &args, at)
+            }
             _ => {
                 // Try parsing as a number
-                if let Ok(val) = token.parse::<f64>() {
+                if let Some(val) = parse_number(token) {
                     Ok(val)
                 } else if let Some(&val) = constants.get(token) {
                     // Try parsing as a constant
                     Ok(val)
                 } else {
-                    Err(ParseError {
-                        line,
-                        message: format!("Unknown token in expression: '{}'", token),
-                    })
+                    Err(at.error_at(token, format!("Unknown token in expression: '{}'", token)))
                 }
             }
         }
     }
 
+    // Evaluates one of the built-in functions
+    fn call_function(name: &str, args: &[f64], at: SourceLine) -> Result<f64, ParseError> {
+        let arity = match name {
+            "sqrt" | "abs" => 1,
+            "min" | "max" => 2,
+            "clamp" => 3,
+            _ => {
+                return Err(
+                    at.error_at(name, format!("Unknown function in expression: '{}'", name))
+                );
+            }
+        };
+        if args.len() != arity {
+            return Err(at.error_at(
+                name,
+                format!(
+                    "{} takes {} argument(s) but was given {}",
+                    name,
+                    arity,
+                    args.len()
+                ),
+            ));
+        }
+        match name {
+            "sqrt" if args[0] < 0.0 => Err(at.error_at(
+                name,
+                "Square root of a negative number in constant expression".to_string(),
+            )),
+            "sqrt" => Ok(args[0].sqrt()),
+            "abs" => Ok(args[0].abs()),
+            "min" => Ok(args[0].min(args[1])),
+            "max" => Ok(args[0].max(args[1])),
+            _ if args[1] > args[2] || args[1].is_nan() || args[2].is_nan() => Err(at.error_at(
+                name,
+                "clamp lower bound is above its upper bound".to_string(),
+            )),
+            _ => Ok(args[0].clamp(args[1], args[2])),
+        }
+    }
+
     let mut pos = 0;
-    let result = parse_expr(&tokens, &mut pos, constants, line)?;
+    let result = parse_expr(&tokens, &mut pos, constants, at)?;
 
     if pos < tokens.len() {
-        Err(ParseError {
-            line,
-            message: format!("Unexpected token at end of expression: '{}'", tokens[pos]),
-        })
+        Err(at.error_at(
+            tokens[pos],
+            format!("Unexpected token at end of expression: '{}'", tokens[pos]),
+        ))
     } else {
         Ok(result)
     }
 }
 
+/// Blanks out `/* ... */` block comments, keeping their newlines so line
+/// numbers in later errors still match the source. Line comments are skipped
+/// first, so a `/*` after `;`, `#` or `//` doesn't open a block.
+fn strip_block_comments(source: &str) -> Result<String, ParseError> {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+    let mut block_start = None; // Line of the open block comment
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            stripped.push(c);
+            continue;
+        }
+        if let Some(start) = block_start {
+            if c == '*' && chars.next_if_eq(&'/').is_some() {
+                block_start = None;
+            } else if c == '/' && chars.next_if_eq(&'*').is_some() {
+                return Err(ParseError {
+                    line,
+                    column: 0,
+                    message: format!(
+                        "Nested block comment (the block comment opened on line {} is still open)",
+                        start
+                    ),
+                });
+            }
+            stripped.push(' ');
+            continue;
+        }
+        if c == '/' && chars.next_if_eq(&'*').is_some() {
+            block_start = Some(line);
+            stripped.push_str("  ");
+        } else if c == ';' || c == '#' || (c == '/' && chars.peek() == Some(&'/')) {
+            // Line comment: copy the rest of the line untouched
+            stripped.push(c);
+            while let Some(next) = chars.next_if(|&next| next != '\n') {
+                stripped.push(next);
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    match block_start {
+        Some(start) => Err(ParseError {
+            line: start,
+            column: 0,
+            message: "Unterminated block comment".to_string(),
+        }),
+        None => Ok(stripped),
+    }
+}
+
+/// Splits source into statements tagged with their line number: line
+/// comments (`;`, `#`, `//`) are dropped, then `|` separates several
+/// statements on one line (`push 1 | push 2 | add`). Blank statements are
+/// skipped.
+fn split_statements(source: &str) -> Vec<(usize, &str)> {
+    source
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            let code = line
+                .split(';')
+                .next()
+                .unwrap() // Take part before first ';'
+                .split('#')
+                .next()
+                .unwrap() // Then take part before first '#'
+                .split("//")
+                .next()
+                .unwrap(); // Then take part before first '//'
+            code.split('|')
+                .map(str::trim)
+                .filter(|statement| !statement.is_empty())
+                .map(move |statement| (index + 1, statement))
+        })
+        .collect()
+}
+
 /// Parses a robot assembly program from a string
 pub fn parse_assembly(
     source: &str,
     predefined_constants: Option<&HashMap<String, f64>>,
 ) -> Result<ParsedProgram, ParseError> {
     let mut constants = HashMap::new();
+    let mut aliases = HashMap::new(); // .alias names for registers
     let mut labels = HashMap::new();
+    let mut references = SymbolReferences::default();
+    let source = strip_block_comments(source)?;
 
     // Add predefined constants first
     if let Some(predefined) = predefined_constants {
@@ -198,38 +489,13 @@ pub fn parse_assembly(
         }
     }
 
-    // First pass: collect user constants and labels, count instructions properly
-    let mut line_num = 0;
-    let mut instruction_index = 0;
-    for line in source.lines() {
-        line_num += 1;
-        let original_line = line.trim();
-        // Skip lines that are empty or *start* with a comment character
-        if original_line.is_empty()
-            || original_line.starts_with(';')
-            || original_line.starts_with('#')
-            || original_line.starts_with("//")
-        {
-            continue;
-        }
-
-        // Handle comments potentially anywhere on the line
-        let line_no_comment = original_line
-            .split(';')
-            .next()
-            .unwrap() // Take part before first ';'
-            .split('#')
-            .next()
-            .unwrap() // Then take part before first '#'
-            .split("//")
-            .next()
-            .unwrap() // Then take part before first '//'
-            .trim(); // Trim remaining whitespace
-
-        if line_no_comment.is_empty() {
-            continue;
-        }
+    let statements = split_statements(&source);
+    let source_lines: Vec<&str> = source.lines().collect();
 
+    // First pass: collect user constants, aliases and labels, count instructions properly
+    let mut instruction_index = 0;
+    for &(line_num, line_no_comment) in &statements {
+        let at = SourceLine::new(line_num, source_lines[line_num - 1]);
         if line_no_comment.starts_with(".const") {
             // Parse constant
             let parts: Vec<_> = line_no_comment.split_whitespace().collect();
@@ -239,6 +505,7 @@ pub fn parse_assembly(
                 if predefined_constants.is_some_and(|pre| pre.contains_key(&name)) {
                     return Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: format!("Attempted to redefine built-in constant: {}", name),
                     });
                 }
@@ -247,19 +514,29 @@ pub fn parse_assembly(
                 let expr = line_no_comment.splitn(3, ' ').nth(2).unwrap();
 
                 // Try to evaluate the expression
-                match parse_constant_expression(expr, &constants, line_num) {
+                match parse_constant_expression(expr, &constants, at) {
                     Ok(value) => {
                         if constants.contains_key(&name) {
                             return Err(ParseError {
                                 line: line_num,
+                                column: 0,
                                 message: format!("Duplicate constant definition: {}", name),
                             });
                         }
+                        if aliases.contains_key(&name) {
+                            return Err(ParseError {
+                                line: line_num,
+                                column: 0,
+                                message: format!("Constant {} is already a register alias", name),
+                            });
+                        }
+                        references.constants.insert(name.clone(), Vec::new());
                         constants.insert(name, value);
                     }
                     Err(e) => {
                         return Err(ParseError {
                             line: line_num,
+                            column: e.column,
                             message: format!(
                                 "Invalid expression for constant {}: {}",
                                 name, e.message
@@ -270,12 +547,47 @@ pub fn parse_assembly(
             } else {
                 return Err(ParseError {
                     line: line_num,
+                    column: 0,
                     message: "Invalid .const format. Use: .const NAME EXPRESSION".to_string(),
                 });
             }
             continue; // .const lines don't count as instructions
         }
 
+        if line_no_comment.starts_with(".alias") {
+            let parts: Vec<_> = line_no_comment.split_whitespace().collect();
+            if parts.len() != 3 {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Invalid .alias format. Use: .alias NAME @REGISTER".to_string(),
+                });
+            }
+            let name = parts[1];
+            let register = parse_register(parts.get(2), &aliases, at)?;
+            let collision = if parse_register(Some(&name), &HashMap::new(), at).is_ok() {
+                Some("a register name")
+            } else if constants.contains_key(name) {
+                Some("a constant")
+            } else if aliases.contains_key(name) {
+                Some("an alias")
+            } else {
+                None
+            };
+            if let Some(existing) = collision {
+                return Err(at.error_at(name, format!("Alias {} is already {}", name, existing)));
+            }
+            aliases.insert(name.to_string(), register);
+            continue; // .alias lines don't count as instructions
+        }
+
+        if line_no_comment.starts_with(".data")
+            || line_no_comment.starts_with(".shape")
+            || line_no_comment.starts_with(".scanner")
+        {
+            continue; // .data values, .shape and .scanner are collected in the second pass
+        }
+
         let mut is_instruction_line = true;
         if let Some((label_part, rest_part)) = line_no_comment.split_once(':') {
             let label = label_part.trim();
@@ -284,6 +596,7 @@ pub fn parse_assembly(
                 if labels.contains_key(label) {
                     return Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: format!("Duplicate label: {}", label),
                     });
                 }
@@ -291,6 +604,7 @@ pub fn parse_assembly(
             } else {
                 return Err(ParseError {
                     line: line_num,
+                    column: 0,
                     message: "Label cannot be empty".to_string(),
                 });
             }
@@ -309,39 +623,131 @@ pub fn parse_assembly(
     }
 
     // Second pass: parse instructions using the combined constants map
-    line_num = 0;
     let mut collected_results = Vec::new();
+    let mut data = Vec::new();
+    let mut shape = None;
+    let mut scanner = None;
 
-    for line in source.lines() {
-        line_num += 1;
-        let original_line = line.trim();
-        // Skip lines that are empty or *start* with a comment character
-        if original_line.is_empty()
-            || original_line.starts_with(';')
-            || original_line.starts_with('#')
-            || original_line.starts_with("//")
-        {
-            continue;
+    for &(line_num, line_no_comment) in &statements {
+        let at = SourceLine::new(line_num, source_lines[line_num - 1]);
+        if line_no_comment.starts_with(".const") || line_no_comment.starts_with(".alias") {
+            continue; // Skip const and alias directives
         }
-        // Handle comments potentially anywhere on the line
-        let line_no_comment = original_line
-            .split(';')
-            .next()
-            .unwrap() // Take part before first ';'
-            .split('#')
-            .next()
-            .unwrap() // Then take part before first '#'
-            .split("//")
-            .next()
-            .unwrap() // Then take part before first '//'
-            .trim(); // Trim remaining whitespace
-
-        if line_no_comment.is_empty() {
-            continue;
+
+        if let Some(name) = line_no_comment.strip_prefix(".shape") {
+            let parsed = name.trim().parse().map_err(|message| ParseError {
+                line: line_num,
+                column: 0,
+                message,
+            })?;
+            if shape.replace(parsed).is_some() {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Duplicate .shape directive".to_string(),
+                });
+            }
+            continue; // .shape lines don't count as instructions
         }
 
-        if line_no_comment.starts_with(".const") {
-            continue; // Skip const directives
+        if let Some(settings) = line_no_comment.strip_prefix(".scanner") {
+            if scanner.is_some() {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Duplicate .scanner directive".to_string(),
+                });
+            }
+            let tokens: Vec<&str> = settings.split_whitespace().collect();
+            if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Invalid .scanner format. Use: .scanner range VALUE fov VALUE"
+                        .to_string(),
+                });
+            }
+            let mut parsed = ScannerSettings::default();
+            for pair in tokens.chunks(2) {
+                let (key, value) = (pair[0], pair[1]);
+                let (setting, min, max) = match key.to_lowercase().as_str() {
+                    "range" => (
+                        &mut parsed.range,
+                        config::SCANNER_MIN_RANGE,
+                        config::SCANNER_MAX_RANGE,
+                    ),
+                    "fov" => (
+                        &mut parsed.fov,
+                        config::SCANNER_MIN_FOV,
+                        config::SCANNER_MAX_FOV,
+                    ),
+                    _ => {
+                        return Err(at.error_at(
+                            key,
+                            format!("Unknown .scanner setting {} (expected range or fov)", key),
+                        ));
+                    }
+                };
+                let amount = parse_constant_expression(value, &constants, at)?;
+                if !(min..=max).contains(&amount) {
+                    return Err(at.error_at(
+                        value,
+                        format!(".scanner {} must be between {} and {}", key, min, max),
+                    ));
+                }
+                *setting = amount;
+            }
+            scanner = Some(parsed);
+            continue; // .scanner lines don't count as instructions
+        }
+
+        if let Some(values) = line_no_comment.strip_prefix(".data") {
+            // Values are comma separated (allowing spaced expressions) or whitespace separated.
+            // Commas inside parentheses separate function arguments, not values.
+            let values = values.trim();
+            let items: Vec<&str> = if values.contains(',') {
+                let mut depth = 0;
+                values
+                    .split(|c: char| {
+                        match c {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        c == ',' && depth == 0
+                    })
+                    .map(str::trim)
+                    .collect()
+            } else {
+                values.split_whitespace().collect()
+            };
+            if items.iter().all(|item| item.is_empty()) {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Invalid .data format. Use: .data VALUE [VALUE ...]".to_string(),
+                });
+            }
+            for item in items {
+                let value =
+                    parse_constant_expression(item, &constants, at).map_err(|e| ParseError {
+                        line: line_num,
+                        column: e.column,
+                        message: format!("Invalid .data value {}: {}", item, e.message),
+                    })?;
+                data.push(value);
+            }
+            if data.len() > DEFAULT_MEMORY_SIZE {
+                return Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: format!(
+                        ".data section exceeds memory size of {} values",
+                        DEFAULT_MEMORY_SIZE
+                    ),
+                });
+            }
+            continue; // .data lines don't count as instructions
         }
 
         // Determine the part of the line containing the potential instruction
@@ -357,19 +763,22 @@ pub fn parse_assembly(
         }
 
         // Allow optional commas as argument separators
-        let instruction_part = instruction_part.replace(",", " ");
-        let parts: Vec<_> = instruction_part.split_whitespace().collect();
+        let parts: Vec<_> = instruction_part
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+            .collect();
         // parts cannot be empty here because instruction_part wasn't empty
 
         let parse_result: Result<Instruction, ParseError> = match parts[0].to_lowercase().as_str() {
             "push" => {
                 if parts.len() > 1 {
                     // Pass the final constants map to parse_operand
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Push(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "push requires an operand".to_string(),
                     })
                 }
@@ -378,54 +787,115 @@ pub fn parse_assembly(
                 if parts.len() == 1 {
                     Ok(Instruction::PopDiscard)
                 } else {
-                    let reg = parse_register(parts.get(1), line_num)?;
+                    let reg = parse_register(parts.get(1), &aliases, at)?;
                     Ok(Instruction::Pop(reg))
                 }
             }
             "dup" => Ok(Instruction::Dup),
             "swap" => Ok(Instruction::Swap),
+            "over" => Ok(Instruction::Over),
+            "rot" => Ok(Instruction::Rot),
+            "pick" => {
+                if parts.len() > 1 {
+                    let depth = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Pick(depth))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "pick requires a depth operand".to_string(),
+                    })
+                }
+            }
             "mov" => {
                 if parts.len() > 2 {
-                    let dest_reg = parse_register(parts.get(1), line_num)?;
-                    let src = parse_operand(parts.get(2), &constants, line_num)?;
+                    let dest_reg = parse_register(parts.get(1), &aliases, at)?;
+                    let src = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::Mov(dest_reg, src))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "mov requires register and operand".to_string(),
                     })
                 }
             }
+            "movc" => {
+                if parts.len() > 4 {
+                    let dest_reg = parse_register(parts.get(1), &aliases, at)?;
+                    let src = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    let lo = parse_operand(parts.get(3), &constants, &aliases, at)?;
+                    let hi = parse_operand(parts.get(4), &constants, &aliases, at)?;
+                    Ok(Instruction::MovC(dest_reg, src, lo, hi))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "movc requires register, value, lower and upper bound operands"
+                            .to_string(),
+                    })
+                }
+            }
+            "movidx" => {
+                if parts.len() > 2 {
+                    let index = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let src = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::MovIdx(index, src))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "movidx requires index and value operands".to_string(),
+                    })
+                }
+            }
+            "lodidx" => {
+                if parts.len() > 2 {
+                    let dest_reg = parse_register(parts.get(1), &aliases, at)?;
+                    let index = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::LodIdx(dest_reg, index))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "lodidx requires destination register and index operand"
+                            .to_string(),
+                    })
+                }
+            }
             "lod" => {
                 if parts.len() > 1 {
-                    let dest_reg = parse_register(parts.get(1), line_num)?;
+                    let dest_reg = parse_register(parts.get(1), &aliases, at)?;
                     Ok(Instruction::Lod(dest_reg))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "lod requires destination register".to_string(),
                     })
                 }
             }
             "sto" => {
                 if parts.len() > 1 {
-                    let value = parse_operand(parts.get(1), &constants, line_num)?;
+                    let value = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Sto(value))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "sto requires a value or register operand".to_string(),
                     })
                 }
             }
             "cmp" => {
                 if parts.len() > 2 {
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::Cmp(left, right))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "cmp requires two operands".to_string(),
                     })
                 }
@@ -433,8 +903,8 @@ pub fn parse_assembly(
             "add" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::AddOp(left, right))
                 } else {
                     // Stack form
@@ -444,8 +914,8 @@ pub fn parse_assembly(
             "sub" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::SubOp(left, right))
                 } else {
                     // Stack form
@@ -455,8 +925,8 @@ pub fn parse_assembly(
             "mul" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::MulOp(left, right))
                 } else {
                     // Stack form
@@ -466,8 +936,8 @@ pub fn parse_assembly(
             "div" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::DivOp(left, right))
                 } else {
                     // Stack form
@@ -477,20 +947,70 @@ pub fn parse_assembly(
             "mod" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::ModOp(left, right))
                 } else {
                     // Stack form
                     Ok(Instruction::Mod)
                 }
             }
+            "min" => {
+                if parts.len() > 2 {
+                    // Operand form
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::MinOp(left, right))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Min)
+                }
+            }
+            "max" => {
+                if parts.len() > 2 {
+                    // Operand form
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::MaxOp(left, right))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Max)
+                }
+            }
+            "rand_normal" => {
+                if parts.len() > 2 {
+                    let mean = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let stddev = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::RandNormal(mean, stddev))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "rand_normal requires mean and standard deviation operands"
+                            .to_string(),
+                    })
+                }
+            }
+            "clamp" => {
+                if parts.len() > 3 {
+                    let value = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let lo = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    let hi = parse_operand(parts.get(3), &constants, &aliases, at)?;
+                    Ok(Instruction::ClampOp(value, lo, hi))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "clamp requires value, lower and upper bound operands".to_string(),
+                    })
+                }
+            }
             "divmod" => Ok(Instruction::Divmod),
             "pow" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::PowOp(left, right))
                 } else {
                     // Stack form
@@ -500,7 +1020,7 @@ pub fn parse_assembly(
             "sqrt" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::SqrtOp(op))
                 } else {
                     // Stack form
@@ -510,7 +1030,7 @@ pub fn parse_assembly(
             "log" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::LogOp(op))
                 } else {
                     // Stack form
@@ -520,7 +1040,7 @@ pub fn parse_assembly(
             "sin" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::SinOp(op))
                 } else {
                     // Stack form
@@ -530,7 +1050,7 @@ pub fn parse_assembly(
             "cos" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::CosOp(op))
                 } else {
                     // Stack form
@@ -540,7 +1060,7 @@ pub fn parse_assembly(
             "tan" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::TanOp(op))
                 } else {
                     // Stack form
@@ -550,7 +1070,7 @@ pub fn parse_assembly(
             "asin" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::AsinOp(op))
                 } else {
                     // Stack form
@@ -560,7 +1080,7 @@ pub fn parse_assembly(
             "acos" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::AcosOp(op))
                 } else {
                     // Stack form
@@ -570,7 +1090,7 @@ pub fn parse_assembly(
             "atan" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::AtanOp(op))
                 } else {
                     // Stack form
@@ -580,8 +1100,8 @@ pub fn parse_assembly(
             "atan2" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::Atan2Op(left, right))
                 } else {
                     // Stack form
@@ -591,18 +1111,58 @@ pub fn parse_assembly(
             "abs" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::AbsOp(op))
                 } else {
                     // Stack form
                     Ok(Instruction::Abs)
                 }
             }
+            "neg" => {
+                if parts.len() > 1 {
+                    // Operand form
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::NegOp(op))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Neg)
+                }
+            }
+            "floor" => {
+                if parts.len() > 1 {
+                    // Operand form
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::FloorOp(op))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Floor)
+                }
+            }
+            "ceil" => {
+                if parts.len() > 1 {
+                    // Operand form
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::CeilOp(op))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Ceil)
+                }
+            }
+            "round" => {
+                if parts.len() > 1 {
+                    // Operand form
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::RoundOp(op))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Round)
+                }
+            }
             "and" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::AndOp(left, right))
                 } else {
                     // Stack form
@@ -612,8 +1172,8 @@ pub fn parse_assembly(
             "or" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::OrOp(left, right))
                 } else {
                     // Stack form
@@ -623,8 +1183,8 @@ pub fn parse_assembly(
             "xor" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::XorOp(left, right))
                 } else {
                     // Stack form
@@ -634,7 +1194,7 @@ pub fn parse_assembly(
             "not" => {
                 if parts.len() > 1 {
                     // Operand form
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::NotOp(op))
                 } else {
                     // Stack form
@@ -644,8 +1204,8 @@ pub fn parse_assembly(
             "shl" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::ShlOp(left, right))
                 } else {
                     // Stack form
@@ -655,17 +1215,40 @@ pub fn parse_assembly(
             "shr" => {
                 if parts.len() > 2 {
                     // Operand form
-                    let left = parse_operand(parts.get(1), &constants, line_num)?;
-                    let right = parse_operand(parts.get(2), &constants, line_num)?;
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
                     Ok(Instruction::ShrOp(left, right))
                 } else {
                     // Stack form
                     Ok(Instruction::Shr)
                 }
             }
+            "rol" => {
+                if parts.len() > 2 {
+                    // Operand form
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::RolOp(left, right))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Rol)
+                }
+            }
+            "ror" => {
+                if parts.len() > 2 {
+                    // Operand form
+                    let left = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let right = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::RorOp(left, right))
+                } else {
+                    // Stack form
+                    Ok(Instruction::Ror)
+                }
+            }
             "jmp" | "jz" | "jnz" | "jl" | "jle" | "jg" | "jge" | "je" | "jne" => {
                 let target_label = parts.get(1).ok_or(ParseError {
                     line: line_num,
+                    column: 0,
                     message: "Missing label for jump instruction".to_string(),
                 })?;
                 // Use .get directly on the borrowed str from parts
@@ -674,6 +1257,7 @@ pub fn parse_assembly(
                     .copied()
                     .ok_or_else(|| ParseError {
                         line: line_num,
+                        column: 0,
                         message: format!("Unknown label: {}", target_label),
                     })?;
                 match parts[0].to_lowercase().as_str() {
@@ -690,6 +1274,7 @@ pub fn parse_assembly(
             "call" => {
                 let target_label = parts.get(1).ok_or(ParseError {
                     line: line_num,
+                    column: 0,
                     message: "Missing label for call instruction".to_string(),
                 })?;
                 // Use .get directly on the borrowed str from parts
@@ -698,14 +1283,37 @@ pub fn parse_assembly(
                     .copied()
                     .ok_or_else(|| ParseError {
                         line: line_num,
+                        column: 0,
                         message: format!("Unknown label: {}", target_label),
                     })?;
                 Ok(Instruction::Call(target))
             }
             "ret" => Ok(Instruction::Ret),
+            "jmpr" => {
+                let reg = parse_register(parts.get(1), &aliases, at)?;
+                Ok(Instruction::JmpReg(reg))
+            }
+            "addr" => {
+                // Pseudo-instruction: pushes a label's instruction index, for jmpr
+                let target_label = parts.get(1).ok_or(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "Missing label for addr instruction".to_string(),
+                })?;
+                let target = labels
+                    .get(*target_label)
+                    .copied()
+                    .ok_or_else(|| ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: format!("Unknown label: {}", target_label),
+                    })?;
+                Ok(Instruction::Push(Operand::Value(target as f64)))
+            }
             "loop" => {
                 let target_label = parts.get(1).ok_or(ParseError {
                     line: line_num,
+                    column: 0,
                     message: "Missing label for loop instruction".to_string(),
                 })?;
                 // Use .get directly on the borrowed str from parts
@@ -714,111 +1322,269 @@ pub fn parse_assembly(
                     .copied()
                     .ok_or_else(|| ParseError {
                         line: line_num,
+                        column: 0,
                         message: format!("Unknown label: {}", target_label),
                     })?;
                 Ok(Instruction::Loop(target))
             }
             "select" => {
                 if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Select(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "select requires component id or register".to_string(),
                     })
                 }
             }
             "deselect" => Ok(Instruction::Deselect),
+            "whoami" => Ok(Instruction::Whoami),
             "rotate" => {
                 if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Rotate(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "rotate requires angle operand".to_string(),
                     })
                 }
             }
             "drive" => {
                 if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Drive(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "drive requires velocity operand".to_string(),
                     })
                 }
             }
             "fire" => {
-                if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                if parts.len() > 2 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let bounces = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::FireBounce(op, bounces))
+                } else if parts.len() > 1 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Fire(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "fire requires power operand".to_string(),
                     })
                 }
             }
+            "shield" => match parts.get(1).map(|part| part.to_lowercase()).as_deref() {
+                Some("on") => Ok(Instruction::Shield(Operand::Value(1.0))),
+                Some("off") => Ok(Instruction::Shield(Operand::Value(0.0))),
+                Some(_) => {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Shield(op))
+                }
+                None => Err(ParseError {
+                    line: line_num,
+                    column: 0,
+                    message: "shield requires on, off or an operand".to_string(),
+                }),
+            },
             "scan" => Ok(Instruction::Scan),
+            "sweep" => Ok(Instruction::Sweep),
+            "mortar" => {
+                if parts.len() > 1 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Mortar(op))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "mortar requires power operand".to_string(),
+                    })
+                }
+            }
+            "beam" => {
+                if parts.len() > 1 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Beam(op))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "beam requires power operand".to_string(),
+                    })
+                }
+            }
+            "detonate" => {
+                if parts.len() > 1 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Detonate(op))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "detonate requires power operand".to_string(),
+                    })
+                }
+            }
+            "mine" => {
+                if parts.len() > 1 {
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    Ok(Instruction::Mine(op))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "mine requires power operand".to_string(),
+                    })
+                }
+            }
             "nop" => Ok(Instruction::Nop),
+            "halt" => Ok(Instruction::Halt),
+            "yield" => Ok(Instruction::Yield),
+            "randrange" => {
+                if parts.len() > 2 {
+                    let lo = parse_operand(parts.get(1), &constants, &aliases, at)?;
+                    let hi = parse_operand(parts.get(2), &constants, &aliases, at)?;
+                    Ok(Instruction::RandRange(lo, hi))
+                } else {
+                    Err(ParseError {
+                        line: line_num,
+                        column: 0,
+                        message: "randrange requires lower and upper bound operands".to_string(),
+                    })
+                }
+            }
             "dbg" => {
                 if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Dbg(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "dbg requires an operand".to_string(),
                     })
                 }
             }
             "sleep" => {
                 if parts.len() > 1 {
-                    let op = parse_operand(parts.get(1), &constants, line_num)?;
+                    let op = parse_operand(parts.get(1), &constants, &aliases, at)?;
                     Ok(Instruction::Sleep(op))
                 } else {
                     Err(ParseError {
                         line: line_num,
+                        column: 0,
                         message: "sleep requires a cycle count operand".to_string(),
                     })
                 }
             }
             _ => Err(ParseError {
                 line: line_num,
+                column: 0,
                 message: format!("Unknown instruction: {}", parts[0]),
             }),
         };
+        record_references(
+            &mut references,
+            &parts,
+            collected_results.len(),
+            &labels,
+            &constants,
+            &aliases,
+            at,
+        );
         collected_results.push(parse_result);
     }
 
     // Check for any errors during parsing and collect valid instructions
     let instructions: Vec<Instruction> = collected_results.into_iter().collect::<Result<_, _>>()?;
 
-    Ok(ParsedProgram { instructions })
+    for (label, target) in labels {
+        references.labels.entry(label.clone()).or_default();
+        references.label_targets.insert(label, target);
+    }
+
+    Ok(ParsedProgram {
+        instructions,
+        data,
+        shape: shape.unwrap_or_default(),
+        scanner: scanner.unwrap_or_default(),
+        references,
+    })
+}
+
+// Helper: note the labels and constants named by one instruction's arguments
+fn record_references(
+    references: &mut SymbolReferences,
+    parts: &[&str],
+    index: usize,
+    labels: &HashMap<String, usize>,
+    constants: &HashMap<String, f64>,
+    aliases: &HashMap<String, Register>,
+    at: SourceLine,
+) {
+    let is_branch = matches!(
+        parts[0].to_lowercase().as_str(),
+        "jmp"
+            | "jz"
+            | "jnz"
+            | "jl"
+            | "jle"
+            | "jg"
+            | "jge"
+            | "je"
+            | "jne"
+            | "call"
+            | "loop"
+            | "addr"
+    );
+    for arg in &parts[1..] {
+        if is_branch && labels.contains_key(*arg) {
+            references
+                .labels
+                .entry(arg.to_string())
+                .or_default()
+                .push(index);
+        } else if constants.contains_key(*arg)
+            && parse_number(arg).is_none()
+            && parse_register(Some(arg), aliases, at).is_err()
+        {
+            // Same precedence as parse_operand: numbers and registers win over constants
+            references
+                .constants
+                .entry(arg.to_string())
+                .or_default()
+                .push(index);
+        }
+    }
 }
 
 // Helper: parse an operand (register, value, or constant)
 fn parse_operand(
     part: Option<&&str>,
     constants: &HashMap<String, f64>, // Now receives the combined constants
-    line: usize,
+    aliases: &HashMap<String, Register>,
+    at: SourceLine,
 ) -> Result<Operand, ParseError> {
     let s = part.ok_or(ParseError {
-        line,
+        line: at.number,
+        column: 0,
         message: "Missing operand".to_string(),
     })?;
 
     // Try parsing as number first
-    if let Ok(val) = s.parse::<f64>() {
+    if let Some(val) = parse_number(s) {
         return Ok(Operand::Value(val));
     }
 
     // Try parsing as register
-    if let Ok(reg) = parse_register(Some(s), line) {
+    if let Ok(reg) = parse_register(Some(s), aliases, at) {
         return Ok(Operand::Register(reg));
     }
 
@@ -827,22 +1593,30 @@ fn parse_operand(
         return Ok(Operand::Value(val));
     }
 
-    Err(ParseError {
-        line,
-        message: format!(
+    Err(at.error_at(
+        s,
+        format!(
             "Invalid operand: {} (not a number, register, or known constant)",
             s
         ),
-    })
+    ))
 }
 
-// Helper: parse a register name
-fn parse_register(part: Option<&&str>, line: usize) -> Result<Register, ParseError> {
+// Helper: parse a register name or an .alias for one
+pub(crate) fn parse_register(
+    part: Option<&&str>,
+    aliases: &HashMap<String, Register>,
+    at: SourceLine,
+) -> Result<Register, ParseError> {
     use Register::*;
     let s = part.ok_or(ParseError {
-        line,
+        line: at.number,
+        column: 0,
         message: "Missing register".to_string(),
     })?;
+    if let Some(&register) = aliases.get(*s) {
+        return Ok(register);
+    }
     match s.to_lowercase().as_str() {
         "@d0" => Ok(D0),
         "@d1" => Ok(D1),
@@ -884,10 +1658,20 @@ fn parse_register(part: Option<&&str>, line: usize) -> Result<Register, ParseErr
         "@weaponcooldown" | "@weapon_cooldown" => Ok(WeaponCooldown),
         "@targetdistance" | "@target_distance" => Ok(TargetDistance),
         "@targetdirection" | "@target_direction" => Ok(TargetDirection),
-        _ => Err(ParseError {
-            line,
-            message: format!("Unknown register: {}", s),
-        }),
+        "@alivetime" | "@alive_time" => Ok(AliveTime),
+        "@score" => Ok(Score),
+        "@incombat" | "@in_combat" => Ok(InCombat),
+        "@coin" => Ok(Coin),
+        "@armor" => Ok(Armor),
+        "@shield" => Ok(Shield),
+        "@forwardcleardistance" | "@forward_clear_distance" => Ok(ForwardClearDistance),
+        "@lasttargetx" | "@last_target_x" => Ok(LastTargetX),
+        "@lasttargety" | "@last_target_y" => Ok(LastTargetY),
+        "@closestallydistance" | "@closest_ally_distance" => Ok(ClosestAllyDistance),
+        "@closestallydirection" | "@closest_ally_direction" => Ok(ClosestAllyDirection),
+        "@lastinstructioncost" | "@last_instruction_cost" => Ok(LastInstructionCost),
+        "@targetid" | "@target_id" => Ok(TargetId),
+        _ => Err(at.error_at(s, format!("Unknown register: {}", s))),
     }
 }
 
@@ -1168,6 +1952,32 @@ mod tests {
         assert!(matches!(program.instructions[2], Instruction::Jz(1)));
     }
 
+    #[test]
+    fn test_symbol_references() {
+        let source = r#"
+            .const SPEED 2
+            .const UNUSED 7
+        start:
+            push SPEED
+            jz done
+            drive SPEED
+            jmp done
+        done:
+            call start
+        "#;
+        let program = parse_assembly(source, None).unwrap();
+        let references = &program.references;
+        assert_eq!(references.labels["done"], vec![1, 3]);
+        assert_eq!(references.labels["start"], vec![4]);
+        assert_eq!(references.label_targets["done"], 4);
+        assert_eq!(references.constants["SPEED"], vec![0, 2]);
+        assert!(references.constants["UNUSED"].is_empty());
+
+        let report = references.report_lines();
+        assert!(report.contains(&"  done (-> 4): 1, 3".to_string()));
+        assert!(report.contains(&"  UNUSED: unused".to_string()));
+    }
+
     #[test]
     fn test_user_constants() {
         let source = ".const MY_VAL 10.5\n push MY_VAL";
@@ -1263,6 +2073,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_register_alias() {
+        let source = r#"
+        .alias target_angle @d7
+        .alias hp @health
+            mov target_angle 90.0
+            push hp
+            pop target_angle
+        "#;
+        let program = parse_assembly(source, None).unwrap();
+        assert_eq!(program.instructions.len(), 3);
+        assert!(matches!(
+            program.instructions[0],
+            Instruction::Mov(Register::D7, Operand::Value(v)) if v == 90.0
+        ));
+        assert!(matches!(
+            program.instructions[1],
+            Instruction::Push(Operand::Register(Register::Health))
+        ));
+        assert!(matches!(
+            program.instructions[2],
+            Instruction::Pop(Register::D7)
+        ));
+    }
+
+    #[test]
+    fn test_register_alias_errors() {
+        let err = parse_assembly(".alias speed @d0\n.alias speed @d1", None).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("Alias speed is already an alias"));
+
+        let err = parse_assembly(".alias @d1 @d0", None).unwrap_err();
+        assert!(err.message.contains("already a register name"));
+
+        let err = parse_assembly(".const SPEED 1\n.alias SPEED @d0", None).unwrap_err();
+        assert!(err.message.contains("already a constant"));
+
+        let err = parse_assembly(".alias SPEED @d0\n.const SPEED 1", None).unwrap_err();
+        assert!(err.message.contains("already a register alias"));
+
+        assert!(parse_assembly(".alias speed @nope", None).is_err());
+        assert!(parse_assembly(".alias speed", None).is_err());
+    }
+
     #[test]
     fn test_parse_errors() {
         assert!(parse_assembly("invalid_instruction", None).is_err());
@@ -1276,6 +2130,81 @@ mod tests {
         assert!(parse_assembly("cmp @d1", None).is_err()); // Missing operand
     }
 
+    #[test]
+    fn test_piped_statements() {
+        let source = "start: push 1 | push 2 | add ; sum | not a statement\nnext: jmp start | inner: nop\nbogus | nop";
+        let error = parse_assembly(source, None).unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let program = parse_assembly(&source.replace("bogus", "nop"), None).unwrap();
+        assert!(matches!(
+            program.instructions[0],
+            Instruction::Push(Operand::Value(1.0))
+        ));
+        assert!(matches!(
+            program.instructions[1],
+            Instruction::Push(Operand::Value(2.0))
+        ));
+        assert!(matches!(program.instructions[2], Instruction::Add));
+        assert!(matches!(program.instructions[3], Instruction::Jmp(0)));
+        assert_eq!(program.instructions.len(), 7);
+
+        // Each piped statement takes its own instruction index
+        let targets = &program.references.label_targets;
+        assert_eq!(targets["start"], 0);
+        assert_eq!(targets["next"], 3);
+        assert_eq!(targets["inner"], 4);
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let source = "push 1\n/* disabled:\npush 2\npush 3 */\npush 4 /* inline */ ; trailing /* not a block\nbogus";
+        let error = parse_assembly(source, None).unwrap_err();
+        assert_eq!(error.line, 6); // Line numbers still count the commented lines
+
+        let program = parse_assembly(&source.replace("\nbogus", ""), None).unwrap();
+        assert_eq!(program.instructions.len(), 2);
+        assert!(matches!(
+            program.instructions[1],
+            Instruction::Push(Operand::Value(4.0))
+        ));
+    }
+
+    #[test]
+    fn test_block_comment_errors() {
+        let error = parse_assembly("push 1\n/* never closed\npush 2", None).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.message.contains("Unterminated block comment"));
+
+        let error = parse_assembly("/* outer\n/* inner */ */\npush 1", None).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.message.contains("Nested block comment"));
+    }
+
+    #[test]
+    fn test_error_columns() {
+        let error = parse_assembly("push 1\n    mov @d0, @bogus", None).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 14));
+        assert_eq!(error.location(), "line 2, column 14");
+
+        let error = parse_assembly("loop: pop @nope", None).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+        assert!(error.message.contains("Unknown register"));
+
+        let source = ".const A 1\n.const LONG (A + 2) * (A - TYPO) / 4";
+        let error = parse_assembly(source, None).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 28));
+        assert!(error.message.contains("TYPO"));
+
+        let error = parse_assembly("push 1 | push 2 | push nope", None).unwrap_err();
+        assert_eq!(error.column, 24);
+
+        // Errors about the whole statement have no column
+        let error = parse_assembly("push", None).unwrap_err();
+        assert_eq!(error.column, 0);
+        assert_eq!(error.location(), "line 1");
+    }
+
     #[test]
     fn test_parse_all_instructions() {
         // More comprehensive test touching most instructions
@@ -1470,6 +2399,46 @@ mod tests {
     }
 
     #[test]
+    fn test_parse_neg() {
+        let program = parse_assembly("neg\nneg @d1\nneg -2.5", None).unwrap();
+        assert!(matches!(program.instructions[0], Instruction::Neg));
+        match &program.instructions[1] {
+            Instruction::NegOp(op) => {
+                assert!(matches!(op, &Operand::Register(Register::D1)));
+            }
+            _ => panic!("Expected NegOp instruction"),
+        }
+        match &program.instructions[2] {
+            Instruction::NegOp(op) => {
+                assert!(matches!(op, &Operand::Value(-2.5)));
+            }
+            _ => panic!("Expected NegOp instruction"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rounding_ops() {
+        let program =
+            parse_assembly("floor\nceil\nround\nfloor -1.5\nceil @d2\nround 2.5", None).unwrap();
+        assert!(matches!(program.instructions[0], Instruction::Floor));
+        assert!(matches!(program.instructions[1], Instruction::Ceil));
+        assert!(matches!(program.instructions[2], Instruction::Round));
+        assert!(matches!(
+            program.instructions[3],
+            Instruction::FloorOp(Operand::Value(-1.5))
+        ));
+        assert!(matches!(
+            program.instructions[4],
+            Instruction::CeilOp(Operand::Register(Register::D2))
+        ));
+        assert!(matches!(
+            program.instructions[5],
+            Instruction::RoundOp(Operand::Value(2.5))
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)] // Literal values mirror the parsed source
     fn test_parse_arithmetic_operand_ops() {
         // Test parsing of operand-based arithmetic operations
         let source = r#"
@@ -1627,6 +2596,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_min_max() {
+        let source = r#"
+            min                ; Stack based min
+            max                ; Stack based max
+            min @d0 10.0       ; Operand based min
+            max 0.0, @d1       ; Operand based max
+        "#;
+        let program = parse_assembly(source, None).unwrap();
+        assert_eq!(program.instructions.len(), 4);
+        assert!(matches!(program.instructions[0], Instruction::Min));
+        assert!(matches!(program.instructions[1], Instruction::Max));
+        match &program.instructions[2] {
+            Instruction::MinOp(left, right) => {
+                assert!(matches!(left, &Operand::Register(Register::D0)));
+                assert!(matches!(right, &Operand::Value(10.0)));
+            }
+            _ => panic!("Expected MinOp instruction"),
+        }
+        match &program.instructions[3] {
+            Instruction::MaxOp(left, right) => {
+                assert!(matches!(left, &Operand::Value(0.0)));
+                assert!(matches!(right, &Operand::Register(Register::D1)));
+            }
+            _ => panic!("Expected MaxOp instruction"),
+        }
+    }
+
+    #[test]
+    fn test_parse_clamp() {
+        let program = parse_assembly("clamp @d0 0.0 1.0", None).unwrap();
+        match &program.instructions[0] {
+            Instruction::ClampOp(value, lo, hi) => {
+                assert!(matches!(value, &Operand::Register(Register::D0)));
+                assert!(matches!(lo, &Operand::Value(0.0)));
+                assert!(matches!(hi, &Operand::Value(1.0)));
+            }
+            _ => panic!("Expected ClampOp instruction"),
+        }
+        assert!(parse_assembly("clamp @d0 0.0", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_rand_normal() {
+        let program = parse_assembly("rand_normal 0 @d1", None).unwrap();
+        assert!(matches!(
+            &program.instructions[0],
+            Instruction::RandNormal(Operand::Value(0.0), Operand::Register(Register::D1))
+        ));
+        assert!(parse_assembly("rand_normal 1.0", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_randrange() {
+        let program = parse_assembly("randrange 0.0 360.0\nrandrange @d0 @d1", None).unwrap();
+        assert!(matches!(
+            &program.instructions[0],
+            Instruction::RandRange(Operand::Value(0.0), Operand::Value(360.0))
+        ));
+        assert!(matches!(
+            &program.instructions[1],
+            Instruction::RandRange(
+                Operand::Register(Register::D0),
+                Operand::Register(Register::D1)
+            )
+        ));
+        assert!(parse_assembly("randrange 1.0", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_fire_with_bounces() {
+        let program = parse_assembly("fire 0.5\nfire 0.5 2\nfire @d0, @d1", None).unwrap();
+        assert!(matches!(
+            &program.instructions[0],
+            Instruction::Fire(Operand::Value(0.5))
+        ));
+        assert!(matches!(
+            &program.instructions[1],
+            Instruction::FireBounce(Operand::Value(0.5), Operand::Value(2.0))
+        ));
+        assert!(matches!(
+            &program.instructions[2],
+            Instruction::FireBounce(
+                Operand::Register(Register::D0),
+                Operand::Register(Register::D1)
+            )
+        ));
+    }
+
+    #[test]
+    fn test_parse_shield() {
+        let program =
+            parse_assembly("shield on\nshield OFF\nshield @d0\nmov @d1 @shield", None).unwrap();
+        assert!(matches!(
+            &program.instructions[0],
+            Instruction::Shield(Operand::Value(1.0))
+        ));
+        assert!(matches!(
+            &program.instructions[1],
+            Instruction::Shield(Operand::Value(0.0))
+        ));
+        assert!(matches!(
+            &program.instructions[2],
+            Instruction::Shield(Operand::Register(Register::D0))
+        ));
+        assert!(matches!(
+            &program.instructions[3],
+            Instruction::Mov(Register::D1, Operand::Register(Register::Shield))
+        ));
+        assert!(parse_assembly("shield", None).is_err());
+    }
+
     #[test]
     fn test_parse_bitwise_stack_ops() {
         // Test parsing of stack-based bitwise operations
@@ -1739,6 +2820,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_jmpr_and_addr() {
+        let program =
+            parse_assembly("start:\naddr target\npop @d0\njmpr @d0\ntarget:\nnop", None).unwrap();
+        assert!(matches!(
+            program.instructions[0],
+            Instruction::Push(Operand::Value(3.0))
+        ));
+        assert!(matches!(
+            program.instructions[2],
+            Instruction::JmpReg(Register::D0)
+        ));
+        assert!(parse_assembly("addr nowhere", None).is_err());
+        assert!(parse_assembly("jmpr", None).is_err());
+        assert!(parse_assembly("jmpr 5", None).is_err());
+    }
+
+    #[test]
+    fn test_addr_pushes_label_index() {
+        let source = "addr handlers\naddr idle\nnop\nidle:\nnop\nhandlers:\njmp idle";
+        let program = parse_assembly(source, None).unwrap();
+        let targets = &program.references.label_targets;
+        for (index, label) in ["handlers", "idle"].iter().enumerate() {
+            match program.instructions[index] {
+                Instruction::Push(Operand::Value(address)) => {
+                    assert_eq!(address, targets[*label] as f64)
+                }
+                _ => panic!("Expected addr to assemble to a push"),
+            }
+        }
+        // Taking an address counts as a use in the cross-reference
+        assert_eq!(program.references.labels["handlers"], vec![0]);
+        assert_eq!(program.references.labels["idle"], vec![1, 4]);
+    }
+
+    #[test]
+    fn test_parse_rotate_ops() {
+        let program = parse_assembly("rol\nror\nrol 0x80000000 1\nror @d0 @d1", None).unwrap();
+        assert!(matches!(program.instructions[0], Instruction::Rol));
+        assert!(matches!(program.instructions[1], Instruction::Ror));
+        assert!(matches!(
+            program.instructions[2],
+            Instruction::RolOp(Operand::Value(2147483648.0), Operand::Value(1.0))
+        ));
+        assert!(matches!(
+            program.instructions[3],
+            Instruction::RorOp(
+                Operand::Register(Register::D0),
+                Operand::Register(Register::D1)
+            )
+        ));
+    }
+
     #[test]
     fn test_parse_control_flow_ops() {
         // Test parsing of control flow operations
@@ -1796,6 +2930,7 @@ mod tests {
             deselect       ; Deselect current component
             rotate 45.0    ; Rotate component
             drive 0.5      ; Set drive velocity
+            whoami         ; Push the selected component id
         "#;
 
         let result = parse_assembly(source, None);
@@ -1806,11 +2941,11 @@ mod tests {
         );
         let program = result.unwrap();
 
-        // Check 4 instructions
+        // Check 5 instructions
         assert_eq!(
             program.instructions.len(),
-            4,
-            "Expected 4 component instructions"
+            5,
+            "Expected 5 component instructions"
         );
 
         // Verify each instruction type and its operands
@@ -1836,9 +2971,28 @@ mod tests {
             }
             _ => panic!("Expected Drive instruction"),
         }
+
+        assert!(matches!(program.instructions[4], Instruction::Whoami));
+    }
+
+    #[test]
+    fn test_parse_over_rot_pick() {
+        let program = parse_assembly("over\nrot\npick 2\npick @d0", None).unwrap();
+        assert!(matches!(program.instructions[0], Instruction::Over));
+        assert!(matches!(program.instructions[1], Instruction::Rot));
+        assert!(matches!(
+            program.instructions[2],
+            Instruction::Pick(Operand::Value(2.0))
+        ));
+        assert!(matches!(
+            program.instructions[3],
+            Instruction::Pick(Operand::Register(Register::D0))
+        ));
+        assert!(parse_assembly("pick", None).is_err());
     }
 
     #[test]
+    #[allow(clippy::approx_constant)] // Literal values mirror the parsed source
     fn test_parse_stack_and_register_ops() {
         // Test parsing of stack and register operations
         let source = r#"
@@ -2036,4 +3190,201 @@ mod tests {
             Instruction::SubOp(Operand::Register(Register::D1), Operand::Value(1.0))
         ));
     }
+
+    #[test]
+    fn test_parse_data_directive() {
+        let source = r#"
+        .const SIZE 4
+        .data 1 2 3
+        .data SIZE * 2, (SIZE + 1) / 5
+        start: lod @d0
+        "#;
+        let program = parse_assembly(source, None).expect("Parsing failed");
+        assert_eq!(program.data, vec![1.0, 2.0, 3.0, 8.0, 1.0]);
+        // .data lines don't count as instructions
+        assert_eq!(program.instructions.len(), 1);
+
+        assert!(parse_assembly(".data", None).is_err());
+        assert!(parse_assembly(".data 1 UNKNOWN", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_and_binary_literals() {
+        let source = r#"
+        .const MASK 0xFF00
+        .const LOW 0b1010
+        .const NEXT 0xFF + 1
+        .const NEG -0xFF
+        push 0xff
+        push 0B11
+        push -0x10
+        push MASK
+        "#;
+        let program = parse_assembly(source, None).unwrap();
+        let pushed: Vec<f64> = program
+            .instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Push(Operand::Value(v)) => *v,
+                other => panic!("Expected push of a value, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(pushed, vec![255.0, 3.0, -16.0, 65280.0]);
+
+        let constants = HashMap::from([("LOW".to_string(), 10.0)]);
+        assert_eq!(
+            parse_constant_expression("0xFF + 1", &constants, SourceLine::new(1, "0xFF + 1"))
+                .unwrap(),
+            256.0
+        );
+        assert_eq!(
+            parse_constant_expression("-0xFF", &constants, SourceLine::new(1, "-0xFF")).unwrap(),
+            -255.0
+        );
+        assert_eq!(
+            parse_constant_expression("LOW * 0b10", &constants, SourceLine::new(1, "LOW * 0b10"))
+                .unwrap(),
+            20.0
+        );
+
+        assert!(parse_assembly("push 0xG1", None).is_err());
+        assert!(parse_assembly("push 0b102", None).is_err());
+        assert!(parse_assembly("push 0x", None).is_err());
+        assert!(parse_assembly("push 0x+1", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_scientific_and_separated_literals() {
+        let source = r#"
+        .const G 6.674e-11
+        .const MILLION 1_000_000
+        .const KILO 1.5e3 - 500
+        .const SCALED 2 * 2.5E+2
+        push 1_000
+        push 1.5e3
+        push -2e2
+        push 0xFF_FF
+        push MILLION
+        "#;
+        let program = parse_assembly(source, None).unwrap();
+        let pushed: Vec<f64> = program
+            .instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Push(Operand::Value(v)) => *v,
+                other => panic!("Expected push of a value, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(pushed, vec![1000.0, 1500.0, -200.0, 65535.0, 1_000_000.0]);
+
+        let constants = HashMap::new();
+        let value = |expr| parse_constant_expression(expr, &constants, SourceLine::new(1, expr));
+        assert_eq!(value("6.674e-11").unwrap(), 6.674e-11);
+        assert_eq!(value("1.5e3 - 500").unwrap(), 1000.0);
+        assert_eq!(value("2 * 2.5E+2").unwrap(), 500.0);
+        assert_eq!(value("0x1e-5").unwrap(), 25.0); // Hex digits, then a subtraction
+
+        // Underscores only group digits; they don't turn names into numbers
+        let with_name = HashMap::from([("_1".to_string(), 7.0)]);
+        assert_eq!(
+            parse_constant_expression("_1", &with_name, SourceLine::new(1, "_1")).unwrap(),
+            7.0
+        );
+    }
+
+    #[test]
+    fn test_constant_expression_functions() {
+        let source = r#"
+        .const MY_X 1.4
+        .const SPAWN_X clamp(MY_X, 0, ARENA_WIDTH)
+        .const BIGGER 2 * max(3, 4)
+        .const NESTED min(max(1, 2), sqrt(abs(-16))) + 1
+        .data max(1, 5), min(2, 3) * 2
+        push SPAWN_X
+        push BIGGER
+        push NESTED
+        "#;
+        let predefined = HashMap::from([("ARENA_WIDTH".to_string(), 1.0)]);
+        let program = parse_assembly(source, Some(&predefined)).unwrap();
+        let pushed: Vec<f64> = program
+            .instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Push(Operand::Value(v)) => *v,
+                other => panic!("Expected push of a value, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(pushed, vec![1.0, 8.0, 3.0]);
+        assert_eq!(program.data, vec![5.0, 4.0]);
+
+        let constants = HashMap::new();
+        let value = |expr| parse_constant_expression(expr, &constants, SourceLine::new(1, expr));
+        assert_eq!(value("max(3, 4) * 2 + 1").unwrap(), 9.0);
+        assert_eq!(value("-abs(2 - 5)").unwrap(), -3.0);
+        assert!(value("max(1)").is_err());
+        assert!(value("clamp(1, 5, 0)").is_err());
+        assert!(value("sqrt(-1)").is_err());
+        assert!(value("max(1, 2").is_err());
+        let error = value("1 + floor(2)").unwrap_err();
+        assert!(error.message.contains("Unknown function"));
+        assert_eq!(error.column, 5);
+    }
+
+    #[test]
+    fn test_parse_shape_directive() {
+        let program = parse_assembly(".shape Square\nstart: nop", None).unwrap();
+        assert_eq!(program.shape, RobotShape::Square);
+        // .shape lines don't count as instructions
+        assert_eq!(program.instructions.len(), 1);
+        assert_eq!(
+            parse_assembly("nop", None).unwrap().shape,
+            RobotShape::Triangle
+        );
+
+        assert!(parse_assembly(".shape hexagon", None).is_err());
+        assert!(parse_assembly(".shape circle\n.shape square", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_scanner_directive() {
+        let program =
+            parse_assembly(".const WIDE 45\n.scanner fov WIDE range 0.5\nnop", None).unwrap();
+        assert_eq!(
+            program.scanner,
+            ScannerSettings {
+                range: 0.5,
+                fov: 45.0
+            }
+        );
+        // .scanner lines don't count as instructions
+        assert_eq!(program.instructions.len(), 1);
+        assert_eq!(
+            parse_assembly("nop", None).unwrap().scanner,
+            ScannerSettings::default()
+        );
+
+        // Values outside the engine's bounds are rejected, pointing at the value
+        let error = parse_assembly(".scanner range 0.5 fov 180", None).unwrap_err();
+        assert!(error.message.contains("between"));
+        assert_eq!(error.column, 24);
+        assert!(parse_assembly(".scanner range 0.01", None).is_err());
+        assert!(parse_assembly(".scanner reach 1", None).is_err());
+        assert!(parse_assembly(".scanner range", None).is_err());
+        assert!(parse_assembly(".scanner fov 10\n.scanner fov 20", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_indexed_register_ops() {
+        let program = parse_assembly("movidx 5 42\nlodidx @d0 @d1", None).unwrap();
+        assert!(matches!(
+            program.instructions[0],
+            Instruction::MovIdx(Operand::Value(5.0), Operand::Value(42.0))
+        ));
+        assert!(matches!(
+            program.instructions[1],
+            Instruction::LodIdx(Register::D0, Operand::Register(Register::D1))
+        ));
+        assert!(parse_assembly("movidx 5", None).is_err());
+        assert!(parse_assembly("lodidx @d0", None).is_err());
+    }
 }
@@ -51,6 +51,20 @@ pub enum Register {
     WeaponCooldown,  // Cooldown remaining for weapons
     TargetDistance,  // Last detected target distance
     TargetDirection, // Last detected target angle
+    // Match state registers (read-only)
+    AliveTime,            // Cycles this robot has survived
+    Score,                // Weighted fitness score
+    InCombat,             // 1.0 if recently dealt or took damage
+    Coin,                 // Stable per-robot 0/1 tiebreaker
+    ForwardClearDistance, // Forward distance including other robots
+    LastTargetX,          // Arena x of the last target found by a scan
+    LastTargetY,          // Arena y of the last target found by a scan
+    ClosestAllyDistance,  // Distance to the nearest living teammate
+    ClosestAllyDirection, // Absolute angle to the nearest living teammate
+    LastInstructionCost,  // Cycle cost of the most recently executed instruction
+    TargetId,             // Id of the robot the last scan found (-1 if none)
+    Armor,                // Flat reduction applied to kinetic hits
+    Shield,               // Shield strength left (absorbs hits while the shield is on)
 }
 
 impl Register {
@@ -84,6 +98,15 @@ impl Register {
         )
     }
 
+    /// Returns the general purpose data register `@d<index>` (D0..D18), if in range
+    pub fn data(index: usize) -> Option<Register> {
+        use Register::*;
+        const DATA_REGISTERS: [Register; 19] = [
+            D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14, D15, D16, D17, D18,
+        ];
+        DATA_REGISTERS.get(index).copied()
+    }
+
     /// Returns true if the register is read-only (state register)
     pub fn is_readonly(&self) -> bool {
         !self.is_writable()
@@ -94,12 +117,20 @@ impl Register {
 #[derive(Debug, Clone)]
 pub struct Registers {
     // All registers as f64 (except @c, which is i64 internally)
-    data: [f64; 42], // Increased size from 41 to 42 for Index
+    data: [f64; 53], // One slot per register
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Registers {
     pub fn new() -> Self {
-        Registers { data: [0.0; 42] } // Update size
+        let mut data = [0.0; 53]; // Update size
+        data[Self::idx(Register::TargetId)] = -1.0; // No target scanned yet
+        Registers { data }
     }
 
     /// Get the index for a register in the data array
@@ -146,6 +177,19 @@ impl Registers {
             WeaponCooldown => 37,   // Shifted WeaponCooldown
             TargetDistance => 38,   // Shifted TargetDistance
             TargetDirection => 39,  // Shifted TargetAngle
+            AliveTime => 40,
+            Score => 41,
+            InCombat => 42,
+            Coin => 43,
+            ForwardClearDistance => 44,
+            LastTargetX => 45,
+            LastTargetY => 46,
+            ClosestAllyDistance => 47,
+            ClosestAllyDirection => 48,
+            LastInstructionCost => 49,
+            TargetId => 50,
+            Armor => 51,
+            Shield => 52,
         }
     }
 
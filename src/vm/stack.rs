@@ -1,4 +1,4 @@
-// VM Stack: simple fixed-size f64 stack with push/pop/dup/swap operations
+// VM Stack: simple fixed-size f64 stack with push/pop/dup/swap/over/rot/pick operations
 
 use super::error::StackError;
 use std::collections::VecDeque;
@@ -56,6 +56,40 @@ impl Stack {
         Ok(())
     }
 
+    /// Copies the second value from the top onto the top (a b -- a b a)
+    pub fn over(&mut self) -> Result<(), StackError> {
+        self.pick(1)
+    }
+
+    /// Moves the third value from the top to the top (a b c -- b c a)
+    pub fn rot(&mut self) -> Result<(), StackError> {
+        let len = self.data.len();
+        if len < 3 {
+            return Err(StackError::Underflow);
+        }
+        let value = self.data.remove(len - 3).unwrap();
+        self.data.push_back(value);
+        Ok(())
+    }
+
+    /// Copies the value `depth` places below the top onto the top (0 = top)
+    pub fn pick(&mut self, depth: usize) -> Result<(), StackError> {
+        if depth >= self.data.len() {
+            return Err(StackError::Underflow);
+        }
+        if self.data.len() >= self.max_size {
+            return Err(StackError::Overflow);
+        }
+        let value = self.data[self.data.len() - 1 - depth];
+        self.data.push_back(value);
+        Ok(())
+    }
+
+    /// Returns the value on top of the stack without popping it
+    pub fn top(&self) -> Option<f64> {
+        self.data.back().copied()
+    }
+
     /// Returns a slice representing the current stack data (top is last element)
     pub fn view(&self) -> &[f64] {
         self.data.as_slices().0 // VecDeque can be non-contiguous, just get the main slice for debug
@@ -101,4 +135,23 @@ mod tests {
         assert_eq!(stack.pop().unwrap(), 3.0);
         assert_eq!(stack.pop().unwrap(), 3.0);
     }
+
+    #[test]
+    fn test_stack_over_rot_pick() {
+        let mut stack = Stack::with_size(4);
+        assert!(stack.rot().is_err());
+        stack.push(1.0).unwrap();
+        stack.push(2.0).unwrap();
+        stack.push(3.0).unwrap();
+        assert!(stack.rot().is_ok()); // 2 3 1
+        assert!(stack.over().is_ok()); // 2 3 1 3
+        assert!(stack.pick(0).is_err()); // Full
+        assert_eq!(stack.pop().unwrap(), 3.0);
+        assert!(stack.pick(3).is_err()); // Only three values deep
+        assert!(stack.pick(2).is_ok()); // 2 3 1 2
+        assert_eq!(stack.pop().unwrap(), 2.0);
+        assert_eq!(stack.pop().unwrap(), 1.0);
+        assert_eq!(stack.pop().unwrap(), 3.0);
+        assert_eq!(stack.pop().unwrap(), 2.0);
+    }
 }
@@ -1,6 +1,7 @@
 // VM State: registers, stack, ip, fault status, cycle counter, etc.
 
 use super::error::{RegisterError, VMFault};
+use super::numeric::operand_to_index;
 use super::registers::{Register, Registers};
 use super::stack::Stack;
 use crate::config;
@@ -17,13 +18,21 @@ pub struct VMState {
     pub cycle: u32,                        // Current cycle within turn
     pub instruction_cycles_remaining: u32, // Cycles left for current instruction
     pub memory: Vec<f64>,                  // Memory array for the VM
+    pub strict_bitwise: bool,              // Fault (not saturate) on bitwise operands beyond 2^53
+    pub halted: bool,                      // Program stopped itself with `halt`
+}
+
+// Default memory size - can be adjusted as needed
+pub const DEFAULT_MEMORY_SIZE: usize = 1024;
+
+impl Default for VMState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VMState {
     pub fn new() -> Self {
-        // Default memory size - can be adjusted as needed
-        const DEFAULT_MEMORY_SIZE: usize = 1024;
-
         VMState {
             registers: Registers::new(),
             stack: Stack::with_size(32), // Use explicit size constructor
@@ -34,6 +43,8 @@ impl VMState {
             cycle: 0,
             instruction_cycles_remaining: 0, // Start ready for first instruction
             memory: vec![0.0; DEFAULT_MEMORY_SIZE], // Initialize memory with zeros
+            strict_bitwise: false,
+            halted: false,
         }
     }
 
@@ -54,6 +65,8 @@ impl VMState {
             VMFault::InvalidComponentForOp => 8,
             VMFault::CallStackOverflow => 14,
             VMFault::CallStackUnderflow => 15,
+            VMFault::NumericError => 16,
+            VMFault::InvalidOperand => 17,
         };
         self.registers
             .set_internal(Register::Fault, fault_code as f64)
@@ -89,7 +102,8 @@ impl VMState {
             .registers
             .get(Register::Index)
             .map_err(|_| VMFault::InvalidRegister)?;
-        let index = index as usize;
+        // Negative, fractional or non-finite indices are out of bounds too
+        let index = operand_to_index(index).map_err(|_| VMFault::InvalidRegister)?;
 
         // Check if index is within bounds
         if index < self.memory.len() {
@@ -105,7 +119,8 @@ impl VMState {
             .registers
             .get(Register::Index)
             .map_err(|_| VMFault::InvalidRegister)?;
-        let index = index as usize;
+        // Negative, fractional or non-finite indices are out of bounds too
+        let index = operand_to_index(index).map_err(|_| VMFault::InvalidRegister)?;
 
         // Check if index is within bounds
         if index < self.memory.len() {